@@ -0,0 +1,473 @@
+use crate::models::{Connection, ConnectionGroup};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type BundleResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 当前导出格式版本，导入时拒绝更高版本
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 连接导出包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionBundle {
+    pub version: u32,
+    /// 导出时间（Unix 秒）
+    #[serde(default)]
+    pub exported_at: u64,
+    /// 是否包含明文凭据
+    #[serde(default)]
+    pub secrets_included: bool,
+    pub connections: Vec<Connection>,
+    /// 导出连接所在的分组及其上级分组
+    #[serde(default)]
+    pub groups: Vec<ConnectionGroup>,
+}
+
+/// 导出包格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub format: BundleFormat,
+    /// 去掉密码与私钥口令，默认开启；关闭时需先解锁保险库
+    pub strip_secrets: bool,
+    /// 只导出指定连接，为空时导出全部
+    pub ids: Option<Vec<String>>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: BundleFormat::default(),
+            strip_secrets: true,
+            ids: None,
+        }
+    }
+}
+
+/// 导入方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ImportMode {
+    /// 与已有连接合并
+    #[default]
+    Merge,
+    /// 用导出包替换全部已有连接
+    Replace,
+}
+
+/// 导入选项
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BundleImportOptions {
+    /// 为空时按内容自动识别
+    pub format: Option<BundleFormat>,
+    pub mode: ImportMode,
+    /// 为每个导入的连接生成新 ID，不会与已有连接冲突
+    pub rekey: bool,
+    /// 合并时 ID 冲突的连接覆盖已有连接，否则跳过
+    pub overwrite: bool,
+    /// 只生成报告，不修改已保存的连接
+    pub dry_run: bool,
+}
+
+/// ID 冲突的处理结果
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum ConflictAction {
+    Skipped,
+    Overwritten,
+}
+
+/// 一个 ID 冲突
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleConflict {
+    pub id: String,
+    /// 导出包中的名称
+    pub name: String,
+    /// 已有连接（或导出包中先出现的同 ID 连接）的名称
+    pub existing_name: String,
+    pub action: ConflictAction,
+}
+
+/// 导入报告
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleImportReport {
+    /// 新增或覆盖的连接
+    pub imported: Vec<Connection>,
+    /// Replace 模式下被移除（未被同 ID 连接覆盖）的已有连接数
+    pub removed: usize,
+    pub conflicts: Vec<BundleConflict>,
+    pub dry_run: bool,
+}
+
+/// 合并结果
+pub struct MergeOutcome {
+    /// 导入后的完整连接列表
+    pub connections: Vec<Connection>,
+    /// 导入后的完整分组列表
+    pub groups: Vec<ConnectionGroup>,
+    pub report: BundleImportReport,
+}
+
+/// 序列化导出包，只包含导出连接用到的分组
+pub fn encode(
+    connections: Vec<Connection>,
+    groups: &[ConnectionGroup],
+    format: BundleFormat,
+    secrets_included: bool,
+) -> BundleResult<String> {
+    let bundle = ConnectionBundle {
+        version: BUNDLE_VERSION,
+        exported_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        secrets_included,
+        groups: referenced_groups(&connections, groups),
+        connections,
+    };
+    Ok(match format {
+        BundleFormat::Json => serde_json::to_string_pretty(&bundle)?,
+        BundleFormat::Toml => toml::to_string_pretty(&bundle)?,
+    })
+}
+
+/// 解析导出包；未指定格式时以 `{` 开头视为 JSON，否则视为 TOML
+pub fn decode(text: &str, format: Option<BundleFormat>) -> BundleResult<ConnectionBundle> {
+    let format = format.unwrap_or(if text.trim_start().starts_with('{') {
+        BundleFormat::Json
+    } else {
+        BundleFormat::Toml
+    });
+    let bundle: ConnectionBundle = match format {
+        BundleFormat::Json => serde_json::from_str(text)?,
+        BundleFormat::Toml => toml::from_str(text)?,
+    };
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than the supported version {}",
+            bundle.version, BUNDLE_VERSION
+        ).into());
+    }
+    Ok(bundle)
+}
+
+/// 连接所在的分组及其全部上级分组，保持原有顺序
+fn referenced_groups(connections: &[Connection], groups: &[ConnectionGroup]) -> Vec<ConnectionGroup> {
+    let mut needed = HashSet::new();
+    for connection in connections {
+        let mut current = connection.group_id.as_deref();
+        while let Some(id) = current {
+            if !needed.insert(id) {
+                break;
+            }
+            current = groups.iter().find(|g| g.id == id).and_then(|g| g.parent_id.as_deref());
+        }
+    }
+    groups.iter().filter(|g| needed.contains(g.id.as_str())).cloned().collect()
+}
+
+/// 按导入选项把导出包中的连接与分组合并到已有连接与分组
+/// 分组按 ID 合并，已有分组保持不变；连接指向不存在的分组时移到根目录
+pub fn merge(
+    existing: Vec<Connection>,
+    existing_groups: Vec<ConnectionGroup>,
+    incoming: ConnectionBundle,
+    options: &BundleImportOptions,
+) -> MergeOutcome {
+    let groups = match options.mode {
+        ImportMode::Replace => incoming.groups,
+        ImportMode::Merge => {
+            let mut groups = existing_groups;
+            for group in incoming.groups {
+                if !groups.iter().any(|g| g.id == group.id) {
+                    groups.push(group);
+                }
+            }
+            groups
+        }
+    };
+    let incoming = incoming.connections.into_iter().map(|mut connection| {
+        if connection.group_id.as_ref().is_some_and(|id| !groups.iter().any(|g| &g.id == id)) {
+            connection.group_id = None;
+        }
+        connection
+    });
+
+    let mut conflicts = Vec::new();
+    let mut imported: Vec<Connection> = Vec::new();
+    let mut seen = HashSet::new();
+
+    for mut connection in incoming {
+        if options.rekey {
+            connection.id = uuid::Uuid::new_v4().to_string();
+        }
+        // 导出包内部重复的 ID 只保留第一个
+        if !seen.insert(connection.id.clone()) {
+            let first = imported.iter().find(|c| c.id == connection.id);
+            conflicts.push(BundleConflict {
+                id: connection.id.clone(),
+                name: connection.name.clone(),
+                existing_name: first.map(|c| c.name.clone()).unwrap_or_default(),
+                action: ConflictAction::Skipped,
+            });
+            continue;
+        }
+        imported.push(connection);
+    }
+
+    let (connections, removed) = match options.mode {
+        ImportMode::Replace => {
+            for connection in &imported {
+                if let Some(old) = existing.iter().find(|c| c.id == connection.id) {
+                    conflicts.push(conflict(connection, old, ConflictAction::Overwritten));
+                }
+            }
+            let removed = existing.iter()
+                .filter(|old| !imported.iter().any(|c| c.id == old.id))
+                .count();
+            (imported.clone(), removed)
+        }
+        ImportMode::Merge => {
+            let mut connections = existing;
+            imported.retain(|connection| {
+                let Some(pos) = connections.iter().position(|c| c.id == connection.id) else {
+                    connections.push(connection.clone());
+                    return true;
+                };
+                if options.overwrite {
+                    conflicts.push(conflict(connection, &connections[pos], ConflictAction::Overwritten));
+                    connections[pos] = connection.clone();
+                    true
+                } else {
+                    conflicts.push(conflict(connection, &connections[pos], ConflictAction::Skipped));
+                    false
+                }
+            });
+            (connections, 0)
+        }
+    };
+
+    MergeOutcome {
+        connections,
+        groups,
+        report: BundleImportReport {
+            imported,
+            removed,
+            conflicts,
+            dry_run: options.dry_run,
+        },
+    }
+}
+
+fn conflict(incoming: &Connection, existing: &Connection, action: ConflictAction) -> BundleConflict {
+    BundleConflict {
+        id: incoming.id.clone(),
+        name: incoming.name.clone(),
+        existing_name: existing.name.clone(),
+        action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ConflictAction::{Overwritten, Skipped};
+
+    fn connection(id: &str, name: &str, group_id: Option<&str>) -> Connection {
+        let mut connection = Connection::new_local(name.to_string());
+        connection.id = id.to_string();
+        connection.group_id = group_id.map(str::to_string);
+        connection
+    }
+
+    fn group(id: &str, name: &str) -> ConnectionGroup {
+        ConnectionGroup { id: id.to_string(), name: name.to_string(), parent_id: None }
+    }
+
+    fn existing() -> (Vec<Connection>, Vec<ConnectionGroup>) {
+        (
+            vec![connection("a", "old-a", Some("g1")), connection("b", "old-b", None)],
+            vec![group("g1", "existing-g1")],
+        )
+    }
+
+    /// 包含与已有连接冲突的 ID、导出包内部重复的 ID 与指向不存在分组的连接
+    fn bundle() -> ConnectionBundle {
+        ConnectionBundle {
+            version: BUNDLE_VERSION,
+            exported_at: 0,
+            secrets_included: false,
+            connections: vec![
+                connection("a", "new-a", Some("g2")),
+                connection("c", "new-c", Some("missing")),
+                connection("a", "dup-a", None),
+                connection("d", "new-d", Some("g1")),
+            ],
+            groups: vec![group("g2", "bundle-g2"), group("g1", "bundle-g1")],
+        }
+    }
+
+    fn names(connections: &[Connection]) -> Vec<&str> {
+        connections.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    struct Case {
+        mode: ImportMode,
+        rekey: bool,
+        overwrite: bool,
+        imported: &'static [&'static str],
+        connections: &'static [&'static str],
+        /// (导出包中的名称, 已有名称, 处理结果)
+        conflicts: &'static [(&'static str, &'static str, ConflictAction)],
+        removed: usize,
+        groups: &'static [&'static str],
+    }
+
+    const CASES: &[Case] = &[
+        Case {
+            mode: ImportMode::Merge, rekey: false, overwrite: false,
+            imported: &["new-c", "new-d"],
+            connections: &["old-a", "old-b", "new-c", "new-d"],
+            conflicts: &[("dup-a", "new-a", Skipped), ("new-a", "old-a", Skipped)],
+            removed: 0,
+            groups: &["existing-g1", "bundle-g2"],
+        },
+        Case {
+            mode: ImportMode::Merge, rekey: false, overwrite: true,
+            imported: &["new-a", "new-c", "new-d"],
+            connections: &["new-a", "old-b", "new-c", "new-d"],
+            conflicts: &[("dup-a", "new-a", Skipped), ("new-a", "old-a", Overwritten)],
+            removed: 0,
+            groups: &["existing-g1", "bundle-g2"],
+        },
+        Case {
+            mode: ImportMode::Merge, rekey: true, overwrite: false,
+            imported: &["new-a", "new-c", "dup-a", "new-d"],
+            connections: &["old-a", "old-b", "new-a", "new-c", "dup-a", "new-d"],
+            conflicts: &[],
+            removed: 0,
+            groups: &["existing-g1", "bundle-g2"],
+        },
+        Case {
+            mode: ImportMode::Merge, rekey: true, overwrite: true,
+            imported: &["new-a", "new-c", "dup-a", "new-d"],
+            connections: &["old-a", "old-b", "new-a", "new-c", "dup-a", "new-d"],
+            conflicts: &[],
+            removed: 0,
+            groups: &["existing-g1", "bundle-g2"],
+        },
+        Case {
+            mode: ImportMode::Replace, rekey: false, overwrite: false,
+            imported: &["new-a", "new-c", "new-d"],
+            connections: &["new-a", "new-c", "new-d"],
+            conflicts: &[("dup-a", "new-a", Skipped), ("new-a", "old-a", Overwritten)],
+            removed: 1,
+            groups: &["bundle-g2", "bundle-g1"],
+        },
+        Case {
+            mode: ImportMode::Replace, rekey: false, overwrite: true,
+            imported: &["new-a", "new-c", "new-d"],
+            connections: &["new-a", "new-c", "new-d"],
+            conflicts: &[("dup-a", "new-a", Skipped), ("new-a", "old-a", Overwritten)],
+            removed: 1,
+            groups: &["bundle-g2", "bundle-g1"],
+        },
+        Case {
+            mode: ImportMode::Replace, rekey: true, overwrite: false,
+            imported: &["new-a", "new-c", "dup-a", "new-d"],
+            connections: &["new-a", "new-c", "dup-a", "new-d"],
+            conflicts: &[],
+            removed: 2,
+            groups: &["bundle-g2", "bundle-g1"],
+        },
+        Case {
+            mode: ImportMode::Replace, rekey: true, overwrite: true,
+            imported: &["new-a", "new-c", "dup-a", "new-d"],
+            connections: &["new-a", "new-c", "dup-a", "new-d"],
+            conflicts: &[],
+            removed: 2,
+            groups: &["bundle-g2", "bundle-g1"],
+        },
+    ];
+
+    #[test]
+    fn merge_matrix() {
+        for case in CASES {
+            let label = format!("{:?} rekey={} overwrite={}", case.mode, case.rekey, case.overwrite);
+            let options = BundleImportOptions {
+                mode: case.mode,
+                rekey: case.rekey,
+                overwrite: case.overwrite,
+                ..Default::default()
+            };
+            let (connections, groups) = existing();
+            let outcome = merge(connections, groups, bundle(), &options);
+
+            assert_eq!(names(&outcome.report.imported), case.imported, "{}", label);
+            assert_eq!(names(&outcome.connections), case.connections, "{}", label);
+            assert_eq!(outcome.report.removed, case.removed, "{}", label);
+            let groups: Vec<&str> = outcome.groups.iter().map(|g| g.name.as_str()).collect();
+            assert_eq!(groups, case.groups, "{}", label);
+
+            let conflicts: Vec<(&str, &str, ConflictAction)> = outcome.report.conflicts.iter()
+                .map(|c| (c.name.as_str(), c.existing_name.as_str(), c.action))
+                .collect();
+            assert_eq!(conflicts, case.conflicts, "{}", label);
+            for conflict in &outcome.report.conflicts {
+                assert_eq!(conflict.id, "a", "{}", label);
+            }
+
+            // 导入后的连接 ID 唯一，重新生成的 ID 不与原 ID 相同
+            let ids: HashSet<&str> = outcome.connections.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(ids.len(), outcome.connections.len(), "{}", label);
+            if case.rekey {
+                assert!(outcome.report.imported.iter().all(|c| !["a", "c", "d"].contains(&c.id.as_str())), "{}", label);
+            }
+
+            // 指向不存在分组的连接移到根目录，其余保持不变
+            for connection in &outcome.connections {
+                if let Some(group_id) = &connection.group_id {
+                    assert!(outcome.groups.iter().any(|g| &g.id == group_id), "{}: dangling {}", label, group_id);
+                }
+            }
+            let group_of = |name: &str| outcome.connections.iter()
+                .find(|c| c.name == name)
+                .map(|c| c.group_id.as_deref());
+            assert_eq!(group_of("new-c"), Some(None), "{}", label);
+            assert_eq!(group_of("new-d"), Some(Some("g1")), "{}", label);
+            if case.imported.contains(&"new-a") {
+                assert_eq!(group_of("new-a"), Some(Some("g2")), "{}", label);
+            }
+        }
+    }
+
+    #[test]
+    fn dry_run_is_reported() {
+        let (connections, groups) = existing();
+        let options = BundleImportOptions { dry_run: true, ..Default::default() };
+        assert!(merge(connections, groups, bundle(), &options).report.dry_run);
+    }
+
+    #[test]
+    fn encode_decode_round_trip_keeps_referenced_groups() {
+        let groups = vec![
+            ConnectionGroup { id: "root".to_string(), name: "root".to_string(), parent_id: None },
+            ConnectionGroup { id: "child".to_string(), name: "child".to_string(), parent_id: Some("root".to_string()) },
+            group("unused", "unused"),
+        ];
+        let connections = vec![connection("x", "x", Some("child"))];
+        for format in [BundleFormat::Json, BundleFormat::Toml] {
+            let text = encode(connections.clone(), &groups, format, false).unwrap();
+            let bundle = decode(&text, None).unwrap();
+            assert_eq!(names(&bundle.connections), vec!["x"]);
+            let ids: Vec<&str> = bundle.groups.iter().map(|g| g.id.as_str()).collect();
+            assert_eq!(ids, vec!["root", "child"]);
+        }
+        assert!(decode(r#"{"version": 2, "connections": []}"#, None).is_err());
+    }
+}
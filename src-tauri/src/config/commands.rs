@@ -1,5 +1,8 @@
-use crate::models::Connection;
+use crate::models::{Connection, ConnectionGroup};
+use crate::config::search::{self, ConnectionMatch};
+use crate::config::bundle::{self, BundleImportOptions, BundleImportReport, ExportOptions, MergeOutcome};
 use crate::config::storage::ConnectionStorage;
+use crate::vault::{self, VaultState};
 use tauri::State;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -8,11 +11,17 @@ pub type ConnectionStorageState = Arc<Mutex<ConnectionStorage>>;
 
 #[tauri::command]
 pub async fn save_connection(
-    connection: Connection,
+    mut connection: Connection,
     storage: State<'_, ConnectionStorageState>,
+    vault: State<'_, VaultState>,
 ) -> Result<(), String> {
     debug_log!("[CONFIG] Saving connection: {} ({})", connection.name, connection.id);
-    
+
+    // 明文凭据移入保险库，配置文件中只保存引用
+    vault.lock().await
+        .seal_connection(&mut connection)
+        .map_err(|e| format!("Failed to save connection: {}", e))?;
+
     let storage = storage.lock().await;
     storage
         .add_connection(connection)
@@ -35,9 +44,14 @@ pub async fn load_connections(
 pub async fn delete_connection(
     id: String,
     storage: State<'_, ConnectionStorageState>,
+    vault: State<'_, VaultState>,
 ) -> Result<(), String> {
     debug_log!("[CONFIG] Deleting connection: {}", id);
-    
+
+    vault.lock().await
+        .remove_connection(&id)
+        .map_err(|e| format!("Failed to delete connection: {}", e))?;
+
     let storage = storage.lock().await;
     storage
         .remove_connection(&id)
@@ -46,13 +60,197 @@ pub async fn delete_connection(
 
 #[tauri::command]
 pub async fn update_connection(
-    connection: Connection,
+    mut connection: Connection,
     storage: State<'_, ConnectionStorageState>,
+    vault: State<'_, VaultState>,
 ) -> Result<(), String> {
     debug_log!("[CONFIG] Updating connection: {} ({})", connection.name, connection.id);
-    
+
+    vault.lock().await
+        .seal_connection(&mut connection)
+        .map_err(|e| format!("Failed to update connection: {}", e))?;
+
     let storage = storage.lock().await;
     storage
         .update_connection(connection)
         .map_err(|e| format!("Failed to update connection: {}", e))
 }
+
+/// 导出连接到 JSON / TOML 文件，返回导出的连接数
+/// 默认去掉凭据；保留凭据时从保险库解密为明文写入（需先解锁）
+#[tauri::command]
+pub async fn export_connections(
+    path: String,
+    options: Option<ExportOptions>,
+    storage: State<'_, ConnectionStorageState>,
+    vault: State<'_, VaultState>,
+) -> Result<usize, String> {
+    let options = options.unwrap_or_default();
+    debug_log!("[CONFIG] Exporting connections to {} ({:?}, strip_secrets={})", path, options.format, options.strip_secrets);
+
+    let vault = vault.lock().await;
+    let storage = storage.lock().await;
+    let mut connections = storage
+        .load_connections()
+        .map_err(|e| format!("Failed to load connections: {}", e))?;
+    let groups = storage
+        .load_groups()
+        .map_err(|e| format!("Failed to load groups: {}", e))?;
+    drop(storage);
+    if let Some(ids) = &options.ids {
+        connections.retain(|c| ids.contains(&c.id));
+    }
+
+    for connection in connections.iter_mut() {
+        if options.strip_secrets {
+            vault::strip_secrets(connection);
+        } else {
+            vault.resolve_connection(connection)
+                .map_err(|e| format!("Failed to export credentials of {}: {}", connection.name, e))?;
+            vault::escape_secrets(connection);
+        }
+    }
+
+    let count = connections.len();
+    let content = bundle::encode(connections, &groups, options.format, !options.strip_secrets)
+        .map_err(|e| format!("Failed to encode bundle: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(count)
+}
+
+/// 从导出包导入连接，返回每个冲突 ID 的处理结果
+/// 导出包中的明文凭据会移入保险库；`dry_run` 时只返回报告
+#[tauri::command]
+pub async fn import_connections(
+    path: String,
+    options: Option<BundleImportOptions>,
+    storage: State<'_, ConnectionStorageState>,
+    vault: State<'_, VaultState>,
+) -> Result<BundleImportReport, String> {
+    let options = options.unwrap_or_default();
+    debug_log!("[CONFIG] Importing bundle {} ({:?}, rekey={}, dry_run={})", path, options.mode, options.rekey, options.dry_run);
+
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut bundle = bundle::decode(&content, options.format)
+        .map_err(|e| format!("Invalid connection bundle: {}", e))?;
+    for connection in bundle.connections.iter_mut() {
+        vault::strip_secret_refs(connection);
+    }
+
+    let vault = vault.lock().await;
+    let storage = storage.lock().await;
+    let existing = storage
+        .load_connections()
+        .map_err(|e| format!("Failed to load connections: {}", e))?;
+    let existing_groups = storage
+        .load_groups()
+        .map_err(|e| format!("Failed to load groups: {}", e))?;
+    let existing_ids: Vec<String> = existing.iter().map(|c| c.id.clone()).collect();
+
+    let MergeOutcome { mut connections, groups, mut report } = bundle::merge(existing, existing_groups, bundle, &options);
+    if options.dry_run {
+        return Ok(report);
+    }
+
+    // 明文凭据移入保险库；报告中返回带引用的连接
+    let imported_ids: Vec<String> = report.imported.iter().map(|c| c.id.clone()).collect();
+    for connection in connections.iter_mut().filter(|c| imported_ids.contains(&c.id)) {
+        vault.seal_connection(connection)
+            .map_err(|e| format!("Failed to store credentials of {}: {}", connection.name, e))?;
+    }
+    report.imported = connections.iter().filter(|c| imported_ids.contains(&c.id)).cloned().collect();
+
+    // Replace 模式下清理被移除连接的凭据
+    for id in existing_ids.iter().filter(|id| !connections.iter().any(|c| &c.id == *id)) {
+        vault.remove_connection(id)
+            .map_err(|e| format!("Failed to remove credentials: {}", e))?;
+    }
+
+    storage
+        .save_groups(&groups)
+        .map_err(|e| format!("Failed to save groups: {}", e))?;
+    storage
+        .save_connections(&connections)
+        .map_err(|e| format!("Failed to save connections: {}", e))?;
+    debug_log!("[CONFIG] Imported {} connections, {} conflicts", report.imported.len(), report.conflicts.len());
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn load_groups(
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<Vec<ConnectionGroup>, String> {
+    let storage = storage.lock().await;
+    storage
+        .load_groups()
+        .map_err(|e| format!("Failed to load groups: {}", e))
+}
+
+/// 新增或更新分组（重命名、移动到其他上级分组）
+#[tauri::command]
+pub async fn save_group(
+    group: ConnectionGroup,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<(), String> {
+    debug_log!("[CONFIG] Saving group: {} ({})", group.name, group.id);
+
+    let storage = storage.lock().await;
+    storage
+        .save_group(group)
+        .map_err(|e| format!("Failed to save group: {}", e))
+}
+
+/// 删除分组，其中的连接与下级分组移到上一级
+#[tauri::command]
+pub async fn delete_group(
+    id: String,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<(), String> {
+    debug_log!("[CONFIG] Deleting group: {}", id);
+
+    let storage = storage.lock().await;
+    storage
+        .delete_group(&id)
+        .map_err(|e| format!("Failed to delete group: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_connection_favorite(
+    id: String,
+    favorite: bool,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<Connection, String> {
+    let storage = storage.lock().await;
+    storage
+        .set_favorite(&id, favorite)
+        .map_err(|e| format!("Failed to update favorite: {}", e))
+}
+
+/// 记录连接的最近使用时间，用于搜索结果排序
+#[tauri::command]
+pub async fn mark_connection_used(
+    id: String,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<Connection, String> {
+    let storage = storage.lock().await;
+    storage
+        .mark_used(&id)
+        .map_err(|e| format!("Failed to update connection: {}", e))
+}
+
+/// 模糊搜索已保存的连接，空查询按收藏与最近使用排序返回全部连接
+#[tauri::command]
+pub async fn search_connections(
+    query: String,
+    limit: Option<usize>,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<Vec<ConnectionMatch>, String> {
+    let storage = storage.lock().await;
+    let connections = storage
+        .load_connections()
+        .map_err(|e| format!("Failed to load connections: {}", e))?;
+    let groups = storage
+        .load_groups()
+        .map_err(|e| format!("Failed to load groups: {}", e))?;
+    Ok(search::search(connections, &groups, &query, limit))
+}
@@ -1,5 +1,9 @@
 mod storage;
+mod bundle;
+mod search;
 pub mod commands;
 
 pub use storage::ConnectionStorage;
+pub use bundle::{BundleImportOptions, BundleImportReport, ExportOptions};
+pub use search::ConnectionMatch;
 pub use commands::*;
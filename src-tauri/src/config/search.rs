@@ -0,0 +1,273 @@
+use crate::models::{Connection, ConnectionGroup};
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// 各字段匹配得分的权重
+const NAME_WEIGHT: u32 = 3;
+const HOST_WEIGHT: u32 = 2;
+const USER_WEIGHT: u32 = 1;
+const TAG_WEIGHT: u32 = 2;
+const GROUP_WEIGHT: u32 = 1;
+
+/// 一条搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionMatch {
+    pub connection: Connection,
+    /// 从根到所属分组的名称，如 `["生产", "数据库"]`
+    pub group_path: Vec<String>,
+    pub score: u32,
+}
+
+/// 按名称、主机、用户名、标签与分组路径模糊搜索连接
+/// 查询按空白拆分为多个词，每个词都必须命中某个字段；空查询返回全部连接。
+/// 结果按得分排序，同分时收藏优先，再按最近使用时间与名称排序
+pub fn search(
+    connections: Vec<Connection>,
+    groups: &[ConnectionGroup],
+    query: &str,
+    limit: Option<usize>,
+) -> Vec<ConnectionMatch> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut matches: Vec<ConnectionMatch> = connections.into_iter()
+        .filter_map(|connection| {
+            let group_path = group_path(groups, connection.group_id.as_deref());
+            let mut score = 0;
+            for term in &terms {
+                score += score_connection(&connection, &group_path, term)?;
+            }
+            Some(ConnectionMatch { connection, group_path, score })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score.cmp(&a.score)
+            .then_with(|| b.connection.favorited_at.is_some().cmp(&a.connection.favorited_at.is_some()))
+            .then_with(|| b.connection.last_used_at.cmp(&a.connection.last_used_at))
+            .then_with(|| compare_names(&a.connection.name, &b.connection.name))
+    });
+    if let Some(limit) = limit {
+        matches.truncate(limit);
+    }
+    matches
+}
+
+/// 从根到指定分组的名称；分组不存在或存在环时在该处截断
+pub fn group_path(groups: &[ConnectionGroup], group_id: Option<&str>) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut visited = Vec::new();
+    let mut current = group_id;
+    while let Some(id) = current {
+        let Some(group) = groups.iter().find(|g| g.id == id) else {
+            break;
+        };
+        if visited.contains(&id) {
+            break;
+        }
+        visited.push(id);
+        path.push(group.name.clone());
+        current = group.parent_id.as_deref();
+    }
+    path.reverse();
+    path
+}
+
+/// 单个查询词在各字段中的最高加权得分，均未命中时返回 None
+fn score_connection(connection: &Connection, group_path: &[String], term: &str) -> Option<u32> {
+    let (host, user) = endpoint(connection);
+    let fields = [(connection.name.as_str(), NAME_WEIGHT), (host, HOST_WEIGHT), (user, USER_WEIGHT)]
+        .into_iter()
+        .chain(connection.tags.iter().map(|t| (t.as_str(), TAG_WEIGHT)))
+        .chain(group_path.iter().map(|g| (g.as_str(), GROUP_WEIGHT)));
+
+    fields
+        .filter_map(|(text, weight)| fuzzy_score(text, term).map(|s| s * weight))
+        .max()
+}
+
+/// 连接的主机与用户名，本地终端与串口以设备名作为主机
+fn endpoint(connection: &Connection) -> (&str, &str) {
+    if let Some(ssh) = &connection.ssh_config {
+        (&ssh.host, &ssh.username)
+    } else if let Some(ftp) = &connection.ftp_config {
+        (&ftp.host, &ftp.username)
+    } else if let Some(telnet) = &connection.telnet_config {
+        (&telnet.host, "")
+    } else if let Some(serial) = &connection.serial_config {
+        (&serial.device, "")
+    } else {
+        ("", "")
+    }
+}
+
+/// 模糊匹配得分：完全相同 > 前缀 > 子串 > 按顺序出现的子序列，未命中时返回 None
+/// `term` 需已转为小写
+fn fuzzy_score(text: &str, term: &str) -> Option<u32> {
+    if text.is_empty() || term.is_empty() {
+        return None;
+    }
+    let text = text.to_lowercase();
+    if text == term {
+        return Some(100);
+    }
+    if text.starts_with(term) {
+        return Some(80);
+    }
+    if let Some(pos) = text.find(term) {
+        // 在单词开头命中（如 `db` 命中 `prod-db-01`）比在单词中间命中更相关
+        let boundary = text[..pos].chars().next_back().is_none_or(|c| !c.is_alphanumeric());
+        return Some(if boundary { 70 } else { 60 });
+    }
+
+    // 子序列：连续命中与单词开头命中加分，总分不超过子串匹配
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    let mut term_chars = term.chars().peekable();
+    for c in text.chars() {
+        let Some(&wanted) = term_chars.peek() else {
+            break;
+        };
+        if c == wanted {
+            term_chars.next();
+            score += 1;
+            if consecutive {
+                score += 2;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            consecutive = true;
+        } else {
+            consecutive = false;
+        }
+        previous = Some(c);
+    }
+    if term_chars.peek().is_some() {
+        return None;
+    }
+    let max = term.chars().count() as u32 * 6;
+    Some(10 + score * 40 / max)
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SshAuth, SshConfig};
+
+    fn ssh(name: &str, host: &str, user: &str) -> Connection {
+        let config = SshConfig::new(host.to_string(), 22, user.to_string(), SshAuth::Agent { socket_path: None });
+        let mut connection = Connection::new_ssh(name.to_string(), config);
+        connection.id = name.to_string();
+        connection
+    }
+
+    fn group(id: &str, name: &str, parent_id: Option<&str>) -> ConnectionGroup {
+        ConnectionGroup { id: id.to_string(), name: name.to_string(), parent_id: parent_id.map(str::to_string) }
+    }
+
+    fn names(matches: &[ConnectionMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.connection.name.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_score_tiers() {
+        assert_eq!(fuzzy_score("web", "web"), Some(100));
+        assert_eq!(fuzzy_score("Web-01", "web"), Some(80));
+        assert_eq!(fuzzy_score("prod-db-01", "db"), Some(70));
+        assert_eq!(fuzzy_score("mongodb", "db"), Some(60));
+        let subsequence = fuzzy_score("prod-web-01", "pw1").unwrap();
+        assert!((10..60).contains(&subsequence));
+        assert!(fuzzy_score("prod-web-01", "pwx").is_none());
+        assert!(fuzzy_score("", "a").is_none());
+    }
+
+    #[test]
+    fn results_are_ordered_by_score() {
+        let connections = vec![
+            ssh("mongodb", "10.0.0.3", "root"),
+            ssh("db", "10.0.0.1", "root"),
+            ssh("prod-db-01", "10.0.0.2", "root"),
+            ssh("dashboard", "10.0.0.4", "root"),
+            ssh("web", "db.example", "root"),
+            ssh("cache", "10.0.0.5", "root"),
+        ];
+        let matches = search(connections, &[], "db", None);
+        // 名称完全相同 > 主机前缀（80×2）> 名称单词开头 > 名称子串 > 子序列
+        assert_eq!(names(&matches), vec!["db", "prod-db-01", "mongodb", "web", "dashboard"]);
+        assert!(matches.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn ties_prefer_favorites_then_recent_then_name() {
+        let mut favorite = ssh("Zulu", "z.example", "root");
+        favorite.favorited_at = Some(1);
+        let mut recent = ssh("yankee", "y.example", "root");
+        recent.last_used_at = Some(200);
+        let mut older = ssh("xray", "x.example", "root");
+        older.last_used_at = Some(100);
+        let connections = vec![
+            ssh("bravo", "b.example", "root"),
+            older,
+            ssh("Alpha", "a.example", "root"),
+            recent,
+            favorite,
+        ];
+
+        let matches = search(connections.clone(), &[], "", None);
+        assert_eq!(names(&matches), vec!["Zulu", "yankee", "xray", "Alpha", "bravo"]);
+        assert!(matches.iter().all(|m| m.score == 0));
+
+        let limited = search(connections, &[], "  ", Some(2));
+        assert_eq!(names(&limited), vec!["Zulu", "yankee"]);
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        let mut tagged = ssh("api", "10.0.0.1", "deploy");
+        tagged.tags = vec!["prod".to_string()];
+        tagged.group_id = Some("eu".to_string());
+        let connections = vec![tagged, ssh("prod-api", "10.0.0.2", "root"), ssh("staging-api", "10.0.0.3", "deploy")];
+        let groups = vec![group("eu", "Europe", None)];
+
+        // api：名称完全相同 + 标签完全相同；prod-api：名称前缀 + 名称单词开头
+        assert_eq!(names(&search(connections.clone(), &groups, "prod api", None)), vec!["api", "prod-api"]);
+        assert_eq!(names(&search(connections.clone(), &groups, "API Deploy", None)), vec!["api", "staging-api"]);
+        assert_eq!(names(&search(connections.clone(), &groups, "europe deploy", None)), vec!["api"]);
+        assert!(search(connections, &groups, "prod missing", None).is_empty());
+    }
+
+    #[test]
+    fn group_path_follows_parents() {
+        let groups = vec![
+            group("db", "Databases", Some("prod")),
+            group("prod", "Production", None),
+            group("orphan", "Orphan", Some("gone")),
+        ];
+        assert_eq!(group_path(&groups, Some("db")), vec!["Production", "Databases"]);
+        assert_eq!(group_path(&groups, Some("orphan")), vec!["Orphan"]);
+        assert!(group_path(&groups, Some("gone")).is_empty());
+        assert!(group_path(&groups, None).is_empty());
+    }
+
+    #[test]
+    fn group_path_stops_on_cycles() {
+        let groups = vec![
+            group("a", "A", Some("b")),
+            group("b", "B", Some("a")),
+            group("self", "Self", Some("self")),
+        ];
+        assert_eq!(group_path(&groups, Some("a")), vec!["B", "A"]);
+        assert_eq!(group_path(&groups, Some("b")), vec!["A", "B"]);
+        assert_eq!(group_path(&groups, Some("self")), vec!["Self"]);
+
+        let mut looped = ssh("looped", "10.0.0.1", "root");
+        looped.group_id = Some("a".to_string());
+        let matches = search(vec![looped], &groups, "a", None);
+        assert_eq!(matches[0].group_path, vec!["B", "A"]);
+    }
+}
@@ -1,11 +1,15 @@
-use crate::models::Connection;
+use crate::models::{Connection, ConnectionGroup};
 use std::fs;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct ConnectionsConfig {
+    #[serde(default)]
     connections: Vec<Connection>,
+    #[serde(default)]
+    groups: Vec<ConnectionGroup>,
 }
 
 pub struct ConnectionStorage {
@@ -26,26 +30,31 @@ impl ConnectionStorage {
         Ok(Self { config_path })
     }
 
-    pub fn load_connections(&self) -> Result<Vec<Connection>, Box<dyn std::error::Error>> {
+    fn load_config(&self) -> Result<ConnectionsConfig, Box<dyn std::error::Error>> {
         if !self.config_path.exists() {
-            return Ok(Vec::new());
+            return Ok(ConnectionsConfig::default());
         }
 
         let content = fs::read_to_string(&self.config_path)?;
-        let config: ConnectionsConfig = toml::from_str(&content)?;
-        Ok(config.connections)
+        Ok(toml::from_str(&content)?)
     }
 
-    pub fn save_connections(&self, connections: &[Connection]) -> Result<(), Box<dyn std::error::Error>> {
-        let config = ConnectionsConfig {
-            connections: connections.to_vec(),
-        };
-        
-        let content = toml::to_string_pretty(&config)?;
+    fn save_config(&self, config: &ConnectionsConfig) -> Result<(), Box<dyn std::error::Error>> {
+        let content = toml::to_string_pretty(config)?;
         fs::write(&self.config_path, content)?;
         Ok(())
     }
 
+    pub fn load_connections(&self) -> Result<Vec<Connection>, Box<dyn std::error::Error>> {
+        Ok(self.load_config()?.connections)
+    }
+
+    pub fn save_connections(&self, connections: &[Connection]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = self.load_config()?;
+        config.connections = connections.to_vec();
+        self.save_config(&config)
+    }
+
     pub fn add_connection(&self, connection: Connection) -> Result<(), Box<dyn std::error::Error>> {
         let mut connections = self.load_connections()?;
         connections.push(connection);
@@ -71,4 +80,82 @@ impl ConnectionStorage {
         let connections = self.load_connections()?;
         Ok(connections.into_iter().find(|c| c.id == id))
     }
+
+    /// 设置或取消收藏
+    pub fn set_favorite(&self, id: &str, favorite: bool) -> Result<Connection, Box<dyn std::error::Error>> {
+        self.modify_connection(id, |c| c.favorited_at = favorite.then(now))
+    }
+
+    /// 记录最近一次连接时间
+    pub fn mark_used(&self, id: &str) -> Result<Connection, Box<dyn std::error::Error>> {
+        self.modify_connection(id, |c| c.last_used_at = Some(now()))
+    }
+
+    fn modify_connection(
+        &self,
+        id: &str,
+        modify: impl FnOnce(&mut Connection),
+    ) -> Result<Connection, Box<dyn std::error::Error>> {
+        let mut config = self.load_config()?;
+        let connection = config.connections.iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| format!("Connection {} not found", id))?;
+        modify(connection);
+        let connection = connection.clone();
+        self.save_config(&config)?;
+        Ok(connection)
+    }
+
+    pub fn load_groups(&self) -> Result<Vec<ConnectionGroup>, Box<dyn std::error::Error>> {
+        Ok(self.load_config()?.groups)
+    }
+
+    pub fn save_groups(&self, groups: &[ConnectionGroup]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = self.load_config()?;
+        config.groups = groups.to_vec();
+        self.save_config(&config)
+    }
+
+    /// 新增或更新分组；上级分组必须存在且不能是自身或自身的下级
+    pub fn save_group(&self, group: ConnectionGroup) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = self.load_config()?;
+        let mut parent = group.parent_id.clone();
+        while let Some(parent_id) = parent {
+            if parent_id == group.id {
+                return Err(format!("Group {} cannot be moved into itself", group.name).into());
+            }
+            parent = config.groups.iter()
+                .find(|g| g.id == parent_id)
+                .ok_or_else(|| format!("Parent group {} not found", parent_id))?
+                .parent_id.clone();
+        }
+
+        match config.groups.iter().position(|g| g.id == group.id) {
+            Some(pos) => config.groups[pos] = group,
+            None => config.groups.push(group),
+        }
+        self.save_config(&config)
+    }
+
+    /// 删除分组，其下级分组与连接移到被删除分组的上级
+    pub fn delete_group(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = self.load_config()?;
+        let Some(pos) = config.groups.iter().position(|g| g.id == id) else {
+            return Ok(());
+        };
+        let parent_id = config.groups.remove(pos).parent_id;
+
+        for group in config.groups.iter_mut().filter(|g| g.parent_id.as_deref() == Some(id)) {
+            group.parent_id = parent_id.clone();
+        }
+        for connection in config.connections.iter_mut().filter(|c| c.group_id.as_deref() == Some(id)) {
+            connection.group_id = parent_id.clone();
+        }
+        self.save_config(&config)
+    }
+}
+
+/// 当前时间（Unix 秒）
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
 }
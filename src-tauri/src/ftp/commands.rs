@@ -0,0 +1,45 @@
+use crate::models::Connection;
+use crate::ftp::FtpSessionWrapper;
+use crate::remote_fs::FsRegistry;
+use crate::vault::VaultState;
+use tauri::State;
+use std::sync::Arc;
+
+/// 创建 FTP 会话并注册到文件系统注册表，之后通过 fs_* 命令操作
+#[tauri::command]
+pub async fn ftp_connect(
+    mut config: Connection,
+    session_id: Option<String>,
+    registry: State<'_, FsRegistry>,
+    vault: State<'_, VaultState>,
+) -> Result<(), String> {
+    debug_log!("[FTP-CMD] ftp_connect called for connection: {}", config.name);
+
+    let session_id = session_id.unwrap_or_else(|| config.id.clone());
+
+    // 检查是否已存在
+    {
+        let registry_guard = registry.lock().await;
+        if registry_guard.contains_key(&session_id) {
+            debug_log!("[FTP-CMD] FTP session {} already exists", session_id);
+            return Ok(());
+        }
+    }
+
+    // 把保险库引用替换为明文凭据
+    vault.lock().await
+        .resolve_connection(&mut config)
+        .map_err(|e| format!("Failed to load credentials: {}", e))?;
+
+    let ftp_config = config.ftp_config
+        .ok_or("FTP config is required for FTP connection")?;
+
+    let session = FtpSessionWrapper::new(ftp_config, session_id.clone())
+        .await
+        .map_err(|e| format!("Failed to create FTP session: {}", e))?;
+
+    registry.lock().await.insert(session_id.clone(), Arc::new(session));
+
+    debug_log!("[FTP-CMD] ftp_connect completed successfully for id: {}", session_id);
+    Ok(())
+}
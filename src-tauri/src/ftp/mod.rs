@@ -0,0 +1,6 @@
+// FTP / FTPS 模块
+pub mod session;
+pub mod commands;
+
+pub use session::FtpSessionWrapper;
+pub use commands::*;
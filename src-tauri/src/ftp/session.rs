@@ -0,0 +1,399 @@
+use crate::models::{FtpConfig, FtpSecurity};
+use crate::remote_fs::filesystem::file_name;
+use crate::remote_fs::{FileDetails, FileEntry, RemoteFs};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use suppaftp::list::{File, PosixPexQuery};
+use suppaftp::tokio::{AsyncRustlsConnector, AsyncRustlsFtpStream};
+use suppaftp::types::FileType;
+use suppaftp::{Mode, Status};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// 建立连接并登录的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 数据连接每次读写的块大小
+const TRANSFER_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 主动模式下等待服务器连入数据端口的超时时间
+const ACTIVE_MODE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// FTP 会话包装器，通过 RemoteFs 供文件浏览器使用
+pub struct FtpSessionWrapper {
+    pub id: String,
+    pub ftp: Arc<Mutex<AsyncRustlsFtpStream>>,
+}
+
+impl FtpSessionWrapper {
+    /// 连接 FTP 服务器并登录（FTPS 使用内置的 Mozilla 根证书校验服务器证书）
+    pub async fn new(
+        config: FtpConfig,
+        session_id: String,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        debug_log!("[FTP] ===== Starting FTP Connection =====");
+        debug_log!("[FTP] Target: {}:{} ({:?})", config.host, config.port, config.security);
+
+        let ftp = tokio::time::timeout(CONNECT_TIMEOUT, connect(&config))
+            .await
+            .map_err(|_| format!("Connection to {}:{} timed out", config.host, config.port))??;
+
+        debug_log!("[FTP] ===== FTP Session Established =====");
+
+        Ok(Self {
+            id: session_id,
+            ftp: Arc::new(Mutex::new(ftp)),
+        })
+    }
+}
+
+/// FTP 控制连接同一时间只能进行一个传输，所有操作在会话锁内串行执行
+#[async_trait]
+impl RemoteFs for FtpSessionWrapper {
+    /// 读写均在整个传输期间持有会话锁，同一会话内复制会死锁
+    fn concurrent_transfers(&self) -> bool {
+        false
+    }
+
+    /// 解析 LIST 输出，兼容 Unix 与 DOS 格式
+    async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        let lines = ftp.list(Some(path)).await
+            .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+        let entries = lines.iter()
+            .filter_map(|line| match line.parse::<File>() {
+                Ok(file) => Some(file),
+                Err(_) => {
+                    // 跳过 "total 12" 等无法解析的行
+                    debug_log!("[FTP] Skipping unparsable LIST line: {:?}", line);
+                    None
+                }
+            })
+            .filter(|file| file.name() != "." && file.name() != "..")
+            .map(|file| to_entry(&file))
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// FTP 没有通用的 stat 命令，通过列出父目录查找
+    async fn stat(&self, path: &str) -> Result<FileEntry, Box<dyn std::error::Error + Send + Sync>> {
+        let name = file_name(path);
+        if name == "/" {
+            return Ok(FileEntry {
+                name,
+                is_dir: true,
+                is_symlink: false,
+                size: 0,
+                permissions: None,
+                modified: None,
+            });
+        }
+
+        self.find_in_parent(path).await.map(|(file, _)| to_entry(&file))
+    }
+
+    async fn read_range(&self, path: &str, offset: u64, len: u64) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        if offset > 0 {
+            ftp.resume_transfer(offset as usize).await
+                .map_err(|e| format!("Server does not support resuming (REST): {}", e))?;
+        }
+        let stream = ftp.retr_as_stream(path).await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let mut stream = stream.take(len);
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).await
+            .map_err(|e| format!("Failed to read data: {}", e))?;
+        let stream = stream.into_inner();
+
+        if (data.len() as u64) < len {
+            ftp.finalize_retr_stream(stream).await
+                .map_err(|e| format!("Failed to finish download: {}", e))?;
+        } else {
+            // 未读到文件末尾，中止剩余传输
+            ftp.abort(stream).await
+                .map_err(|e| format!("Failed to abort transfer: {}", e))?;
+        }
+        Ok(data)
+    }
+
+    async fn read_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        sink: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        if offset > 0 {
+            ftp.resume_transfer(offset as usize).await
+                .map_err(|e| format!("Server does not support resuming (REST): {}", e))?;
+        }
+        let mut stream = ftp.retr_as_stream(path).await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let mut buf = vec![0u8; TRANSFER_CHUNK_SIZE];
+        let mut total = 0u64;
+        let copied: Result<(), Box<dyn std::error::Error + Send + Sync>> = async {
+            loop {
+                let n = stream.read(&mut buf).await
+                    .map_err(|e| format!("Failed to read data: {}", e))?;
+                if n == 0 {
+                    return Ok(());
+                }
+                sink.write_all(&buf[..n]).await?;
+                total += n as u64;
+            }
+        }
+        .await;
+
+        if let Err(e) = copied {
+            // 接收端提前结束（如只需要文件开头），中止传输使控制连接可以继续使用
+            let _ = ftp.abort(stream).await;
+            return Err(e);
+        }
+        ftp.finalize_retr_stream(stream).await
+            .map_err(|e| format!("Failed to finish download: {}", e))?;
+        Ok(total)
+    }
+
+    async fn write_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        source: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        // REST 之后的 STOR 从该位置开始覆盖写入
+        if offset > 0 {
+            ftp.resume_transfer(offset as usize).await
+                .map_err(|e| format!("Server does not support resuming (REST): {}", e))?;
+        }
+        let mut stream = ftp.put_with_stream(path).await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+
+        let mut buf = vec![0u8; TRANSFER_CHUNK_SIZE];
+        let mut total = 0u64;
+        loop {
+            let n = source.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            stream.write_all(&buf[..n]).await
+                .map_err(|e| format!("Failed to write data: {}", e))?;
+            total += n as u64;
+        }
+
+        ftp.finalize_put_stream(stream).await
+            .map_err(|e| format!("Failed to finish upload: {}", e))?;
+        Ok(total)
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        ftp.rename(from, to).await
+            .map_err(|e| format!("Failed to rename: {}", e))?;
+        Ok(())
+    }
+
+    async fn remove(&self, path: &str, is_dir: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        if is_dir {
+            ftp.rmdir(path).await
+                .map_err(|e| format!("Failed to remove directory: {}", e))?;
+        } else {
+            ftp.rm(path).await
+                .map_err(|e| format!("Failed to remove file: {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        ftp.mkdir(path).await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        Ok(())
+    }
+
+    /// 使用 SITE CHMOD 扩展命令，部分服务器不支持
+    async fn chmod(&self, path: &str, mode: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut ftp = self.ftp.lock().await;
+        ftp.site(format!("CHMOD {:o} {}", mode & 0o7777, path)).await
+            .map_err(|e| format!("Failed to change permissions: {}", e))?;
+        Ok(())
+    }
+
+    async fn symlink(&self, _target: &str, _link: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("Symbolic links are not supported over FTP".into())
+    }
+
+    /// LIST 输出中 `name -> target` 的目标部分
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let (file, _) = self.find_in_parent(path).await?;
+        file.symlink()
+            .map(|target| target.to_string_lossy().to_string())
+            .ok_or_else(|| format!("{} is not a symbolic link", path).into())
+    }
+
+    async fn set_modified(&self, _path: &str, _mtime: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("Setting modification time is not supported over FTP".into())
+    }
+
+    /// 来自父目录的 LIST 输出，Unix 格式时包含属主与属组名
+    async fn details(&self, path: &str) -> Result<FileDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let (file, line) = self.find_in_parent(path).await?;
+        let entry = to_entry(&file);
+
+        // "drwxr-xr-x 2 owner group 4096 Jan 1 00:00 name"
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let is_posix = line.starts_with(['-', 'd', 'l', 'b', 'c', 'p', 's']) && fields.len() > 4;
+        let (owner, group) = if is_posix {
+            (Some(fields[2].to_string()), Some(fields[3].to_string()))
+        } else {
+            (None, None)
+        };
+
+        Ok(FileDetails {
+            path: path.to_string(),
+            name: entry.name,
+            is_dir: entry.is_dir,
+            is_symlink: entry.is_symlink,
+            size: entry.size,
+            permissions: entry.permissions,
+            uid: file.uid(),
+            gid: file.gid(),
+            owner,
+            group,
+            accessed: None,
+            modified: entry.modified,
+            link_target: file.symlink().map(|target| target.to_string_lossy().to_string()),
+        })
+    }
+
+    async fn chown(&self, _path: &str, _uid: u32, _gid: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("Changing ownership is not supported over FTP".into())
+    }
+
+    /// 相对路径以当前工作目录（登录后即用户主目录）为基准
+    async fn canonicalize(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        if path.starts_with('/') {
+            return Ok(path.to_string());
+        }
+        let mut ftp = self.ftp.lock().await;
+        let cwd = ftp.pwd().await
+            .map_err(|e| format!("Failed to get working directory: {}", e))?;
+        Ok(match path {
+            "" | "." => cwd,
+            _ => format!("{}/{}", cwd.trim_end_matches('/'), path),
+        })
+    }
+
+    /// 退出登录（QUIT）
+    async fn close(&self) {
+        let _ = self.ftp.lock().await.quit().await;
+    }
+}
+
+impl FtpSessionWrapper {
+    /// FTP 没有通用的 stat 命令，通过列出父目录查找条目，同时返回原始 LIST 行
+    async fn find_in_parent(&self, path: &str) -> Result<(File, String), Box<dyn std::error::Error + Send + Sync>> {
+        let trimmed = path.trim_end_matches('/');
+        let (parent, name) = match trimmed.rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((parent, name)) => (parent, name),
+            None => (".", trimmed),
+        };
+
+        let mut ftp = self.ftp.lock().await;
+        let lines = ftp.list(Some(parent)).await
+            .map_err(|e| format!("Failed to read directory: {}", e))?;
+        lines.into_iter()
+            .filter_map(|line| line.parse::<File>().ok().map(|file| (file, line)))
+            .find(|(file, _)| file.name() == name)
+            .ok_or_else(|| format!("No such file or directory: {}", path).into())
+    }
+}
+
+/// 建立控制连接、按配置协商 TLS 并登录
+async fn connect(config: &FtpConfig) -> Result<AsyncRustlsFtpStream, Box<dyn std::error::Error + Send + Sync>> {
+    let addr = (config.host.as_str(), config.port);
+
+    let mut ftp = match config.security {
+        FtpSecurity::None => AsyncRustlsFtpStream::connect(addr).await
+            .map_err(|e| format!("Connection failed: {}", e))?,
+        FtpSecurity::Explicit => AsyncRustlsFtpStream::connect(addr).await
+            .map_err(|e| format!("Connection failed: {}", e))?
+            .into_secure(tls_connector()?, &config.host).await
+            .map_err(|e| format!("TLS negotiation failed: {}", e))?,
+        // 隐式 TLS 在 TCP 连接建立后立即握手，随后与显式模式一样要求数据连接加密
+        FtpSecurity::Implicit => {
+            let mut ftp = AsyncRustlsFtpStream::connect_secure_implicit(addr, tls_connector()?, &config.host).await
+                .map_err(|e| format!("TLS negotiation failed: {}", e))?;
+            ftp.custom_command("PBSZ 0", &[Status::CommandOk]).await
+                .map_err(|e| format!("TLS negotiation failed: {}", e))?;
+            ftp.custom_command("PROT P", &[Status::CommandOk]).await
+                .map_err(|e| format!("TLS negotiation failed: {}", e))?;
+            ftp
+        }
+    };
+    debug_log!("[FTP] ✅ Connected, welcome: {:?}", ftp.get_welcome_msg());
+
+    if !config.passive {
+        ftp = ftp.active_mode(ACTIVE_MODE_TIMEOUT);
+    } else {
+        ftp.set_mode(Mode::Passive);
+    }
+
+    let (username, password) = if config.username.is_empty() {
+        ("anonymous", "anonymous@")
+    } else {
+        (config.username.as_str(), config.password.as_str())
+    };
+    ftp.login(username, password).await
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+    debug_log!("[FTP] ✅ Logged in as {}", username);
+
+    // 默认的 ASCII 模式会改写二进制文件中的换行符
+    ftp.transfer_type(FileType::Binary).await
+        .map_err(|e| format!("Failed to switch to binary mode: {}", e))?;
+
+    Ok(ftp)
+}
+
+/// 使用内置 Mozilla 根证书的 TLS 连接器
+///
+/// 显式指定 aws-lc-rs（与 russh 相同）作为加密实现，依赖树中同时启用了多个实现时 rustls 无法自动选择。
+fn tls_connector() -> Result<AsyncRustlsConnector, Box<dyn std::error::Error + Send + Sync>> {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(AsyncRustlsConnector::from(TlsConnector::from(Arc::new(config))))
+}
+
+fn to_entry(file: &File) -> FileEntry {
+    FileEntry {
+        name: file.name().to_string(),
+        is_dir: file.is_directory(),
+        is_symlink: file.is_symlink(),
+        size: file.size() as u64,
+        permissions: Some(permissions(file)),
+        modified: file.modified().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+    }
+}
+
+/// 把 LIST 中的权限转换为八进制字符串（与 SFTP 一致）
+fn permissions(file: &File) -> String {
+    let bits = |who: PosixPexQuery| {
+        (file.can_read(who) as u32) << 2 | (file.can_write(who) as u32) << 1 | file.can_execute(who) as u32
+    };
+    format!("{:o}{:o}{:o}", bits(PosixPexQuery::Owner), bits(PosixPexQuery::Group), bits(PosixPexQuery::Others))
+}
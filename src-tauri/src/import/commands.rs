@@ -0,0 +1,43 @@
+use crate::config::ConnectionStorageState;
+use crate::import::{self, ImportPreview, ImportSource};
+use crate::models::Connection;
+use tauri::State;
+
+/// 预览导入结果（dry-run），列出每个条目的映射结果、不支持的选项与重复情况
+/// `path` 为空时仅 SshConfig 来源可用（读取 ~/.ssh/config）
+#[tauri::command]
+pub async fn import_preview(
+    source: ImportSource,
+    path: Option<String>,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<ImportPreview, String> {
+    debug_log!("[IMPORT] import_preview: {:?} {:?}", source, path);
+
+    let preview = import::preview(source, path.as_deref()).map_err(|e| e.to_string())?;
+    let existing = storage.lock().await
+        .load_connections()
+        .map_err(|e| format!("Failed to load connections: {}", e))?;
+    Ok(preview.mark_duplicates(&existing))
+}
+
+/// 导入连接；`names` 为预览中的 source_name 列表，为空时导入全部非重复条目
+#[tauri::command]
+pub async fn import_from_source(
+    source: ImportSource,
+    path: Option<String>,
+    names: Option<Vec<String>>,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<Vec<Connection>, String> {
+    debug_log!("[IMPORT] import_from_source: {:?} {:?}, names={:?}", source, path, names);
+
+    let preview = import::preview(source, path.as_deref()).map_err(|e| e.to_string())?;
+    let storage = storage.lock().await;
+    let existing = storage
+        .load_connections()
+        .map_err(|e| format!("Failed to load connections: {}", e))?;
+    let imported = import::apply(&storage, preview.mark_duplicates(&existing), names)
+        .map_err(|e| format!("Failed to save imported connections: {}", e))?;
+
+    debug_log!("[IMPORT] Imported {} connections from {:?}", imported.len(), source);
+    Ok(imported)
+}
@@ -0,0 +1,203 @@
+use crate::import::text::{parse_ini, read_text};
+use crate::import::{ImportEntry, ImportPreview, ImportResult};
+use crate::models::{Connection, FtpConfig, JumpHost, SerialConfig, SshAuth, SshConfig, TelnetConfig};
+use std::path::Path;
+
+/// 书签节中不是会话的键
+const META_KEYS: &[&str] = &["SubRep", "ImgNum"];
+/// 私钥路径中的 MobaXterm 占位符，需要在本机替换为实际路径
+const PATH_PLACEHOLDERS: &[&str] = &["_CurrentDrive_", "_ProfileDir_", "_MyDocuments_", "_AppDataDir_"];
+
+/// 解析 MobaXterm 的 .mxtsessions 导出
+pub fn preview(path: &str) -> ImportResult<ImportPreview> {
+    let text = read_text(Path::new(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    debug_log!("[IMPORT] Parsing MobaXterm sessions {}", path);
+
+    let mut preview = ImportPreview::default();
+    for section in parse_ini(&text) {
+        if !section.name.starts_with("Bookmarks") {
+            continue;
+        }
+        let folder = section.get("SubRep").filter(|f| !f.is_empty());
+        for (name, value) in &section.entries {
+            if META_KEYS.iter().any(|k| k.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            let source_name = match folder {
+                Some(folder) => format!("{}\\{}", folder, name),
+                None => name.clone(),
+            };
+            match entry(name, value) {
+                Ok(mut entry) => {
+                    entry.source_name = source_name;
+                    preview.push(entry);
+                }
+                Err(e) => preview.warnings.push(format!("Session {}: {}", source_name, e)),
+            }
+        }
+    }
+    Ok(preview)
+}
+
+/// 会话格式为 `#<图标>#<类型>%<参数>%...#<终端设置>#...`，类型编号见 MobaXterm 会话类型列表
+fn entry(name: &str, value: &str) -> Result<ImportEntry, String> {
+    let body = value.split('#').nth(2).ok_or("malformed session")?;
+    let fields: Vec<&str> = body.split('%').map(str::trim).collect();
+    let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+    let port = |i: usize, default: u16| match field(i) {
+        "" => Ok(default),
+        p => p.parse::<u16>().map_err(|_| format!("invalid port {}", p)),
+    };
+    let host = || match field(1) {
+        "" => Err("missing host".to_string()),
+        h => Ok(h.to_string()),
+    };
+    let mut warnings = Vec::new();
+
+    let connection = match field(0) {
+        // SSH
+        "0" => {
+            let auth = match field(14) {
+                "" => SshAuth::Agent { socket_path: None },
+                key => {
+                    if PATH_PLACEHOLDERS.iter().any(|p| key.contains(p)) {
+                        warnings.push(format!("Private key path {} uses a MobaXterm placeholder, update it after import", key));
+                    }
+                    SshAuth::PublicKey { private_key_path: key.to_string(), passphrase: None }
+                }
+            };
+            let mut config = SshConfig::new(host()?, port(2, 22)?, field(3).to_string(), auth);
+            // SSH gateway（跳板机）
+            if !field(8).is_empty() {
+                config.jump_hosts.push(JumpHost {
+                    host: field(8).to_string(),
+                    port: port(9, 22)?,
+                    username: field(10).to_string(),
+                    auth: SshAuth::Agent { socket_path: None },
+                });
+            }
+            Connection::new_ssh(name.to_string(), config)
+        }
+        "1" => Connection::new_telnet(name.to_string(), TelnetConfig::new(host()?, port(2, 23)?)),
+        "6" => Connection::new_ftp(name.to_string(), FtpConfig::new(host()?, port(2, 21)?, field(3).to_string())),
+        // SFTP 会话使用 SSH 连接打开文件浏览器
+        "7" => {
+            warnings.push("SFTP session imported as an SSH connection".to_string());
+            let config = SshConfig::new(host()?, port(2, 22)?, field(3).to_string(), SshAuth::Agent { socket_path: None });
+            Connection::new_ssh(name.to_string(), config)
+        }
+        "8" => {
+            // 端口字段形如 `COM3  (USB Serial Port (COM3))`
+            let device = field(8).split_whitespace().next().ok_or("missing serial port")?;
+            let mut config = SerialConfig::new(device.to_string());
+            if let Some(speed) = field(2).parse::<u32>().ok().filter(|s| *s > 0) {
+                config.baud_rate = speed;
+            }
+            warnings.push("Data bits, parity, stop bits and flow control use defaults (8N1, no flow control)".to_string());
+            Connection::new_serial(name.to_string(), config)
+        }
+        other => return Err(format!("{} sessions are not supported", session_type(other))),
+    };
+
+    let mut entry = ImportEntry::new(name.to_string(), connection);
+    entry.warnings = warnings;
+    Ok(entry)
+}
+
+fn session_type(code: &str) -> String {
+    match code {
+        "2" => "Rsh".to_string(),
+        "3" => "XDMCP".to_string(),
+        "4" => "RDP".to_string(),
+        "5" => "VNC".to_string(),
+        "9" => "File".to_string(),
+        "10" => "Shell".to_string(),
+        "11" => "Browser".to_string(),
+        "12" => "Mosh".to_string(),
+        "13" => "S3".to_string(),
+        "14" => "WSL".to_string(),
+        other => format!("Type {}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionType;
+
+    const SESSIONS: &str = "\
+[Bookmarks]
+SubRep=
+ImgNum=42
+web=#109#0%web.example%2222%alice%%-1%-1%%bastion.example%2200%jump%0%0%0%_ProfileDir_\\.ssh\\id_rsa%%-1%0%0%0%%1080%%0%0%1#MobaFont%10%0%0%-1%15#0# #-1
+router=#98#1%10.0.0.1%%%%%#MobaFont%10#0# #-1
+console=#131#8%-1%115200%3%0%0%1%2%COM3  (USB Serial Port (COM3))%0%0#MobaFont%10#0# #-1
+desktop=#91#4%win.example%3389%admin#MobaFont%10#0# #-1
+broken=garbage
+badport=#109#0%h.example%notaport%root#MobaFont%10#0# #-1
+
+[Bookmarks_1]
+SubRep=Prod\\DB
+ImgNum=41
+db=#109#0%db.example%%root#MobaFont%10#0# #-1
+";
+
+    fn load() -> ImportPreview {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.mxtsessions");
+        std::fs::write(&path, SESSIONS).unwrap();
+        preview(path.to_str().unwrap()).unwrap()
+    }
+
+    fn find<'a>(preview: &'a ImportPreview, name: &str) -> &'a ImportEntry {
+        preview.entries.iter().find(|e| e.source_name == name).unwrap()
+    }
+
+    #[test]
+    fn ssh_fields_are_split_on_percent() {
+        let preview = load();
+        let web = find(&preview, "web");
+        let ssh = web.connection.ssh_config.as_ref().unwrap();
+        assert_eq!((ssh.host.as_str(), ssh.port, ssh.username.as_str()), ("web.example", 2222, "alice"));
+        assert!(matches!(&ssh.auth, SshAuth::PublicKey { private_key_path, .. } if private_key_path == "_ProfileDir_\\.ssh\\id_rsa"));
+        assert!(web.warnings.iter().any(|w| w.contains("placeholder")));
+
+        let jump = &ssh.jump_hosts[0];
+        assert_eq!((jump.host.as_str(), jump.port, jump.username.as_str()), ("bastion.example", 2200, "jump"));
+    }
+
+    #[test]
+    fn empty_fields_fall_back_to_defaults() {
+        let preview = load();
+        let router = find(&preview, "router");
+        assert_eq!(router.connection.connection_type, ConnectionType::Telnet);
+        assert_eq!(router.connection.telnet_config.as_ref().unwrap().port, 23);
+
+        let db = find(&preview, "Prod\\DB\\db");
+        let ssh = db.connection.ssh_config.as_ref().unwrap();
+        assert_eq!(ssh.port, 22);
+        assert!(matches!(ssh.auth, SshAuth::Agent { socket_path: None }));
+        assert!(ssh.jump_hosts.is_empty());
+    }
+
+    #[test]
+    fn serial_device_is_taken_from_port_description() {
+        let preview = load();
+        let serial = find(&preview, "console").connection.serial_config.as_ref().unwrap();
+        assert_eq!(serial.device, "COM3");
+        assert_eq!(serial.baud_rate, 115200);
+    }
+
+    #[test]
+    fn bad_sessions_become_warnings() {
+        let preview = load();
+        assert_eq!(preview.entries.len(), 4);
+        for expected in [
+            "Session desktop: RDP sessions are not supported",
+            "Session broken: malformed session",
+            "Session badport: invalid port notaport",
+        ] {
+            assert!(preview.warnings.iter().any(|w| w == expected), "missing warning {:?} in {:?}", expected, preview.warnings);
+        }
+    }
+}
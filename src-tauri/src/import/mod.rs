@@ -0,0 +1,140 @@
+mod ssh_config;
+mod putty;
+mod mobaxterm;
+mod xshell;
+mod termius;
+mod text;
+pub mod commands;
+
+pub use commands::*;
+
+use crate::config::ConnectionStorage;
+use crate::models::{Connection, ConnectionType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+type ImportResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 导入来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ImportSource {
+    /// OpenSSH 客户端配置（默认 ~/.ssh/config）
+    SshConfig,
+    /// PuTTY 会话注册表导出（.reg）
+    Putty,
+    /// MobaXterm 会话导出（.mxtsessions）
+    MobaXterm,
+    /// Xshell 会话文件（.xsh）或其所在目录
+    Xshell,
+    /// Termius 导出的 JSON
+    Termius,
+}
+
+/// 导入预览中的一个条目
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportEntry {
+    /// 来源中的名称（如 Host 别名），在同一预览中唯一，导入时按此名称选择条目
+    pub source_name: String,
+    pub connection: Connection,
+    /// 无法映射到连接配置的选项，导入后会被丢弃
+    pub unsupported: Vec<String>,
+    /// 其他需要注意的问题（如多个 IdentityFile 只使用第一个）
+    pub warnings: Vec<String>,
+    /// 已存在同名或同一目标的连接
+    pub duplicate: bool,
+}
+
+impl ImportEntry {
+    fn new(source_name: String, connection: Connection) -> Self {
+        Self {
+            source_name,
+            connection,
+            unsupported: Vec::new(),
+            warnings: Vec::new(),
+            duplicate: false,
+        }
+    }
+}
+
+/// 导入预览（dry-run），不会修改已保存的连接
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportPreview {
+    pub entries: Vec<ImportEntry>,
+    /// 与具体条目无关的问题（如 Include 的文件不存在、不支持的会话类型）
+    pub warnings: Vec<String>,
+}
+
+impl ImportPreview {
+    /// 添加条目，来源名称重复时追加序号
+    fn push(&mut self, mut entry: ImportEntry) {
+        let base = entry.source_name.clone();
+        let mut n = 2;
+        while self.entries.iter().any(|e| e.source_name == entry.source_name) {
+            entry.source_name = format!("{} ({})", base, n);
+            n += 1;
+        }
+        self.entries.push(entry);
+    }
+
+    /// 标记与已保存连接同名或同一目标的条目；预览内部重复的目标只保留第一个
+    fn mark_duplicates(mut self, existing: &[Connection]) -> Self {
+        let names: HashSet<&str> = existing.iter().map(|c| c.name.as_str()).collect();
+        let mut endpoints: HashSet<String> = existing.iter().filter_map(endpoint).collect();
+        for entry in &mut self.entries {
+            let name_taken = names.contains(entry.connection.name.as_str());
+            let endpoint_taken = endpoint(&entry.connection).is_some_and(|e| !endpoints.insert(e));
+            entry.duplicate = name_taken || endpoint_taken;
+        }
+        self
+    }
+}
+
+/// 用于判重的连接目标：类型、用户、主机与端口（串口为设备）
+fn endpoint(connection: &Connection) -> Option<String> {
+    match connection.connection_type {
+        ConnectionType::Ssh => connection.ssh_config.as_ref()
+            .map(|c| format!("ssh://{}@{}:{}", c.username, c.host.to_lowercase(), c.port)),
+        ConnectionType::Telnet => connection.telnet_config.as_ref()
+            .map(|c| format!("telnet://{}:{}", c.host.to_lowercase(), c.port)),
+        ConnectionType::Ftp => connection.ftp_config.as_ref()
+            .map(|c| format!("ftp://{}@{}:{}", c.username, c.host.to_lowercase(), c.port)),
+        ConnectionType::Serial => connection.serial_config.as_ref()
+            .map(|c| format!("serial://{}", c.device)),
+        ConnectionType::Local => None,
+    }
+}
+
+/// 解析导入来源；除 ssh_config 外必须指定文件路径
+fn preview(source: ImportSource, path: Option<&str>) -> ImportResult<ImportPreview> {
+    let required = || path.ok_or_else(|| format!("A file path is required to import from {:?}", source));
+    match source {
+        ImportSource::SshConfig => ssh_config::preview(path),
+        ImportSource::Putty => putty::preview(required()?),
+        ImportSource::MobaXterm => mobaxterm::preview(required()?),
+        ImportSource::Xshell => xshell::preview(required()?),
+        ImportSource::Termius => termius::preview(required()?),
+    }
+}
+
+/// 保存预览中的条目：`names` 为空时导入全部非重复条目，否则只导入指定条目
+fn apply(
+    storage: &ConnectionStorage,
+    preview: ImportPreview,
+    names: Option<Vec<String>>,
+) -> Result<Vec<Connection>, Box<dyn std::error::Error>> {
+    let imported: Vec<Connection> = preview.entries
+        .into_iter()
+        .filter(|entry| match &names {
+            Some(names) => names.contains(&entry.source_name),
+            None => !entry.duplicate,
+        })
+        .map(|entry| entry.connection)
+        .collect();
+
+    if !imported.is_empty() {
+        let mut connections = storage.load_connections()?;
+        connections.extend(imported.iter().cloned());
+        storage.save_connections(&connections)?;
+    }
+    Ok(imported)
+}
@@ -0,0 +1,324 @@
+use crate::import::text::{parse_ini, read_text, Section};
+use crate::import::{ImportEntry, ImportPreview, ImportResult};
+use crate::models::{Connection, SerialConfig, SerialFlowControl, SerialParity, SshAuth, SshConfig, TelnetConfig};
+use std::path::Path;
+
+/// regedit 导出中保存会话的注册表路径
+const SESSIONS_KEY: &str = "\\Software\\SimonTatham\\PuTTY\\Sessions\\";
+/// PuTTY 的默认会话模板，不对应具体主机
+const DEFAULT_SESSION: &str = "Default Settings";
+
+/// 解析 PuTTY 会话的 .reg 导出
+pub fn preview(path: &str) -> ImportResult<ImportPreview> {
+    let text = read_text(Path::new(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    debug_log!("[IMPORT] Parsing PuTTY registry export {}", path);
+
+    let mut preview = ImportPreview::default();
+    for section in parse_ini(&text) {
+        // `[-HKEY_...]` 表示删除该键
+        if section.name.starts_with('-') {
+            continue;
+        }
+        let Some(pos) = section.name.find(SESSIONS_KEY) else {
+            continue;
+        };
+        let name = decode_session_name(&section.name[pos + SESSIONS_KEY.len()..]);
+        if name.is_empty() || name.contains('\\') || name == DEFAULT_SESSION {
+            continue;
+        }
+
+        let session = Session::new(&section);
+        match session.entry(&name) {
+            Ok(Some(entry)) => preview.push(entry),
+            Ok(None) => {}
+            Err(e) => preview.warnings.push(format!("Session {}: {}", name, e)),
+        }
+    }
+    Ok(preview)
+}
+
+/// 注册表值
+enum RegValue {
+    Str(String),
+    Dword(u32),
+}
+
+/// 一个会话的注册表值
+struct Session {
+    values: Vec<(String, RegValue)>,
+}
+
+impl Session {
+    fn new(section: &Section) -> Self {
+        let values = section.entries.iter()
+            .filter_map(|(key, value)| {
+                let key = key.strip_prefix('"')?.strip_suffix('"')?.to_string();
+                let value = if let Some(hex) = value.strip_prefix("dword:") {
+                    RegValue::Dword(u32::from_str_radix(hex, 16).ok()?)
+                } else {
+                    RegValue::Str(unescape(value.strip_prefix('"')?.strip_suffix('"')?))
+                };
+                Some((key, value))
+            })
+            .collect();
+        Self { values }
+    }
+
+    fn value(&self, key: &str) -> Option<&RegValue> {
+        self.values.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v)
+    }
+
+    /// 非空字符串值
+    fn string(&self, key: &str) -> Option<&str> {
+        match self.value(key)? {
+            RegValue::Str(s) if !s.is_empty() => Some(s),
+            _ => None,
+        }
+    }
+
+    fn dword(&self, key: &str) -> Option<u32> {
+        match self.value(key)? {
+            RegValue::Dword(n) => Some(*n),
+            RegValue::Str(_) => None,
+        }
+    }
+
+    fn port(&self, default: u16) -> Result<u16, String> {
+        match self.dword("PortNumber") {
+            None | Some(0) => Ok(default),
+            Some(port) => u16::try_from(port).map_err(|_| format!("invalid port {}", port)),
+        }
+    }
+
+    /// 未设置主机（如只保存了外观设置的会话）时返回 None
+    fn entry(&self, name: &str) -> Result<Option<ImportEntry>, String> {
+        let protocol = self.string("Protocol").unwrap_or("ssh").to_ascii_lowercase();
+        let mut warnings = Vec::new();
+
+        let connection = match protocol.as_str() {
+            "ssh" => {
+                let Some(host) = self.string("HostName") else {
+                    return Ok(None);
+                };
+                // HostName 可以写成 user@host
+                let (user, host) = match host.rsplit_once('@') {
+                    Some((user, host)) => (user.to_string(), host.to_string()),
+                    None => (self.string("UserName").unwrap_or_default().to_string(), host.to_string()),
+                };
+                let auth = match self.string("PublicKeyFile") {
+                    Some(path) => SshAuth::PublicKey { private_key_path: path.to_string(), passphrase: None },
+                    None => SshAuth::Agent { socket_path: None },
+                };
+                Connection::new_ssh(name.to_string(), SshConfig::new(host, self.port(22)?, user, auth))
+            }
+            "telnet" => {
+                let Some(host) = self.string("HostName") else {
+                    return Ok(None);
+                };
+                Connection::new_telnet(name.to_string(), TelnetConfig::new(host.to_string(), self.port(23)?))
+            }
+            "serial" => {
+                let device = self.string("SerialLine").ok_or("missing serial line")?;
+                Connection::new_serial(name.to_string(), self.serial(device, &mut warnings))
+            }
+            other => return Err(format!("{} sessions are not supported", other)),
+        };
+
+        let mut entry = ImportEntry::new(name.to_string(), connection);
+        entry.unsupported = self.unsupported();
+        entry.warnings = warnings;
+        Ok(Some(entry))
+    }
+
+    fn serial(&self, device: &str, warnings: &mut Vec<String>) -> SerialConfig {
+        let mut config = SerialConfig::new(device.to_string());
+        if let Some(speed) = self.dword("SerialSpeed").filter(|s| *s > 0) {
+            config.baud_rate = speed;
+        }
+        if let Some(bits) = self.dword("SerialDataBits").filter(|b| (5..=8).contains(b)) {
+            config.data_bits = bits as u8;
+        }
+        // PuTTY 以半位为单位保存停止位
+        match self.dword("SerialStopHalfbits") {
+            Some(4) => config.stop_bits = 2,
+            Some(3) => warnings.push("1.5 stop bits is not supported, using 1".to_string()),
+            _ => {}
+        }
+        match self.dword("SerialParity") {
+            Some(1) => config.parity = SerialParity::Odd,
+            Some(2) => config.parity = SerialParity::Even,
+            Some(3) | Some(4) => warnings.push("Mark / space parity is not supported, using none".to_string()),
+            _ => {}
+        }
+        match self.dword("SerialFlowControl") {
+            Some(1) => config.flow_control = SerialFlowControl::Software,
+            Some(2) => config.flow_control = SerialFlowControl::Hardware,
+            Some(3) => warnings.push("DSR/DTR flow control is not supported, using none".to_string()),
+            _ => {}
+        }
+        config
+    }
+
+    /// 设置了非默认值但无法导入的常用选项
+    fn unsupported(&self) -> Vec<String> {
+        let mut unsupported = Vec::new();
+        if self.dword("ProxyMethod").is_some_and(|m| m != 0) {
+            unsupported.push(format!("Proxy {}", self.string("ProxyHost").unwrap_or_default()));
+        }
+        if let Some(forwards) = self.string("PortForwardings") {
+            unsupported.push(format!("PortForwardings {}", forwards));
+        }
+        if let Some(command) = self.string("RemoteCommand") {
+            unsupported.push(format!("RemoteCommand {}", command));
+        }
+        if self.dword("AgentFwd") == Some(1) {
+            unsupported.push("AgentFwd".to_string());
+        }
+        if self.dword("X11Forward") == Some(1) {
+            unsupported.push("X11Forward".to_string());
+        }
+        unsupported
+    }
+}
+
+/// 还原 .reg 字符串中的 `\\` 与 `\"`
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// PuTTY 把会话名中的特殊字符保存为 %XX
+fn decode_session_name(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionType;
+
+    const EXPORT: &str = r#"Windows Registry Editor Version 5.00
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Default%20Settings]
+"HostName"="default.example"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\my%20server%5Bprod%5D]
+"HostName"="admin@srv.example"
+"PortNumber"=dword:000008ae
+"Protocol"="ssh"
+"PublicKeyFile"="C:\\Users\\me\\key \"prod\".ppk"
+"PortForwardings"="L8080=localhost:80"
+"AgentFwd"=dword:00000001
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\console]
+"Protocol"="serial"
+"SerialLine"="COM4"
+"SerialSpeed"=dword:0001c200
+"SerialDataBits"=dword:00000007
+"SerialStopHalfbits"=dword:00000004
+"SerialParity"=dword:00000002
+"SerialFlowControl"=dword:00000003
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\switch]
+"Protocol"="telnet"
+"HostName"="10.0.0.2"
+"PortNumber"=dword:00000000
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\raw]
+"Protocol"="raw"
+"HostName"="raw.example"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\bigport]
+"HostName"="big.example"
+"PortNumber"=dword:00010000
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\appearance]
+"FontHeight"=dword:0000000a
+
+[-HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\deleted]
+"#;
+
+    /// regedit 以带 BOM 的 UTF-16LE 导出
+    fn load() -> ImportPreview {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("putty.reg");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(EXPORT.replace('\n', "\r\n").encode_utf16().flat_map(u16::to_le_bytes));
+        std::fs::write(&path, bytes).unwrap();
+        preview(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn escaped_names_and_values_are_decoded() {
+        let preview = load();
+        let entry = &preview.entries[0];
+        assert_eq!(entry.source_name, "my server[prod]");
+        let ssh = entry.connection.ssh_config.as_ref().unwrap();
+        assert_eq!((ssh.host.as_str(), ssh.port, ssh.username.as_str()), ("srv.example", 2222, "admin"));
+        assert!(matches!(&ssh.auth, SshAuth::PublicKey { private_key_path, .. } if private_key_path == r#"C:\Users\me\key "prod".ppk"#));
+        assert_eq!(entry.unsupported, vec!["PortForwardings L8080=localhost:80", "AgentFwd"]);
+    }
+
+    #[test]
+    fn serial_and_telnet_sessions() {
+        let preview = load();
+        let console = preview.entries.iter().find(|e| e.source_name == "console").unwrap();
+        let serial = console.connection.serial_config.as_ref().unwrap();
+        assert_eq!((serial.device.as_str(), serial.baud_rate, serial.data_bits, serial.stop_bits), ("COM4", 115200, 7, 2));
+        assert_eq!(serial.parity, SerialParity::Even);
+        assert_eq!(serial.flow_control, SerialFlowControl::None);
+        assert!(console.warnings.iter().any(|w| w.contains("DSR/DTR")));
+
+        let switch = preview.entries.iter().find(|e| e.source_name == "switch").unwrap();
+        assert_eq!(switch.connection.connection_type, ConnectionType::Telnet);
+        assert_eq!(switch.connection.telnet_config.as_ref().unwrap().port, 23);
+    }
+
+    #[test]
+    fn skipped_and_invalid_sessions() {
+        let preview = load();
+        let names: Vec<&str> = preview.entries.iter().map(|e| e.source_name.as_str()).collect();
+        assert_eq!(names, vec!["my server[prod]", "console", "switch"]);
+        assert_eq!(preview.warnings, vec![
+            "Session raw: raw sessions are not supported",
+            "Session bigport: invalid port 65536",
+        ]);
+    }
+
+    #[test]
+    fn unescape_keeps_escaped_characters() {
+        assert_eq!(unescape(r#"a\\b\"c"#), r#"a\b"c"#);
+        assert_eq!(unescape(r"trailing\"), "trailing");
+        assert_eq!(decode_session_name("a%2Fb%zz%4"), "a/b%zz%4");
+    }
+}
@@ -0,0 +1,823 @@
+use crate::import::{ImportEntry, ImportPreview};
+use crate::models::{Connection, ForwardKind, JumpHost, PortForwardConfig, SshAdvanced, SshAuth, SshConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type ParseResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Include 嵌套上限，防止循环包含
+const MAX_INCLUDE_DEPTH: usize = 16;
+/// ProxyJump 递归解析上限，防止跳板机互相引用
+const MAX_JUMP_DEPTH: usize = 8;
+const DEFAULT_PORT: u16 = 22;
+
+/// 能映射到连接配置的单值选项（小写）；IdentityFile 与 LocalForward 单独累积
+const SUPPORTED: &[&str] = &[
+    "hostname",
+    "port",
+    "user",
+    "proxyjump",
+    "identityagent",
+    "serveraliveinterval",
+    "serveralivecountmax",
+    "connecttimeout",
+    "compression",
+];
+
+/// 一条配置指令
+#[derive(Debug, Clone)]
+struct Directive {
+    /// 原始拼写，用于报告
+    keyword: String,
+    args: Vec<String>,
+}
+
+impl Directive {
+    fn value(&self) -> &str {
+        self.args.first().map(String::as_str).unwrap_or("")
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}", self.keyword, self.args.join(" ")).trim_end().to_string()
+    }
+}
+
+/// 配置块的生效条件
+#[derive(Debug, Clone)]
+enum Criteria {
+    /// `Host` 行与 `Match originalhost`：按别名匹配
+    Host(Vec<String>),
+    /// `Match host`：按 HostName（未设置时为别名）匹配
+    MatchHost(Vec<String>),
+    /// 第一个 Host 之前的全局指令与 `Match all`
+    All,
+    /// 不支持的 Match 条件，整个块被忽略
+    Never,
+}
+
+struct Block {
+    criteria: Criteria,
+    directives: Vec<Directive>,
+}
+
+/// 某个别名的有效选项：与 ssh 一致，单值选项取第一次出现的值，IdentityFile / LocalForward 累积
+#[derive(Default)]
+struct Resolved {
+    values: Vec<(String, Directive)>,
+    identity_files: Vec<Directive>,
+    local_forwards: Vec<Directive>,
+}
+
+impl Resolved {
+    fn get(&self, key: &str) -> Option<&Directive> {
+        self.values.iter().find(|(k, _)| k == key).map(|(_, d)| d)
+    }
+
+    fn value(&self, key: &str) -> Option<&str> {
+        self.get(key).map(Directive::value)
+    }
+
+    fn hostname(&self, alias: &str) -> String {
+        self.value("hostname")
+            .map(|h| h.replace("%h", alias).replace("%%", "%"))
+            .unwrap_or_else(|| alias.to_string())
+    }
+
+    fn user(&self) -> String {
+        self.value("user").map(str::to_string).unwrap_or_else(local_user)
+    }
+
+    fn port(&self, warnings: &mut Vec<String>) -> Option<u16> {
+        let value = self.value("port")?;
+        match value.parse() {
+            Ok(port) => Some(port),
+            Err(_) => {
+                warnings.push(format!("Invalid Port {}, using {}", value, DEFAULT_PORT));
+                None
+            }
+        }
+    }
+}
+
+/// 解析后的 ssh_config
+struct SshConfigFile {
+    home: PathBuf,
+    blocks: Vec<Block>,
+    /// Host 行中不含通配符的别名，按出现顺序，每个生成一个连接
+    aliases: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// 解析 ssh_config 并生成导入预览；`path` 为空时读取 ~/.ssh/config
+pub fn preview(path: Option<&str>) -> ParseResult<ImportPreview> {
+    let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
+    let path = match path {
+        Some(path) => expand_home(path, &home),
+        None => home.join(".ssh").join("config"),
+    };
+    debug_log!("[IMPORT] Parsing ssh config {}", path.display());
+
+    let mut config = SshConfigFile {
+        home,
+        blocks: vec![Block { criteria: Criteria::All, directives: Vec::new() }],
+        aliases: Vec::new(),
+        warnings: Vec::new(),
+    };
+    config.parse_file(&path, 0)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let entries = config.aliases.iter().map(|alias| config.entry(alias)).collect();
+    Ok(ImportPreview {
+        entries,
+        warnings: config.warnings,
+    })
+}
+
+impl SshConfigFile {
+    fn current(&mut self) -> &mut Block {
+        self.blocks.last_mut().expect("at least one block")
+    }
+
+    fn parse_file(&mut self, path: &Path, depth: usize) -> ParseResult<()> {
+        let content = fs::read_to_string(path)?;
+        for (index, line) in content.lines().enumerate() {
+            let Some((keyword, args)) = split_line(line) else {
+                continue;
+            };
+            let origin = format!("{}:{}", path.display(), index + 1);
+            match keyword.to_ascii_lowercase().as_str() {
+                "host" => self.start_host(args),
+                "match" => self.start_match(args, &origin),
+                "include" => self.include(&args, depth, &origin),
+                _ => self.current().directives.push(Directive { keyword, args }),
+            }
+        }
+        Ok(())
+    }
+
+    fn start_host(&mut self, patterns: Vec<String>) {
+        for pattern in &patterns {
+            let concrete = !pattern.starts_with('!') && !pattern.contains(['*', '?']);
+            if concrete && !self.aliases.contains(pattern) {
+                self.aliases.push(pattern.clone());
+            }
+        }
+        self.blocks.push(Block { criteria: Criteria::Host(patterns), directives: Vec::new() });
+    }
+
+    /// 只支持 `Match host`、`Match originalhost` 与 `Match all`
+    fn start_match(&mut self, args: Vec<String>, origin: &str) {
+        let lowered: Vec<String> = args.iter().map(|a| a.to_ascii_lowercase()).collect();
+        let split = |list: &str| list.split(',').map(str::to_string).collect::<Vec<_>>();
+        let criteria = match lowered.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            ["all"] => Criteria::All,
+            ["host", _] => Criteria::MatchHost(split(&args[1])),
+            ["originalhost", _] => Criteria::Host(split(&args[1])),
+            _ => {
+                self.warnings.push(format!(
+                    "Unsupported `Match {}` at {}, its options are ignored",
+                    args.join(" "), origin
+                ));
+                Criteria::Never
+            }
+        };
+        self.blocks.push(Block { criteria, directives: Vec::new() });
+    }
+
+    fn include(&mut self, patterns: &[String], depth: usize, origin: &str) {
+        if depth >= MAX_INCLUDE_DEPTH {
+            self.warnings.push(format!("Include nested too deeply at {}", origin));
+            return;
+        }
+
+        let criteria = self.current().criteria.clone();
+        let blocks_before = self.blocks.len();
+        for pattern in patterns {
+            let paths = self.include_paths(pattern);
+            if paths.is_empty() {
+                self.warnings.push(format!("Include {} at {} matched no files", pattern, origin));
+            }
+            for path in paths {
+                if let Err(e) = self.parse_file(&path, depth + 1) {
+                    self.warnings.push(format!("Failed to read {}: {}", path.display(), e));
+                }
+            }
+        }
+
+        // 被包含文件中的 Host / Match 只作用于该文件，之后的指令仍属于外层块
+        if self.blocks.len() != blocks_before {
+            self.blocks.push(Block { criteria, directives: Vec::new() });
+        }
+    }
+
+    /// 展开 Include 路径：相对路径基于 ~/.ssh，文件名部分支持通配符，结果按名称排序
+    fn include_paths(&self, pattern: &str) -> Vec<PathBuf> {
+        let path = expand_home(pattern, &self.home);
+        let path = if path.is_absolute() { path } else { self.home.join(".ssh").join(path) };
+
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !name.contains(['*', '?']) {
+            return if path.is_file() { vec![path] } else { Vec::new() };
+        }
+
+        let Some(dir) = path.parent() else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter(|p| p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| glob(name, n)))
+                .collect())
+            .unwrap_or_default();
+        paths.sort();
+        paths
+    }
+
+    fn resolve(&self, alias: &str) -> Resolved {
+        let mut resolved = Resolved::default();
+        for block in &self.blocks {
+            let applies = match &block.criteria {
+                Criteria::All => true,
+                Criteria::Never => false,
+                Criteria::Host(patterns) => match_list(patterns, alias),
+                Criteria::MatchHost(patterns) => match_list(patterns, &resolved.hostname(alias)),
+            };
+            if !applies {
+                continue;
+            }
+            for directive in &block.directives {
+                let key = directive.keyword.to_ascii_lowercase();
+                match key.as_str() {
+                    "identityfile" => resolved.identity_files.push(directive.clone()),
+                    "localforward" => resolved.local_forwards.push(directive.clone()),
+                    _ if resolved.get(&key).is_none() => resolved.values.push((key, directive.clone())),
+                    _ => {}
+                }
+            }
+        }
+        resolved
+    }
+
+    fn entry(&self, alias: &str) -> ImportEntry {
+        let resolved = self.resolve(alias);
+        let mut warnings = Vec::new();
+
+        let host = resolved.hostname(alias);
+        let port = resolved.port(&mut warnings).unwrap_or(DEFAULT_PORT);
+        let username = resolved.user();
+        let auth = self.auth(&resolved, &host, &username, &mut warnings);
+        let jump_hosts = match resolved.value("proxyjump") {
+            Some(spec) if !spec.eq_ignore_ascii_case("none") => self.jump_chain(spec, 0, &mut warnings),
+            _ => Vec::new(),
+        };
+        let advanced = advanced(&resolved, &mut warnings);
+
+        let mut connection = Connection::new_ssh(alias.to_string(), SshConfig {
+            host,
+            port,
+            username,
+            auth,
+            jump_hosts,
+            advanced,
+        });
+        for directive in &resolved.local_forwards {
+            match local_forward(&directive.args) {
+                Ok(forward) => connection.port_forwards.push(forward),
+                Err(e) => warnings.push(format!("{}: {}", directive.describe(), e)),
+            }
+        }
+
+        let mut entry = ImportEntry::new(alias.to_string(), connection);
+        entry.unsupported = resolved.values.iter()
+            .filter(|(key, _)| !SUPPORTED.contains(&key.as_str()))
+            .map(|(_, directive)| directive.describe())
+            .collect();
+        entry.warnings = warnings;
+        entry
+    }
+
+    /// 第一个 IdentityFile 作为公钥认证，未配置时使用 SSH agent
+    fn auth(&self, resolved: &Resolved, host: &str, user: &str, warnings: &mut Vec<String>) -> SshAuth {
+        let mut identities = resolved.identity_files.iter()
+            .map(|d| self.expand_tokens(d.value(), host, user));
+        let Some(identity) = identities.next() else {
+            let socket_path = resolved.value("identityagent")
+                .filter(|v| !v.eq_ignore_ascii_case("none") && !v.eq_ignore_ascii_case("SSH_AUTH_SOCK"))
+                .map(|v| self.expand_tokens(v, host, user));
+            return SshAuth::Agent { socket_path };
+        };
+
+        let rest: Vec<String> = identities.collect();
+        if !rest.is_empty() {
+            warnings.push(format!("Only the first IdentityFile is used, ignored: {}", rest.join(", ")));
+        }
+        SshAuth::PublicKey {
+            private_key_path: identity,
+            passphrase: None,
+        }
+    }
+
+    /// 展开 ProxyJump；每一跳按其别名解析配置，跳板机自身的 ProxyJump 排在它前面
+    fn jump_chain(&self, spec: &str, depth: usize, warnings: &mut Vec<String>) -> Vec<JumpHost> {
+        if depth >= MAX_JUMP_DEPTH {
+            warnings.push(format!("ProxyJump chain too deep at {}", spec));
+            return Vec::new();
+        }
+
+        let mut chain = Vec::new();
+        for hop in spec.split(',').map(str::trim).filter(|h| !h.is_empty()) {
+            let (user, name, port) = match parse_hop(hop) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    warnings.push(format!("ProxyJump {}: {}", hop, e));
+                    continue;
+                }
+            };
+
+            let resolved = self.resolve(&name);
+            if let Some(inner) = resolved.value("proxyjump").filter(|v| !v.eq_ignore_ascii_case("none")) {
+                chain.extend(self.jump_chain(inner, depth + 1, warnings));
+            }
+
+            let host = resolved.hostname(&name);
+            let port = port.or_else(|| resolved.port(warnings)).unwrap_or(DEFAULT_PORT);
+            let username = user.unwrap_or_else(|| resolved.user());
+            let auth = self.auth(&resolved, &host, &username, warnings);
+            chain.push(JumpHost { host, port, username, auth });
+        }
+        chain
+    }
+
+    /// 展开路径中的 `~` 与 ssh 的 %d / %u / %h / %r / %% 记号
+    fn expand_tokens(&self, value: &str, host: &str, user: &str) -> String {
+        let mut out = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('d') => out.push_str(&self.home.to_string_lossy()),
+                Some('u') => out.push_str(&local_user()),
+                Some('h') => out.push_str(host),
+                Some('r') => out.push_str(user),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        expand_home(&out, &self.home).to_string_lossy().into_owned()
+    }
+}
+
+/// 心跳、连接超时与压缩映射到高级设置
+fn advanced(resolved: &Resolved, warnings: &mut Vec<String>) -> SshAdvanced {
+    let mut advanced = SshAdvanced::default();
+    let mut number = |key: &str| -> Option<u64> {
+        let directive = resolved.get(key)?;
+        directive.value().parse().map_err(|_| {
+            warnings.push(format!("Invalid {}, using default", directive.describe()));
+        }).ok()
+    };
+
+    if let Some(secs) = number("serveraliveinterval") {
+        advanced.keepalive_interval_secs = secs;
+    }
+    if let Some(max) = number("serveralivecountmax") {
+        advanced.keepalive_max = max as usize;
+    }
+    if let Some(secs) = number("connecttimeout") {
+        advanced.connect_timeout_secs = secs;
+    }
+    if let Some(value) = resolved.value("compression") {
+        advanced.compression = value.eq_ignore_ascii_case("yes");
+    }
+    advanced
+}
+
+/// 解析 `LocalForward [bind_address:]port host:hostport`
+fn local_forward(args: &[String]) -> Result<PortForwardConfig, String> {
+    let [listen, target] = args else {
+        return Err("expected listen address and target".to_string());
+    };
+    if listen.contains('/') || target.contains('/') {
+        return Err("Unix domain socket forwarding is not supported".to_string());
+    }
+
+    let (bind_host, bind_port) = split_host_port(listen)?;
+    let bind_host = match bind_host.as_deref() {
+        None => "127.0.0.1".to_string(),
+        Some("*") | Some("") => "0.0.0.0".to_string(),
+        Some(host) => host.to_string(),
+    };
+    let (target_host, target_port) = split_host_port(target)?;
+    let target_host = target_host.ok_or("target must be host:port")?;
+
+    Ok(PortForwardConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: ForwardKind::Local,
+        bind_host,
+        bind_port,
+        target_host,
+        target_port,
+    })
+}
+
+/// 解析 `[user@]host[:port]`，也接受 `ssh://` 前缀
+fn parse_hop(spec: &str) -> Result<(Option<String>, String, Option<u16>), String> {
+    let spec = spec.strip_prefix("ssh://").unwrap_or(spec);
+    let (user, host_port) = match spec.rsplit_once('@') {
+        Some((user, rest)) => (Some(user.to_string()), rest),
+        None => (None, spec),
+    };
+
+    // 未加方括号的 IPv6 地址含多个冒号，视为不带端口
+    if host_port.starts_with('[') || host_port.matches(':').count() == 1 {
+        let (host, port) = split_host_port(host_port)?;
+        let host = host.filter(|h| !h.is_empty()).ok_or("missing host")?;
+        Ok((user, host, Some(port)))
+    } else if host_port.is_empty() {
+        Err("missing host".to_string())
+    } else {
+        Ok((user, host_port.to_string(), None))
+    }
+}
+
+/// 拆分 `host:port`、`[v6]:port` 或单独的 `port`
+fn split_host_port(spec: &str) -> Result<(Option<String>, u16), String> {
+    let (host, port) = if let Some(rest) = spec.strip_prefix('[') {
+        let (host, port) = rest.split_once("]:").ok_or_else(|| format!("invalid address {}", spec))?;
+        (Some(host.to_string()), port)
+    } else if let Some((host, port)) = spec.rsplit_once(':') {
+        (Some(host.to_string()), port)
+    } else {
+        (None, spec)
+    };
+    let port = port.parse().map_err(|_| format!("invalid port in {}", spec))?;
+    Ok((host, port))
+}
+
+/// 拆分为关键字与参数，支持 `Keyword=value` 形式与双引号参数
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let end = line.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(line.len());
+    if end == 0 {
+        return None;
+    }
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    for c in rest.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Some((line[..end].to_string(), args))
+}
+
+/// ssh 风格的模式列表：任一正向模式匹配且没有 `!` 模式匹配
+fn match_list(patterns: &[String], text: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if glob(negated, text) {
+                return false;
+            }
+        } else if glob(pattern, text) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// `*` / `?` 通配符匹配，不区分大小写
+fn glob(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match p.split_first() {
+            None => t.is_empty(),
+            Some(('*', rest)) => (0..=t.len()).any(|i| matches(rest, &t[i..])),
+            Some(('?', rest)) => !t.is_empty() && matches(rest, &t[1..]),
+            Some((c, rest)) => t.first() == Some(c) && matches(rest, &t[1..]),
+        }
+    }
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    matches(&p, &t)
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None if path == "~" => home.to_path_buf(),
+        None => PathBuf::from(path),
+    }
+}
+
+/// 未配置 User 时 ssh 使用本机用户名
+fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在临时主目录的 ~/.ssh 下写入文件并解析 ~/.ssh/config
+    fn load(files: &[(&str, &str)]) -> (tempfile::TempDir, SshConfigFile) {
+        let home = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = home.path().join(".ssh").join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let mut config = SshConfigFile {
+            home: home.path().to_path_buf(),
+            blocks: vec![Block { criteria: Criteria::All, directives: Vec::new() }],
+            aliases: Vec::new(),
+            warnings: Vec::new(),
+        };
+        config.parse_file(&home.path().join(".ssh").join("config"), 0).unwrap();
+        (home, config)
+    }
+
+    fn ssh(entry: &ImportEntry) -> &SshConfig {
+        entry.connection.ssh_config.as_ref().unwrap()
+    }
+
+    fn identity(entry: &ImportEntry) -> &str {
+        match &ssh(entry).auth {
+            SshAuth::PublicKey { private_key_path, .. } => private_key_path,
+            other => panic!("expected public key auth, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn first_value_wins_across_host_blocks() {
+        let (_home, config) = load(&[("config", "\
+Compression yes
+
+Host web
+    User alice
+    Port 2200
+
+Host web *.example
+    User bob
+    Port 22
+    HostName %h.example.com
+")]);
+
+        let web = config.entry("web");
+        assert_eq!(ssh(&web).username, "alice");
+        assert_eq!(ssh(&web).port, 2200);
+        assert_eq!(ssh(&web).host, "web.example.com");
+        assert!(ssh(&web).advanced.compression);
+        assert_eq!(config.aliases, vec!["web"]);
+    }
+
+    #[test]
+    fn global_options_take_precedence_over_host_blocks() {
+        let (_home, config) = load(&[("config", "\
+Port 2022
+
+Host web
+    Port 2200
+")]);
+        assert_eq!(ssh(&config.entry("web")).port, 2022);
+    }
+
+    #[test]
+    fn match_blocks_follow_first_value_wins() {
+        let (_home, config) = load(&[("config", "\
+Host db
+    HostName db.internal
+
+Match host *.internal
+    User ops
+    Port 2222
+
+Match originalhost db
+    Port 3333
+    IdentityAgent ~/agent.sock
+
+Match exec \"true\"
+    User nobody
+")]);
+
+        let db = config.entry("db");
+        assert_eq!(ssh(&db).username, "ops");
+        assert_eq!(ssh(&db).port, 2222);
+        assert!(matches!(&ssh(&db).auth, SshAuth::Agent { socket_path: Some(path) } if path.ends_with("agent.sock")));
+        assert!(config.warnings.iter().any(|w| w.contains("Unsupported `Match exec true`")));
+    }
+
+    #[test]
+    fn negated_patterns_exclude_hosts() {
+        let (_home, config) = load(&[("config", "\
+Host bastion web
+    HostName %h.example
+
+Host * !bastion
+    User alice
+
+Host bastion
+    User jump
+")]);
+
+        assert_eq!(config.aliases, vec!["bastion", "web"]);
+        assert_eq!(ssh(&config.entry("web")).username, "alice");
+        assert_eq!(ssh(&config.entry("bastion")).username, "jump");
+
+        assert!(!match_list(&["!web".to_string()], "db"));
+        assert!(!match_list(&["*".to_string(), "!WEB".to_string()], "web"));
+        assert!(match_list(&["*".to_string(), "!web".to_string()], "db"));
+    }
+
+    #[test]
+    fn split_line_handles_quotes_and_equals() {
+        let split = |line| split_line(line).map(|(k, a)| (k, a.join("|")));
+        assert_eq!(split("Port=2200"), Some(("Port".to_string(), "2200".to_string())));
+        assert_eq!(split("  User = alice  "), Some(("User".to_string(), "alice".to_string())));
+        assert_eq!(
+            split("IdentityFile \"~/keys/my key\" other"),
+            Some(("IdentityFile".to_string(), "~/keys/my key|other".to_string())),
+        );
+        assert_eq!(split("ProxyCommand \"\""), Some(("ProxyCommand".to_string(), String::new())));
+        assert_eq!(split("# comment"), None);
+        assert_eq!(split("   "), None);
+        assert_eq!(split("=value"), None);
+    }
+
+    #[test]
+    fn quoted_arguments_and_key_value_directives() {
+        let (home, config) = load(&[("config", "\
+Host files
+    HostName=files.example
+    Port = 2200
+    IdentityFile \"~/keys/my key\"
+    LocalForward 8080 \"localhost:80\"
+")]);
+
+        let files = config.entry("files");
+        assert_eq!(ssh(&files).host, "files.example");
+        assert_eq!(ssh(&files).port, 2200);
+        assert_eq!(identity(&files), home.path().join("keys/my key").to_string_lossy());
+        let forward = &files.connection.port_forwards[0];
+        assert_eq!((forward.bind_host.as_str(), forward.bind_port), ("127.0.0.1", 8080));
+        assert_eq!((forward.target_host.as_str(), forward.target_port), ("localhost", 80));
+    }
+
+    #[test]
+    fn include_is_recursive_and_scoped() {
+        let (_home, config) = load(&[
+            ("config", "\
+Host outer
+    Include conf.d/*.conf missing.conf
+    User outeruser
+"),
+            ("conf.d/a.conf", "\
+Host a
+    HostName a.example
+    Include nested
+"),
+            ("conf.d/b.conf", "Host b\n    Port 2201\n"),
+            ("conf.d/ignored.txt", "Host ignored\n"),
+            ("nested", "Port 2200\n"),
+        ]);
+
+        assert_eq!(config.aliases, vec!["outer", "a", "b"]);
+        let a = config.entry("a");
+        assert_eq!(ssh(&a).host, "a.example");
+        assert_eq!(ssh(&a).port, 2200);
+        assert_ne!(ssh(&a).username, "outeruser");
+        assert_eq!(ssh(&config.entry("b")).port, 2201);
+        assert_eq!(ssh(&config.entry("outer")).username, "outeruser");
+        assert!(config.warnings.iter().any(|w| w.contains("missing.conf") && w.contains("matched no files")));
+    }
+
+    #[test]
+    fn include_depth_is_limited() {
+        let (_home, config) = load(&[
+            ("config", "Include loop.conf\n"),
+            ("loop.conf", "Host looped\n    Port 2200\nInclude loop.conf\n"),
+        ]);
+
+        assert_eq!(config.aliases, vec!["looped"]);
+        assert_eq!(config.warnings.iter().filter(|w| w.contains("Include nested too deeply")).count(), 1);
+        assert_eq!(ssh(&config.entry("looped")).port, 2200);
+    }
+
+    #[test]
+    fn nested_proxy_jumps_are_flattened() {
+        let (_home, config) = load(&[("config", "\
+Host target
+    ProxyJump mid
+
+Host explicit
+    ProxyJump ops@edge:2200,ssh://[2001:db8::1]:2022,2001:db8::2
+
+Host mid
+    HostName mid.example
+    User miduser
+    ProxyJump edge
+
+Host edge
+    HostName edge.example
+    User edgeuser
+    Port 2022
+
+Host direct
+    ProxyJump none
+")]);
+
+        let chain: Vec<(String, u16, String)> = ssh(&config.entry("target")).jump_hosts.iter()
+            .map(|j| (j.host.clone(), j.port, j.username.clone()))
+            .collect();
+        assert_eq!(chain, vec![
+            ("edge.example".to_string(), 2022, "edgeuser".to_string()),
+            ("mid.example".to_string(), 22, "miduser".to_string()),
+        ]);
+
+        let explicit: Vec<(String, u16)> = ssh(&config.entry("explicit")).jump_hosts.iter()
+            .map(|j| (j.host.clone(), j.port))
+            .collect();
+        assert_eq!(explicit, vec![
+            ("edge.example".to_string(), 2200),
+            ("2001:db8::1".to_string(), 2022),
+            ("2001:db8::2".to_string(), 22),
+        ]);
+        assert_eq!(ssh(&config.entry("explicit")).jump_hosts[0].username, "ops");
+        assert!(ssh(&config.entry("direct")).jump_hosts.is_empty());
+    }
+
+    #[test]
+    fn proxy_jump_cycles_stop_at_depth_limit() {
+        let (_home, config) = load(&[("config", "\
+Host a
+    ProxyJump b
+
+Host b
+    ProxyJump a
+")]);
+
+        let a = config.entry("a");
+        assert_eq!(ssh(&a).jump_hosts.len(), MAX_JUMP_DEPTH);
+        assert!(a.warnings.iter().any(|w| w.contains("ProxyJump chain too deep")));
+    }
+
+    #[test]
+    fn tokens_are_expanded() {
+        let (home, config) = load(&[("config", "\
+Host srv
+    HostName %h.example
+    User deploy
+    IdentityFile ~/.ssh/%r@%h
+    IdentityFile %d/keys/%u-100%%
+
+Host local
+    IdentityFile %d/keys/%u-100%%
+")]);
+
+        let srv = config.entry("srv");
+        let ssh_dir = home.path().join(".ssh");
+        assert_eq!(identity(&srv), ssh_dir.join("deploy@srv.example").to_string_lossy());
+        assert!(srv.warnings.iter().any(|w| w.contains("Only the first IdentityFile")));
+
+        let local = config.entry("local");
+        let expected = home.path().join("keys").join(format!("{}-100%", local_user()));
+        assert_eq!(identity(&local), expected.to_string_lossy());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::import::text::read_text;
+use crate::import::{ImportEntry, ImportPreview, ImportResult};
+use crate::models::{Connection, SshAuth, SshConfig, TelnetConfig};
+use serde_json::Value;
+use std::path::Path;
+
+/// 解析 Termius 导出的 JSON：顶层为主机数组，或包含 `hosts` 数组的对象（可嵌套在 `data` 下）
+pub fn preview(path: &str) -> ImportResult<ImportPreview> {
+    let text = read_text(Path::new(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    debug_log!("[IMPORT] Parsing Termius export {}", path);
+
+    let root: Value = serde_json::from_str(&text)?;
+    let hosts = root.as_array()
+        .or_else(|| root.get("hosts").and_then(Value::as_array))
+        .or_else(|| root.pointer("/data/hosts").and_then(Value::as_array))
+        .ok_or("no hosts found in Termius export")?;
+
+    let mut preview = ImportPreview::default();
+    for (index, host) in hosts.iter().enumerate() {
+        let name = string(host, &["label", "name"])
+            .or_else(|| string(host, &["address", "hostname", "host"]))
+            .unwrap_or_else(|| format!("Host {}", index + 1));
+        match entry(&name, host) {
+            Ok(entry) => preview.push(entry),
+            Err(e) => preview.warnings.push(format!("Host {}: {}", name, e)),
+        }
+    }
+    Ok(preview)
+}
+
+fn entry(name: &str, host: &Value) -> Result<ImportEntry, String> {
+    let address = string(host, &["address", "hostname", "host"]).ok_or("missing address")?;
+    let ssh = field(host, &["ssh_config", "ssh"]);
+    let telnet = field(host, &["telnet_config", "telnet"]);
+    let mut warnings = Vec::new();
+
+    // 同时配置了 SSH 与 Telnet 时优先导入 SSH
+    let connection = if ssh.is_some() || telnet.is_none() {
+        let ssh = ssh.unwrap_or(host);
+        let port = port(ssh).or_else(|| port(host)).unwrap_or(Ok(22))?;
+        let identity = field(ssh, &["identity"]).or_else(|| field(host, &["identity"]));
+        let user = identity.and_then(|i| string(i, &["username"]))
+            .or_else(|| string(ssh, &["username", "user"]))
+            .or_else(|| string(host, &["username", "user"]))
+            .unwrap_or_default();
+
+        let key = identity.and_then(|i| field(i, &["ssh_key", "key"]));
+        let auth = match key.and_then(|k| string(k, &["private_key_path", "path"])) {
+            Some(path) => SshAuth::PublicKey { private_key_path: path, passphrase: None },
+            None => {
+                // Termius 通常把私钥内容直接存在导出中，需另存为文件后再设置路径
+                if let Some(key) = key {
+                    let label = string(key, &["label", "name"]).unwrap_or_default();
+                    warnings.push(format!(
+                        "Key {} is stored inside Termius, save it to a file and set the key path after import",
+                        label
+                    ));
+                }
+                SshAuth::Agent { socket_path: None }
+            }
+        };
+        Connection::new_ssh(name.to_string(), SshConfig::new(address, port, user, auth))
+    } else {
+        let telnet = telnet.unwrap_or(host);
+        let port = port(telnet).unwrap_or(Ok(23))?;
+        Connection::new_telnet(name.to_string(), TelnetConfig::new(address, port))
+    };
+
+    let mut entry = ImportEntry::new(name.to_string(), connection);
+    if field(host, &["startup_snippet"]).is_some_and(|s| !s.is_null()) {
+        entry.unsupported.push("startup_snippet".to_string());
+    }
+    entry.warnings = warnings;
+    Ok(entry)
+}
+
+/// 第一个存在且非 null 的字段
+fn field<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().filter_map(|k| value.get(k)).find(|v| !v.is_null())
+}
+
+/// 第一个非空字符串字段
+fn string(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| value.get(k).and_then(Value::as_str))
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 端口可能是数字或字符串；字段不存在时返回 None
+fn port(value: &Value) -> Option<Result<u16, String>> {
+    let port = value.get("port").filter(|p| !p.is_null())?;
+    let parsed = match port {
+        Value::Number(n) => n.as_u64().and_then(|n| u16::try_from(n).ok()),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    Some(parsed.ok_or_else(|| format!("invalid port {}", port)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionType;
+
+    const EXPORT: &str = r#"{"data": {"hosts": [
+        {"label": "api", "address": "api.example", "startup_snippet": {"script": "uptime"},
+         "ssh_config": {"port": "2200", "identity": {"username": "deploy", "ssh_key": {"label": "prod key"}}}},
+        {"address": "10.0.0.5", "telnet_config": {"port": 2323}},
+        {"label": "keyfile", "address": "k.example", "username": "root",
+         "identity": {"ssh_key": {"private_key_path": "/home/me/.ssh/id_ed25519"}}},
+        {"label": "noaddr"},
+        {"label": "bad", "address": "bad.example", "port": 70000}
+    ]}}"#;
+
+    fn load(json: &str) -> ImportResult<ImportPreview> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("termius.json");
+        std::fs::write(&path, json).unwrap();
+        preview(path.to_str().unwrap())
+    }
+
+    #[test]
+    fn hosts_are_imported() {
+        let preview = load(EXPORT).unwrap();
+        let names: Vec<&str> = preview.entries.iter().map(|e| e.source_name.as_str()).collect();
+        assert_eq!(names, vec!["api", "10.0.0.5", "keyfile"]);
+
+        let api = &preview.entries[0];
+        let ssh = api.connection.ssh_config.as_ref().unwrap();
+        assert_eq!((ssh.port, ssh.username.as_str()), (2200, "deploy"));
+        assert!(matches!(ssh.auth, SshAuth::Agent { socket_path: None }));
+        assert!(api.warnings.iter().any(|w| w.contains("prod key")));
+        assert_eq!(api.unsupported, vec!["startup_snippet"]);
+
+        let telnet = &preview.entries[1].connection;
+        assert_eq!(telnet.connection_type, ConnectionType::Telnet);
+        assert_eq!(telnet.telnet_config.as_ref().unwrap().port, 2323);
+
+        let keyfile = preview.entries[2].connection.ssh_config.as_ref().unwrap();
+        assert_eq!(keyfile.username, "root");
+        assert!(matches!(&keyfile.auth, SshAuth::PublicKey { private_key_path, .. } if private_key_path.ends_with("id_ed25519")));
+    }
+
+    #[test]
+    fn bad_hosts_become_warnings() {
+        let preview = load(EXPORT).unwrap();
+        assert_eq!(preview.warnings, vec![
+            "Host noaddr: missing address",
+            "Host bad: invalid port 70000",
+        ]);
+        assert!(load(r#"{"groups": []}"#).is_err());
+        assert_eq!(load(r#"[{"address": "top.example"}]"#).unwrap().entries.len(), 1);
+    }
+}
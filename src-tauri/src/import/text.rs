@@ -0,0 +1,53 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 读取文本文件，识别 UTF-16（regedit、Xshell 默认导出格式）与 UTF-8 BOM
+pub(super) fn read_text(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    Ok(if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        decode_utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        decode_utf16(rest, u16::from_be_bytes)
+    } else {
+        let rest = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&bytes);
+        String::from_utf8_lossy(rest).into_owned()
+    })
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|c| from_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// INI 文件中的一节，保留键的原始顺序
+pub(super) struct Section {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl Section {
+    /// 按键名查找，不区分大小写
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// 解析 INI 格式；第一个节之前的键归入名称为空的节
+pub(super) fn parse_ini(text: &str) -> Vec<Section> {
+    let mut sections = vec![Section { name: String::new(), entries: Vec::new() }];
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push(Section { name: name.to_string(), entries: Vec::new() });
+        } else if let Some((key, value)) = line.split_once('=') {
+            let section = sections.last_mut().expect("at least one section");
+            section.entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
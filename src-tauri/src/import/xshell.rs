@@ -0,0 +1,189 @@
+use crate::import::text::{parse_ini, read_text, Section};
+use crate::import::{ImportEntry, ImportPreview, ImportResult};
+use crate::models::{Connection, SerialConfig, SshAuth, SshConfig, TelnetConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 会话目录递归上限
+const MAX_DEPTH: usize = 16;
+
+/// 解析 Xshell 会话：单个 .xsh 文件，或 Sessions 目录（含子目录）
+pub fn preview(path: &str) -> ImportResult<ImportPreview> {
+    let root = Path::new(path);
+    debug_log!("[IMPORT] Parsing Xshell sessions {}", root.display());
+
+    let files = if root.is_dir() {
+        let mut files = Vec::new();
+        collect(root, 0, &mut files)?;
+        files.sort();
+        files
+    } else {
+        vec![root.to_path_buf()]
+    };
+
+    let mut preview = ImportPreview::default();
+    for file in files {
+        // 目录导入时以相对路径（不含扩展名）作为来源名称，保留分组信息
+        let base = if root.is_dir() { root } else { root.parent().unwrap_or(root) };
+        let source_name = file.strip_prefix(base).unwrap_or(&file)
+            .with_extension("")
+            .to_string_lossy()
+            .into_owned();
+        let name = file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+
+        let result = read_text(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|text| entry(&name, &parse_ini(&text)));
+        match result {
+            Ok(mut entry) => {
+                entry.source_name = source_name;
+                preview.push(entry);
+            }
+            Err(e) => preview.warnings.push(format!("Session {}: {}", source_name, e)),
+        }
+    }
+    Ok(preview)
+}
+
+fn collect(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) -> ImportResult<()> {
+    if depth >= MAX_DEPTH {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(&path, depth + 1, files)?;
+        } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("xsh")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn entry(name: &str, sections: &[Section]) -> Result<ImportEntry, String> {
+    let section = |name: &str| sections.iter().find(|s| s.name.eq_ignore_ascii_case(name));
+    let connection_section = section("CONNECTION").ok_or("missing [CONNECTION] section")?;
+    let auth_section = section("CONNECTION:AUTHENTICATION");
+    let get = |key: &str| connection_section.get(key).filter(|v| !v.is_empty());
+
+    let protocol = get("Protocol").unwrap_or("SSH").to_ascii_uppercase();
+    let port = |default: u16| match get("Port") {
+        None => Ok(default),
+        Some(p) => p.parse::<u16>().map_err(|_| format!("invalid port {}", p)),
+    };
+    let host = || get("Host").map(str::to_string).ok_or("missing host".to_string());
+    let mut warnings = Vec::new();
+    let mut unsupported = Vec::new();
+
+    let connection = match protocol.as_str() {
+        "SSH" | "SFTP" => {
+            let user = auth_section.and_then(|s| s.get("UserName")).unwrap_or_default().to_string();
+            // UserKey 是 Xshell 密钥管理器中的名称而非文件路径
+            if let Some(key) = auth_section.and_then(|s| s.get("UserKey")).filter(|k| !k.is_empty()) {
+                warnings.push(format!(
+                    "User key {} is stored in Xshell, export it and set the key path after import",
+                    key
+                ));
+            }
+            let config = SshConfig::new(host()?, port(22)?, user, SshAuth::Agent { socket_path: None });
+            Connection::new_ssh(name.to_string(), config)
+        }
+        "TELNET" => Connection::new_telnet(name.to_string(), TelnetConfig::new(host()?, port(23)?)),
+        "SERIAL" => {
+            let serial = section("CONNECTION:SERIAL").ok_or("missing [CONNECTION:SERIAL] section")?;
+            let device = match serial.get("Port").filter(|p| !p.is_empty()).ok_or("missing serial port")? {
+                // 数字表示 COM 口编号
+                p if p.chars().all(|c| c.is_ascii_digit()) => format!("COM{}", p),
+                p => p.to_string(),
+            };
+            warnings.push(format!("Verify serial device {} after import", device));
+            let mut config = SerialConfig::new(device);
+            if let Some(speed) = serial.get("BaudRate").and_then(|s| s.parse::<u32>().ok()).filter(|s| *s > 0) {
+                config.baud_rate = speed;
+            }
+            if let Some(bits) = serial.get("DataBits").and_then(|b| b.parse::<u8>().ok()).filter(|b| (5..=8).contains(b)) {
+                config.data_bits = bits;
+            }
+            warnings.push("Parity, stop bits and flow control use defaults".to_string());
+            Connection::new_serial(name.to_string(), config)
+        }
+        other => return Err(format!("{} sessions are not supported", other)),
+    };
+
+    if let Some(proxy) = section("CONNECTION:PROXY").and_then(|s| s.get("Proxy")).filter(|p| !p.is_empty()) {
+        unsupported.push(format!("Proxy {}", proxy));
+    }
+
+    let mut entry = ImportEntry::new(name.to_string(), connection);
+    entry.unsupported = unsupported;
+    entry.warnings = warnings;
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionType;
+
+    const SSH_SESSION: &str = "\
+[CONNECTION]
+Host=srv.example
+Port=2222
+Protocol=SSH
+[CONNECTION:AUTHENTICATION]
+UserName=ops
+UserKey=id_rsa_2048
+[CONNECTION:PROXY]
+Proxy=corp
+";
+
+    /// Xshell 默认以带 BOM 的 UTF-16LE 保存会话文件
+    fn utf16le(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn utf16_session_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("服务器.xsh");
+        fs::write(&path, utf16le(SSH_SESSION)).unwrap();
+
+        let preview = preview(path.to_str().unwrap()).unwrap();
+        let entry = &preview.entries[0];
+        assert_eq!(entry.source_name, "服务器");
+        assert_eq!(entry.connection.name, "服务器");
+        let ssh = entry.connection.ssh_config.as_ref().unwrap();
+        assert_eq!((ssh.host.as_str(), ssh.port, ssh.username.as_str()), ("srv.example", 2222, "ops"));
+        assert!(entry.warnings.iter().any(|w| w.contains("id_rsa_2048")));
+        assert_eq!(entry.unsupported, vec!["Proxy corp"]);
+    }
+
+    #[test]
+    fn session_directory_keeps_relative_names() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("prod")).unwrap();
+        fs::write(dir.path().join("prod/api.xsh"), utf16le(SSH_SESSION)).unwrap();
+        fs::write(
+            dir.path().join("router.xsh"),
+            [&[0xEF, 0xBB, 0xBF][..], b"[CONNECTION]\r\nHost=10.0.0.1\r\nProtocol=TELNET\r\n"].concat(),
+        ).unwrap();
+        fs::write(
+            dir.path().join("console.xsh"),
+            utf16le("[CONNECTION]\nProtocol=SERIAL\n[CONNECTION:SERIAL]\nPort=3\nBaudRate=9600\nDataBits=7\n"),
+        ).unwrap();
+        fs::write(dir.path().join("broken.xsh"), utf16le("[SESSION]\nVersion=7.0\n")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "[CONNECTION]\nHost=ignored\n").unwrap();
+
+        let preview = preview(dir.path().to_str().unwrap()).unwrap();
+        let names: Vec<&str> = preview.entries.iter().map(|e| e.source_name.as_str()).collect();
+        assert_eq!(names, vec!["console", "prod/api", "router"]);
+
+        let console = preview.entries[0].connection.serial_config.as_ref().unwrap();
+        assert_eq!((console.device.as_str(), console.baud_rate, console.data_bits), ("COM3", 9600, 7));
+        assert_eq!(preview.entries[2].connection.connection_type, ConnectionType::Telnet);
+        assert_eq!(preview.entries[2].connection.telnet_config.as_ref().unwrap().port, 23);
+        assert_eq!(preview.warnings, vec!["Session broken: missing [CONNECTION] section"]);
+    }
+}
@@ -10,6 +10,12 @@ pub mod models;
 pub mod config;
 pub mod ssh;
 pub mod sftp;
+pub mod telnet;
+pub mod serial;
+pub mod ftp;
+pub mod remote_fs;
+pub mod vault;
+pub mod import;
 
 
 
@@ -17,6 +23,12 @@ use terminal::commands;
 use ssh::commands as ssh_commands;
 use config::commands as config_commands;
 use sftp::commands as sftp_commands;
+use telnet::commands as telnet_commands;
+use serial::commands as serial_commands;
+use ftp::commands as ftp_commands;
+use remote_fs::commands as fs_commands;
+use vault::commands as vault_commands;
+use import::commands as import_commands;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -24,60 +36,136 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
-            // 初始化本地终端会话管理
-            let sessions = terminal::create_session_map(); // Original was `terminal::create_session_map()`, new is `terminal::pty_manager::create_session_map()`
+            // 初始化终端会话注册表（本地、SSH、Telnet、串口共用）
+            let sessions = terminal::create_terminal_registry();
             app.manage(sessions);
             
-            // 初始化 SSH 会话管理
-            let ssh_sessions = std::sync::Arc::new(tokio::sync::Mutex::new(
-                std::collections::HashMap::<String, ssh::SshSession>::new()
-            ));
-            app.manage(ssh_sessions);
-            
             // 初始化连接配置存储
             let storage = config::ConnectionStorage::new()
                 .expect("Failed to initialize connection storage");
             let storage_state = std::sync::Arc::new(tokio::sync::Mutex::new(storage));
             app.manage(storage_state);
+
+            // 初始化凭据保险库（启动后处于锁定状态，需前端调用 unlock_vault）
+            let vault = vault::Vault::new()
+                .expect("Failed to initialize credential vault");
+            app.manage(std::sync::Arc::new(tokio::sync::Mutex::new(vault)));
             
-            // 初始化 SFTP 会话管理
-            let sftp_sessions = sftp::commands::create_sftp_session_map();
-            app.manage(sftp_sessions);
+            // 初始化文件系统注册表（SFTP / FTP / 本地文件会话）
+            let fs_registry = remote_fs::create_fs_registry();
+            app.manage(fs_registry);
+
+            // 初始化文件传输表（进度与取消）
+            let transfers = remote_fs::create_transfer_map();
+            app.manage(transfers);
+
+            // 初始化可续传传输记录
+            let journal = remote_fs::TransferJournal::new()
+                .expect("Failed to initialize transfer journal");
+            app.manage(std::sync::Arc::new(tokio::sync::Mutex::new(journal)));
+
+            // 初始化传输队列
+            let transfer_queue = remote_fs::create_transfer_queue();
+            app.manage(transfer_queue);
             
             // 初始化 SSH MFA 响应通道管理
             let mfa_channels = ssh::create_mfa_response_map();
             app.manage(mfa_channels);
+
+            // 初始化 SSH 主机密钥确认通道管理
+            let host_key_channels = ssh::create_host_key_response_map();
+            app.manage(host_key_channels);
+
+            // 初始化 SSH 连接池（终端与 SFTP 共享连接）
+            let transport_pool = ssh::create_transport_pool();
+            app.manage(transport_pool);
             
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
-            // 本地终端命令
+            // 终端命令（所有终端类型通用）
             commands::create_terminal,
             commands::write_to_terminal,
             commands::resize_terminal,
             commands::close_terminal,
+            commands::terminal_status,
             // SSH 命令
             ssh_commands::create_ssh_terminal,
             ssh_commands::test_ssh_connection,
-            ssh_commands::write_to_ssh_terminal,
-            ssh_commands::resize_ssh_terminal,
-            ssh_commands::close_ssh_terminal,
             // SSH MFA 命令
             ssh_commands::submit_ssh_mfa_response,
             ssh_commands::cancel_ssh_mfa,
+            // SSH 主机密钥确认命令
+            ssh_commands::submit_ssh_hostkey_response,
+            // SSH 端口转发命令
+            ssh_commands::ssh_forward_add,
+            ssh_commands::ssh_forward_list,
+            ssh_commands::ssh_forward_remove,
+            ssh_commands::ssh_socks_start,
+            ssh_commands::ssh_socks_stop,
+            // Telnet 命令
+            telnet_commands::create_telnet_terminal,
+            // 串口命令
+            serial_commands::list_serial_ports,
+            serial_commands::create_serial_terminal,
+            serial_commands::send_serial_break,
             // 配置管理命令
             config_commands::save_connection,
             config_commands::load_connections,
             config_commands::delete_connection,
             config_commands::update_connection,
+            config_commands::export_connections,
+            config_commands::import_connections,
+            config_commands::load_groups,
+            config_commands::save_group,
+            config_commands::delete_group,
+            config_commands::set_connection_favorite,
+            config_commands::mark_connection_used,
+            config_commands::search_connections,
+            // 凭据保险库命令
+            vault_commands::vault_status,
+            vault_commands::unlock_vault,
+            vault_commands::lock_vault,
+            // 连接导入命令
+            import_commands::import_preview,
+            import_commands::import_from_source,
             // SFTP 命令
             sftp_commands::sftp_connect,
-            sftp_commands::sftp_list_dir,
-            sftp_commands::sftp_download_file,
-            sftp_commands::sftp_upload_file,
-            sftp_commands::sftp_remove,
-            sftp_commands::sftp_create_dir,
-            sftp_commands::sftp_disconnect,
+            // FTP 命令
+            ftp_commands::ftp_connect,
+            // 文件系统命令（所有文件会话通用）
+            fs_commands::fs_open_local,
+            fs_commands::fs_list_dir,
+            fs_commands::fs_stat,
+            fs_commands::fs_read_range,
+            fs_commands::fs_download_file,
+            fs_commands::fs_upload_file,
+            fs_commands::fs_copy_file,
+            fs_commands::fs_download_dir,
+            fs_commands::fs_upload_dir,
+            fs_commands::sftp_cancel_transfer,
+            fs_commands::fs_list_interrupted_transfers,
+            fs_commands::fs_discard_interrupted_transfer,
+            fs_commands::fs_rename,
+            fs_commands::fs_remove,
+            fs_commands::fs_create_dir,
+            fs_commands::fs_chmod,
+            fs_commands::fs_chown,
+            fs_commands::fs_stat_details,
+            fs_commands::fs_read_link,
+            fs_commands::fs_canonicalize,
+            fs_commands::fs_symlink,
+            fs_commands::fs_disconnect,
+            // 传输队列命令
+            fs_commands::transfer_enqueue,
+            fs_commands::transfer_list,
+            fs_commands::transfer_status,
+            fs_commands::transfer_pause,
+            fs_commands::transfer_resume,
+            fs_commands::transfer_cancel,
+            fs_commands::transfer_retry,
+            fs_commands::transfer_clear_finished,
+            fs_commands::transfer_set_concurrency,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
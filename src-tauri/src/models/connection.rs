@@ -6,6 +6,7 @@ pub enum ConnectionType {
     Ssh,
     Telnet,
     Serial,
+    Ftp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +15,235 @@ pub struct SshConfig {
     pub port: u16,
     pub username: String,
     pub auth: SshAuth,
+    /// 跳板机链路（ProxyJump），按连接顺序排列，为空时直连
+    #[serde(default)]
+    pub jump_hosts: Vec<JumpHost>,
+    /// 高级设置（算法、心跳、连接超时），终端与 SFTP 统一使用
+    #[serde(default)]
+    pub advanced: SshAdvanced,
+}
+
+impl SshConfig {
+    /// 直连、使用默认高级设置
+    pub fn new(host: String, port: u16, username: String, auth: SshAuth) -> Self {
+        Self {
+            host,
+            port,
+            username,
+            auth,
+            jump_hosts: Vec::new(),
+            advanced: SshAdvanced::default(),
+        }
+    }
+}
+
+/// SSH 算法预设
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum AlgorithmPreset {
+    /// 兼容旧版服务器（如阿里云堡垒机），包含 SHA1 密钥交换
+    #[default]
+    Compatible,
+    /// 仅使用现代算法，排除所有基于 SHA1 的算法
+    Modern,
+}
+
+/// SSH 高级设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SshAdvanced {
+    pub preset: AlgorithmPreset,
+    /// 自定义算法列表（OpenSSH 名称，按优先级排列），为空时使用预设
+    pub kex: Vec<String>,
+    pub ciphers: Vec<String>,
+    pub macs: Vec<String>,
+    pub host_key_algorithms: Vec<String>,
+    /// 启用 zlib 压缩
+    pub compression: bool,
+    /// 心跳间隔（秒），0 表示不发送心跳
+    pub keepalive_interval_secs: u64,
+    /// 连续多少次心跳无响应后断开连接
+    pub keepalive_max: usize,
+    /// TCP 连接超时（秒），0 表示不限制
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for SshAdvanced {
+    fn default() -> Self {
+        Self {
+            preset: AlgorithmPreset::default(),
+            kex: Vec::new(),
+            ciphers: Vec::new(),
+            macs: Vec::new(),
+            host_key_algorithms: Vec::new(),
+            compression: false,
+            keepalive_interval_secs: 30,
+            keepalive_max: 6,
+            connect_timeout_secs: 30,
+        }
+    }
+}
+
+/// Telnet 连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelnetConfig {
+    pub host: String,
+    #[serde(default = "default_telnet_port")]
+    pub port: u16,
+    /// TTYPE 协商时上报的终端类型
+    #[serde(default = "default_terminal_type")]
+    pub terminal_type: String,
+    /// TCP 连接超时（秒），0 表示不限制
+    #[serde(default = "default_telnet_timeout")]
+    pub connect_timeout_secs: u64,
+}
+
+impl TelnetConfig {
+    pub fn new(host: String, port: u16) -> Self {
+        Self {
+            host,
+            port,
+            terminal_type: default_terminal_type(),
+            connect_timeout_secs: default_telnet_timeout(),
+        }
+    }
+}
+
+fn default_telnet_port() -> u16 {
+    23
+}
+
+fn default_terminal_type() -> String {
+    "xterm-256color".to_string()
+}
+
+fn default_telnet_timeout() -> u64 {
+    10
+}
+
+/// FTP 连接的加密方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum FtpSecurity {
+    /// 明文 FTP
+    #[default]
+    None,
+    /// 显式 FTPS（AUTH TLS，通常使用 21 端口）
+    Explicit,
+    /// 隐式 FTPS（连接即 TLS，通常使用 990 端口）
+    Implicit,
+}
+
+/// FTP / FTPS 连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FtpConfig {
+    pub host: String,
+    #[serde(default = "default_ftp_port")]
+    pub port: u16,
+    /// 为空时匿名登录
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub security: FtpSecurity,
+    /// 被动模式（PASV），关闭时使用主动模式（PORT）
+    #[serde(default = "default_passive")]
+    pub passive: bool,
+}
+
+impl FtpConfig {
+    /// 明文 FTP、被动模式、不保存密码
+    pub fn new(host: String, port: u16, username: String) -> Self {
+        Self {
+            host,
+            port,
+            username,
+            password: String::new(),
+            security: FtpSecurity::default(),
+            passive: default_passive(),
+        }
+    }
+}
+
+fn default_ftp_port() -> u16 {
+    21
+}
+
+fn default_passive() -> bool {
+    true
+}
+
+/// 串口校验位
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+/// 串口流控
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    /// XON/XOFF
+    Software,
+    /// RTS/CTS
+    Hardware,
+}
+
+/// 串口连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialConfig {
+    /// 设备路径（如 /dev/ttyUSB0、COM3）
+    pub device: String,
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    /// 数据位（5 - 8）
+    #[serde(default = "default_data_bits")]
+    pub data_bits: u8,
+    #[serde(default)]
+    pub parity: SerialParity,
+    /// 停止位（1 或 2）
+    #[serde(default = "default_stop_bits")]
+    pub stop_bits: u8,
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+}
+
+impl SerialConfig {
+    /// 115200 8N1、无流控
+    pub fn new(device: String) -> Self {
+        Self {
+            device,
+            baud_rate: default_baud_rate(),
+            data_bits: default_data_bits(),
+            parity: SerialParity::default(),
+            stop_bits: default_stop_bits(),
+            flow_control: SerialFlowControl::default(),
+        }
+    }
+}
+
+fn default_baud_rate() -> u32 {
+    115_200
+}
+
+fn default_data_bits() -> u8 {
+    8
+}
+
+fn default_stop_bits() -> u8 {
+    1
+}
+
+/// 跳板机配置，每一跳使用独立的认证方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JumpHost {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth: SshAuth,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +253,61 @@ pub enum SshAuth {
         private_key_path: String,
         passphrase: Option<String>,
     },
+    /// 使用 SSH agent 中的身份（socket_path 为空时使用 SSH_AUTH_SOCK）
+    Agent {
+        socket_path: Option<String>,
+    },
+}
+
+/// 端口转发类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ForwardKind {
+    /// -L：本地 bind_host:bind_port → 经 SSH 主机访问 target_host:target_port
+    #[default]
+    Local,
+    /// -R：SSH 主机上的 bind_host:bind_port → 本机访问 target_host:target_port
+    Remote,
+    /// -D：本地 bind_host:bind_port 上的 SOCKS5 代理，目标由客户端指定（忽略 target_*）
+    Dynamic,
+}
+
+/// 端口转发配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortForwardConfig {
+    pub id: String,
+    #[serde(default)]
+    pub kind: ForwardKind,
+    pub bind_host: String,
+    pub bind_port: u16,
+    #[serde(default)]
+    pub target_host: String,
+    #[serde(default)]
+    pub target_port: u16,
+}
+
+/// 断线自动重连策略：指数退避 + 随机抖动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectPolicy {
+    /// 最大重连次数，用尽后终端退出
+    pub max_attempts: u32,
+    /// 第一次重连前的等待时间，之后每次翻倍
+    pub initial_delay_ms: u64,
+    /// 等待时间上限
+    pub max_delay_ms: u64,
+    /// 抖动比例（0.0 - 1.0），实际等待时间在 delay * (1 ± jitter) 之间
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay_ms: 1000,
+            max_delay_ms: 30_000,
+            jitter: 0.2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,27 +316,96 @@ pub struct Connection {
     pub name: String,
     pub connection_type: ConnectionType,
     pub ssh_config: Option<SshConfig>,
-    // 其他协议配置可以后续添加
-    // pub telnet_config: Option<TelnetConfig>,
-    // pub serial_config: Option<SerialConfig>,
+    /// 连接建立后自动启动的端口转发
+    #[serde(default)]
+    pub port_forwards: Vec<PortForwardConfig>,
+    /// 断线自动重连策略，为空时不重连
+    #[serde(default)]
+    pub reconnect: Option<ReconnectPolicy>,
+    #[serde(default)]
+    pub telnet_config: Option<TelnetConfig>,
+    #[serde(default)]
+    pub serial_config: Option<SerialConfig>,
+    #[serde(default)]
+    pub ftp_config: Option<FtpConfig>,
+    /// 所属分组，为空时位于根目录
+    #[serde(default)]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    /// 颜色标记（如 `#22d3ee`）
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 收藏时间（Unix 秒），为空表示未收藏
+    #[serde(default)]
+    pub favorited_at: Option<u64>,
+    /// 最近一次连接的时间（Unix 秒）
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
 }
 
 impl Connection {
-    pub fn new_local(name: String) -> Self {
+    fn base(name: String, connection_type: ConnectionType) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             name,
-            connection_type: ConnectionType::Local,
+            connection_type,
             ssh_config: None,
+            port_forwards: Vec::new(),
+            reconnect: None,
+            telnet_config: None,
+            serial_config: None,
+            ftp_config: None,
+            group_id: None,
+            tags: Vec::new(),
+            notes: String::new(),
+            color: None,
+            favorited_at: None,
+            last_used_at: None,
         }
     }
 
+    pub fn new_local(name: String) -> Self {
+        Self::base(name, ConnectionType::Local)
+    }
+
     pub fn new_ssh(name: String, ssh_config: SshConfig) -> Self {
         Self {
-            id: uuid::Uuid::new_v4().to_string(),
-            name,
-            connection_type: ConnectionType::Ssh,
             ssh_config: Some(ssh_config),
+            ..Self::base(name, ConnectionType::Ssh)
+        }
+    }
+
+    pub fn new_telnet(name: String, telnet_config: TelnetConfig) -> Self {
+        Self {
+            telnet_config: Some(telnet_config),
+            ..Self::base(name, ConnectionType::Telnet)
+        }
+    }
+
+    pub fn new_serial(name: String, serial_config: SerialConfig) -> Self {
+        Self {
+            serial_config: Some(serial_config),
+            ..Self::base(name, ConnectionType::Serial)
+        }
+    }
+
+    pub fn new_ftp(name: String, ftp_config: FtpConfig) -> Self {
+        Self {
+            ftp_config: Some(ftp_config),
+            ..Self::base(name, ConnectionType::Ftp)
         }
     }
 }
+
+/// 连接分组（文件夹），通过 parent_id 组成层级
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionGroup {
+    pub id: String,
+    pub name: String,
+    /// 上级分组，为空时位于根目录
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
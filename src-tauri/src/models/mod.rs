@@ -1,4 +1,4 @@
 mod connection;
 
-pub use connection::{ConnectionType, SshConfig, SshAuth, Connection};
+pub use connection::{ConnectionType, SshConfig, SshAuth, JumpHost, AlgorithmPreset, SshAdvanced, ForwardKind, PortForwardConfig, ReconnectPolicy, TelnetConfig, SerialConfig, SerialParity, SerialFlowControl, FtpConfig, FtpSecurity, Connection, ConnectionGroup};
 
@@ -0,0 +1,205 @@
+use crate::remote_fs::{FileDetails, RemoteFs};
+use std::collections::HashMap;
+
+/// 读取 /etc/passwd、/etc/group 的最大字节数
+const ID_FILE_LIMIT: u64 = 4 * 1024 * 1024;
+
+/// 解析 chmod 参数：八进制（`755`、`0644`）或符号形式（`u+x,go-w`、`a=rX`）
+///
+/// 符号形式基于 `current` 计算；`X` 仅对目录或已有执行权限的文件添加执行位。
+pub fn parse_mode(spec: &str, current: u32, is_dir: bool) -> Result<u32, String> {
+    let spec = spec.trim();
+    if !spec.is_empty() && spec.chars().all(|c| c.is_digit(8)) {
+        let mode = u32::from_str_radix(spec, 8).map_err(|e| e.to_string())?;
+        if mode > 0o7777 {
+            return Err(format!("Invalid mode: {}", spec));
+        }
+        return Ok(mode);
+    }
+
+    let mut mode = current & 0o7777;
+    for clause in spec.split(',') {
+        let ops_start = clause.find(['+', '-', '='])
+            .ok_or_else(|| format!("Invalid mode: {}", spec))?;
+        let (who, mut rest) = clause.split_at(ops_start);
+
+        let mut mask = 0;
+        for c in who.chars() {
+            mask |= match c {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => return Err(format!("Invalid mode: {}", spec)),
+            };
+        }
+        if mask == 0 {
+            mask = 0o7777;
+        }
+
+        // 一个子句可包含多个操作，如 u+x-w
+        while let Some(op) = rest.chars().next() {
+            let perms_end = rest[1..].find(['+', '-', '=']).map_or(rest.len(), |i| i + 1);
+            let perms = &rest[1..perms_end];
+            rest = &rest[perms_end..];
+
+            let mut bits = 0;
+            for c in perms.chars() {
+                bits |= match c {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    'x' => 0o111,
+                    'X' if is_dir || mode & 0o111 != 0 => 0o111,
+                    'X' => 0,
+                    's' => 0o6000,
+                    't' => 0o1000,
+                    _ => return Err(format!("Invalid mode: {}", spec)),
+                };
+            }
+            bits &= mask;
+
+            match op {
+                '+' => mode |= bits,
+                '-' => mode &= !bits,
+                _ => mode = (mode & !mask) | bits,
+            }
+        }
+    }
+    Ok(mode)
+}
+
+/// 从 /etc/passwd 或 /etc/group 格式的文件读取 ID 与名称的对应关系
+async fn load_id_file(fs: &dyn RemoteFs, path: &str) -> HashMap<u32, String> {
+    let data = match fs.read_range(path, 0, ID_FILE_LIMIT).await {
+        Ok(data) => data,
+        Err(e) => {
+            debug_log!("[FS-ATTRS] Cannot read {}: {}", path, e);
+            return HashMap::new();
+        }
+    };
+
+    // name:password:id:...
+    String::from_utf8_lossy(&data)
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+/// 目标系统上的用户与组
+pub struct IdMaps {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl IdMaps {
+    /// 读取目标系统的 /etc/passwd 与 /etc/group，无法读取时为空
+    pub async fn load(fs: &dyn RemoteFs) -> Self {
+        Self {
+            users: load_id_file(fs, "/etc/passwd").await,
+            groups: load_id_file(fs, "/etc/group").await,
+        }
+    }
+
+    /// 补全详细信息中缺失的属主与属组名
+    pub fn fill_names(&self, details: &mut FileDetails) {
+        if details.owner.is_none() {
+            details.owner = details.uid.and_then(|uid| self.users.get(&uid).cloned());
+        }
+        if details.group.is_none() {
+            details.group = details.gid.and_then(|gid| self.groups.get(&gid).cloned());
+        }
+    }
+
+    /// 用户名或数字 uid
+    pub fn resolve_user(&self, name: &str) -> Result<u32, String> {
+        resolve(&self.users, name).ok_or_else(|| format!("Unknown user: {}", name))
+    }
+
+    /// 组名或数字 gid
+    pub fn resolve_group(&self, name: &str) -> Result<u32, String> {
+        resolve(&self.groups, name).ok_or_else(|| format!("Unknown group: {}", name))
+    }
+}
+
+fn resolve(map: &HashMap<u32, String>, name: &str) -> Option<u32> {
+    name.parse().ok().or_else(|| {
+        map.iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(id, _)| *id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn octal_modes_are_bounded() {
+        assert_eq!(parse_mode("755", 0, false), Ok(0o755));
+        assert_eq!(parse_mode(" 0644 ", 0o777, false), Ok(0o644));
+        assert_eq!(parse_mode("7777", 0, false), Ok(0o7777));
+        assert_eq!(parse_mode("0", 0o755, false), Ok(0));
+        assert!(parse_mode("10000", 0, false).is_err());
+        assert!(parse_mode("77777777777777", 0, false).is_err());
+        assert!(parse_mode("789", 0, false).is_err());
+    }
+
+    #[test]
+    fn symbolic_modes_ignore_file_type_bits() {
+        assert_eq!(parse_mode("u+x", 0o100644, false), Ok(0o744));
+        assert_eq!(parse_mode("a-w", 0o040755, true), Ok(0o555));
+    }
+
+    #[test]
+    fn assignment_clears_unlisted_bits() {
+        assert_eq!(parse_mode("u=rwx,go=", 0o644, false), Ok(0o700));
+        assert_eq!(parse_mode("go=", 0o3775, true), Ok(0o700));
+        assert_eq!(parse_mode("a=r", 0o6777, false), Ok(0o444));
+        assert_eq!(parse_mode("=rx", 0o600, false), Ok(0o555));
+        assert_eq!(parse_mode("u=rw,g=r,o=r", 0o4777, false), Ok(0o644));
+    }
+
+    #[test]
+    fn multiple_operations_in_one_clause() {
+        assert_eq!(parse_mode("u+x-w", 0o644, false), Ok(0o544));
+        assert_eq!(parse_mode("go-rwx+r", 0o777, false), Ok(0o744));
+        assert_eq!(parse_mode("ug+rw,o-rwx", 0o005, false), Ok(0o660));
+    }
+
+    #[test]
+    fn capital_x_depends_on_directory_or_existing_execute() {
+        assert_eq!(parse_mode("a+X", 0o644, false), Ok(0o644));
+        assert_eq!(parse_mode("a+X", 0o644, true), Ok(0o755));
+        assert_eq!(parse_mode("a+X", 0o744, false), Ok(0o755));
+        assert_eq!(parse_mode("go+X", 0o610, false), Ok(0o611));
+        assert_eq!(parse_mode("a=rX", 0o600, true), Ok(0o555));
+        assert_eq!(parse_mode("a=rX", 0o600, false), Ok(0o444));
+    }
+
+    #[test]
+    fn special_bits_are_masked_by_who() {
+        assert_eq!(parse_mode("+t", 0o777, true), Ok(0o1777));
+        assert_eq!(parse_mode("o+t", 0o777, true), Ok(0o1777));
+        assert_eq!(parse_mode("u+t", 0o777, true), Ok(0o777));
+        assert_eq!(parse_mode("+s", 0o755, false), Ok(0o6755));
+        assert_eq!(parse_mode("u+s", 0o755, false), Ok(0o4755));
+        assert_eq!(parse_mode("g+s", 0o755, true), Ok(0o2755));
+        assert_eq!(parse_mode("o+s", 0o755, false), Ok(0o755));
+        assert_eq!(parse_mode("a-st", 0o7755, false), Ok(0o755));
+        assert_eq!(parse_mode("u-s", 0o6755, false), Ok(0o2755));
+    }
+
+    #[test]
+    fn malformed_specs_are_rejected() {
+        for spec in ["", "   ", "u", "ug", "u+z", "x+r", "u+x,", ",u+x", "0o755", "u+x;g+w", "rwx"] {
+            assert!(parse_mode(spec, 0o644, false).is_err(), "{:?} should be rejected", spec);
+        }
+        assert_eq!(parse_mode("u+", 0o644, false), Ok(0o644));
+    }
+}
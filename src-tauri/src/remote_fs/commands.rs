@@ -0,0 +1,634 @@
+use crate::remote_fs::attrs::{self, IdMaps};
+use crate::remote_fs::{
+    recursive, transfer, FileDetails, FileEntry, InterruptedTransfer, JobStatus, LocalFs, QueueContext, QueuedTransfer,
+    RemoteFs, TransferDirection, TransferJob, TransferJournalState, TransferMap, TransferQueueState,
+    TreeJob, TreeOptions, TreeSummary,
+};
+use tauri::{AppHandle, State};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use std::collections::HashMap;
+
+/// 按会话 ID 索引的文件系统注册表，sftp_connect / ftp_connect 等连接命令在此注册
+pub type FsRegistry = Arc<Mutex<HashMap<String, Arc<dyn RemoteFs>>>>;
+
+/// 创建文件系统注册表
+pub fn create_fs_registry() -> FsRegistry {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// 取出会话对应的文件系统，随即释放注册表锁，避免长时间传输阻塞其他会话
+async fn lookup(registry: &FsRegistry, id: &str) -> Result<Arc<dyn RemoteFs>, String> {
+    registry.lock().await
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("File session {} not found", id))
+}
+
+/// 注册本地文件系统会话（双栏传输的本机一侧）
+#[tauri::command]
+pub async fn fs_open_local(
+    session_id: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_open_local: id={}", session_id);
+    registry.lock().await
+        .entry(session_id)
+        .or_insert_with(|| Arc::new(LocalFs));
+    Ok(())
+}
+
+/// 列出目录内容
+#[tauri::command]
+pub async fn fs_list_dir(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<Vec<FileEntry>, String> {
+    debug_log!("[FS-CMD] fs_list_dir: id={}, path={}", id, path);
+
+    let fs = lookup(&registry, &id).await?;
+    let entries = fs.list_dir(&path).await
+        .map_err(|e| format!("Failed to list directory: {}", e))?;
+
+    debug_log!("[FS-CMD] Listed {} entries in {}", entries.len(), path);
+    Ok(entries)
+}
+
+/// 获取文件信息
+#[tauri::command]
+pub async fn fs_stat(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<FileEntry, String> {
+    let fs = lookup(&registry, &id).await?;
+    fs.stat(&path).await
+        .map_err(|e| format!("Failed to stat {}: {}", path, e))
+}
+
+/// 读取文件的一段内容
+#[tauri::command]
+pub async fn fs_read_range(
+    id: String,
+    path: String,
+    offset: u64,
+    length: u64,
+    registry: State<'_, FsRegistry>,
+) -> Result<Vec<u8>, String> {
+    let fs = lookup(&registry, &id).await?;
+    fs.read_range(&path, offset, length).await
+        .map_err(|e| format!("Failed to read file: {}", e))
+}
+
+/// 下载文件到本地，进度通过 `sftp-transfer-progress` 事件发送
+///
+/// `resume` 为 true 时先写入 `<local_path>.part`，中断后以相同参数再次调用即可续传。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fs_download_file(
+    id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+    resume: Option<bool>,
+    verify: Option<bool>,
+    connection_id: Option<String>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_download_file: id={}, remote={}, local={}", id, remote_path, local_path);
+
+    let fs = lookup(&registry, &id).await?;
+    let job = TransferJob {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        src: fs.as_ref(),
+        src_path: &remote_path,
+        dst: &LocalFs,
+        dst_path: &local_path,
+        resume: resume.unwrap_or(false),
+        verify: verify.unwrap_or(false),
+        progress: None,
+    };
+    let record = job.resume.then(|| InterruptedTransfer {
+        transfer_id: job.id.clone(),
+        direction: TransferDirection::Download,
+        connection_id,
+        remote_path: remote_path.clone(),
+        local_path: local_path.clone(),
+        total: None,
+        updated_at: 0,
+    });
+    let bytes = transfer::run_journaled(&transfers, &journal, app_handle, job, record).await
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    debug_log!("[FS-CMD] Downloaded {} bytes to {}", bytes, local_path);
+    Ok(())
+}
+
+/// 上传本地文件，进度通过 `sftp-transfer-progress` 事件发送
+///
+/// `resume` 为 true 时先写入远程 `<remote_path>.part`，中断后以相同参数再次调用即可续传。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fs_upload_file(
+    id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+    resume: Option<bool>,
+    verify: Option<bool>,
+    connection_id: Option<String>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_upload_file: id={}, local={}, remote={}", id, local_path, remote_path);
+
+    let fs = lookup(&registry, &id).await?;
+    let job = TransferJob {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        src: &LocalFs,
+        src_path: &local_path,
+        dst: fs.as_ref(),
+        dst_path: &remote_path,
+        resume: resume.unwrap_or(false),
+        verify: verify.unwrap_or(false),
+        progress: None,
+    };
+    let record = job.resume.then(|| InterruptedTransfer {
+        transfer_id: job.id.clone(),
+        direction: TransferDirection::Upload,
+        connection_id,
+        remote_path: remote_path.clone(),
+        local_path: local_path.clone(),
+        total: None,
+        updated_at: 0,
+    });
+    let bytes = transfer::run_journaled(&transfers, &journal, app_handle, job, record).await
+        .map_err(|e| format!("Upload failed: {}", e))?;
+
+    debug_log!("[FS-CMD] Uploaded {} bytes to {}", bytes, remote_path);
+    Ok(())
+}
+
+/// 在两个会话之间复制文件（双栏传输，两端可以是任意协议）
+///
+/// 会话 ID 在重启后失效，因此会话间复制支持续传但不写入传输记录。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fs_copy_file(
+    src_id: String,
+    src_path: String,
+    dst_id: String,
+    dst_path: String,
+    transfer_id: Option<String>,
+    resume: Option<bool>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    debug_log!("[FS-CMD] fs_copy_file: {}:{} -> {}:{}", src_id, src_path, dst_id, dst_path);
+
+    let src = lookup(&registry, &src_id).await?;
+    let dst = lookup(&registry, &dst_id).await?;
+    let job = TransferJob {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        src: src.as_ref(),
+        src_path: &src_path,
+        dst: dst.as_ref(),
+        dst_path: &dst_path,
+        resume: resume.unwrap_or(false),
+        verify: false,
+        progress: None,
+    };
+    let bytes = transfer::run(&transfers, app_handle, job).await
+        .map_err(|e| format!("Copy failed: {}", e))?;
+
+    debug_log!("[FS-CMD] Copied {} bytes", bytes);
+    Ok(bytes)
+}
+
+fn queue_context(
+    queue: &State<'_, TransferQueueState>,
+    registry: &State<'_, FsRegistry>,
+    transfers: &State<'_, TransferMap>,
+    journal: &State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> QueueContext {
+    QueueContext {
+        queue: queue.inner().clone(),
+        registry: registry.inner().clone(),
+        transfers: transfers.inner().clone(),
+        journal: journal.inner().clone(),
+        app_handle,
+    }
+}
+
+/// 把上传或下载加入传输队列，立即返回任务 ID
+///
+/// 传入未完成传输的 `transfer_id` 可从断点继续。状态变化通过 `transfer-job-updated` 事件发送。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer_enqueue(
+    session_id: String,
+    direction: TransferDirection,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+    connection_id: Option<String>,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    debug_log!("[FS-CMD] transfer_enqueue: session={}, {:?} remote={}, local={}", session_id, direction, remote_path, local_path);
+
+    let job = QueuedTransfer {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        session_id,
+        direction,
+        remote_path,
+        local_path,
+        connection_id,
+        status: JobStatus::Queued,
+        bytes: 0,
+        total: None,
+        error: None,
+        attempts: 0,
+    };
+    let ctx = queue_context(&queue, &registry, &transfers, &journal, app_handle);
+    Ok(ctx.enqueue(job).await)
+}
+
+/// 列出队列中的所有任务
+#[tauri::command]
+pub async fn transfer_list(
+    queue: State<'_, TransferQueueState>,
+) -> Result<Vec<QueuedTransfer>, String> {
+    Ok(queue.lock().await.list())
+}
+
+/// 查询单个任务
+#[tauri::command]
+pub async fn transfer_status(
+    id: String,
+    queue: State<'_, TransferQueueState>,
+) -> Result<QueuedTransfer, String> {
+    queue.lock().await
+        .get(&id)
+        .ok_or_else(|| format!("Transfer {} not found", id))
+}
+
+/// 暂停任务
+#[tauri::command]
+pub async fn transfer_pause(
+    id: String,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] transfer_pause: {}", id);
+    queue_context(&queue, &registry, &transfers, &journal, app_handle).pause(&id).await
+}
+
+/// 恢复已暂停的任务
+#[tauri::command]
+pub async fn transfer_resume(
+    id: String,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] transfer_resume: {}", id);
+    queue_context(&queue, &registry, &transfers, &journal, app_handle).resume(&id).await
+}
+
+/// 取消任务
+#[tauri::command]
+pub async fn transfer_cancel(
+    id: String,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] transfer_cancel: {}", id);
+    queue_context(&queue, &registry, &transfers, &journal, app_handle).cancel(&id).await
+}
+
+/// 重试失败或已取消的任务
+#[tauri::command]
+pub async fn transfer_retry(
+    id: String,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] transfer_retry: {}", id);
+    queue_context(&queue, &registry, &transfers, &journal, app_handle).retry(&id).await
+}
+
+/// 移除已完成、失败或已取消的任务
+#[tauri::command]
+pub async fn transfer_clear_finished(
+    queue: State<'_, TransferQueueState>,
+) -> Result<(), String> {
+    queue.lock().await.clear_finished();
+    Ok(())
+}
+
+/// 设置每个会话同时进行的传输数
+#[tauri::command]
+pub async fn transfer_set_concurrency(
+    limit: usize,
+    queue: State<'_, TransferQueueState>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] transfer_set_concurrency: {}", limit);
+    queue_context(&queue, &registry, &transfers, &journal, app_handle).set_concurrency(limit).await;
+    Ok(())
+}
+
+/// 列出未完成的可续传传输（含上次运行时中断的）
+#[tauri::command]
+pub async fn fs_list_interrupted_transfers(
+    transfers: State<'_, TransferMap>,
+    journal: State<'_, TransferJournalState>,
+) -> Result<Vec<InterruptedTransfer>, String> {
+    let records = journal.lock().await.load()
+        .map_err(|e| format!("Failed to load transfer journal: {}", e))?;
+
+    // 排除正在进行的传输
+    let active = transfers.lock().await;
+    Ok(records.into_iter()
+        .filter(|record| !active.contains_key(&record.transfer_id))
+        .collect())
+}
+
+/// 放弃一个未完成的传输（保留已传输的 .part 文件）
+#[tauri::command]
+pub async fn fs_discard_interrupted_transfer(
+    transfer_id: String,
+    journal: State<'_, TransferJournalState>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_discard_interrupted_transfer: {}", transfer_id);
+    journal.lock().await.remove(&transfer_id)
+        .map_err(|e| format!("Failed to update transfer journal: {}", e))
+}
+
+/// 取消进行中的传输
+#[tauri::command]
+pub async fn sftp_cancel_transfer(
+    transfer_id: String,
+    transfers: State<'_, TransferMap>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] sftp_cancel_transfer: {}", transfer_id);
+    transfer::cancel(&transfers, &transfer_id).await
+        .map_err(|e| format!("Failed to cancel transfer: {}", e))
+}
+
+/// 递归下载目录，进度（整棵树）通过 `sftp-transfer-progress` 事件发送
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fs_download_dir(
+    id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+    options: Option<TreeOptions>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    app_handle: AppHandle,
+) -> Result<TreeSummary, String> {
+    debug_log!("[FS-CMD] fs_download_dir: id={}, remote={}, local={}", id, remote_path, local_path);
+
+    let fs = lookup(&registry, &id).await?;
+    let job = TreeJob {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        src: fs.as_ref(),
+        src_path: &remote_path,
+        dst: &LocalFs,
+        dst_path: &local_path,
+        options: options.unwrap_or_default(),
+    };
+    let summary = recursive::copy_tree(&transfers, app_handle, job).await
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    debug_log!("[FS-CMD] Downloaded {} files ({} bytes) to {}", summary.files, summary.bytes, local_path);
+    Ok(summary)
+}
+
+/// 递归上传目录，进度（整棵树）通过 `sftp-transfer-progress` 事件发送
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fs_upload_dir(
+    id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+    options: Option<TreeOptions>,
+    registry: State<'_, FsRegistry>,
+    transfers: State<'_, TransferMap>,
+    app_handle: AppHandle,
+) -> Result<TreeSummary, String> {
+    debug_log!("[FS-CMD] fs_upload_dir: id={}, local={}, remote={}", id, local_path, remote_path);
+
+    let fs = lookup(&registry, &id).await?;
+    let job = TreeJob {
+        id: transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        src: &LocalFs,
+        src_path: &local_path,
+        dst: fs.as_ref(),
+        dst_path: &remote_path,
+        options: options.unwrap_or_default(),
+    };
+    let summary = recursive::copy_tree(&transfers, app_handle, job).await
+        .map_err(|e| format!("Upload failed: {}", e))?;
+
+    debug_log!("[FS-CMD] Uploaded {} files ({} bytes) to {}", summary.files, summary.bytes, remote_path);
+    Ok(summary)
+}
+
+/// 重命名或移动
+#[tauri::command]
+pub async fn fs_rename(
+    id: String,
+    from: String,
+    to: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_rename: id={}, {} -> {}", id, from, to);
+    let fs = lookup(&registry, &id).await?;
+    fs.rename(&from, &to).await
+        .map_err(|e| format!("Failed to rename: {}", e))
+}
+
+/// 删除文件或目录，`recursive` 为 true 时连同目录内容一起删除
+#[tauri::command]
+pub async fn fs_remove(
+    id: String,
+    path: String,
+    is_dir: bool,
+    recursive: Option<bool>,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_remove: id={}, path={}, is_dir={}, recursive={:?}", id, path, is_dir, recursive);
+    let fs = lookup(&registry, &id).await?;
+    if is_dir && recursive.unwrap_or(false) {
+        let removed = recursive::remove_tree(fs.as_ref(), &path).await
+            .map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+        debug_log!("[FS-CMD] Removed {} entries under {}", removed, path);
+        return Ok(());
+    }
+    fs.remove(&path, is_dir).await
+        .map_err(|e| format!("Failed to remove {}: {}", path, e))
+}
+
+/// 创建目录
+#[tauri::command]
+pub async fn fs_create_dir(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_create_dir: id={}, path={}", id, path);
+    let fs = lookup(&registry, &id).await?;
+    fs.create_dir(&path).await
+        .map_err(|e| format!("Failed to create directory: {}", e))
+}
+
+/// 修改权限，`mode` 可以是八进制（`755`）或符号形式（`u+x,go-w`）
+#[tauri::command]
+pub async fn fs_chmod(
+    id: String,
+    path: String,
+    mode: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<u32, String> {
+    debug_log!("[FS-CMD] fs_chmod: id={}, path={}, mode={}", id, path, mode);
+    let fs = lookup(&registry, &id).await?;
+
+    let current = fs.stat(&path).await
+        .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+    let current_mode = current.permissions.as_deref()
+        .and_then(|p| u32::from_str_radix(p, 8).ok())
+        .unwrap_or(0);
+    let new_mode = attrs::parse_mode(&mode, current_mode, current.is_dir)?;
+
+    fs.chmod(&path, new_mode).await
+        .map_err(|e| format!("Failed to change permissions: {}", e))?;
+    Ok(new_mode)
+}
+
+/// 修改属主和 / 或属组，接受名称或数字 ID，名称按目标系统的 /etc/passwd、/etc/group 解析
+#[tauri::command]
+pub async fn fs_chown(
+    id: String,
+    path: String,
+    owner: Option<String>,
+    group: Option<String>,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_chown: id={}, path={}, owner={:?}, group={:?}", id, path, owner, group);
+    let fs = lookup(&registry, &id).await?;
+
+    let current = fs.details(&path).await
+        .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+    let ids = IdMaps::load(fs.as_ref()).await;
+
+    // 未指定的一方保持不变
+    let uid = match owner.as_deref() {
+        Some(name) => ids.resolve_user(name)?,
+        None => current.uid.ok_or("Current owner is unknown")?,
+    };
+    let gid = match group.as_deref() {
+        Some(name) => ids.resolve_group(name)?,
+        None => current.gid.ok_or("Current group is unknown")?,
+    };
+
+    fs.chown(&path, uid, gid).await
+        .map_err(|e| format!("Failed to change owner: {}", e))
+}
+
+/// 获取详细信息（属主、属组、访问时间、链接目标）
+#[tauri::command]
+pub async fn fs_stat_details(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<FileDetails, String> {
+    let fs = lookup(&registry, &id).await?;
+    let mut details = fs.details(&path).await
+        .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+    if details.owner.is_none() || details.group.is_none() {
+        IdMaps::load(fs.as_ref()).await.fill_names(&mut details);
+    }
+    Ok(details)
+}
+
+/// 读取符号链接指向的路径
+#[tauri::command]
+pub async fn fs_read_link(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<String, String> {
+    let fs = lookup(&registry, &id).await?;
+    fs.read_link(&path).await
+        .map_err(|e| format!("Failed to read symlink: {}", e))
+}
+
+/// 解析为绝对路径，`.` 返回登录后的初始目录（用户主目录）
+#[tauri::command]
+pub async fn fs_canonicalize(
+    id: String,
+    path: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<String, String> {
+    let fs = lookup(&registry, &id).await?;
+    fs.canonicalize(&path).await
+        .map_err(|e| format!("Failed to resolve {}: {}", path, e))
+}
+
+/// 创建符号链接
+#[tauri::command]
+pub async fn fs_symlink(
+    id: String,
+    target: String,
+    link: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_symlink: id={}, {} -> {}", id, link, target);
+    let fs = lookup(&registry, &id).await?;
+    fs.symlink(&target, &link).await
+        .map_err(|e| format!("Failed to create symlink: {}", e))
+}
+
+/// 关闭文件会话
+#[tauri::command]
+pub async fn fs_disconnect(
+    id: String,
+    registry: State<'_, FsRegistry>,
+) -> Result<(), String> {
+    debug_log!("[FS-CMD] fs_disconnect: id={}", id);
+    let fs = registry.lock().await.remove(&id);
+    if let Some(fs) = fs {
+        fs.close().await;
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/// 跨文件系统复制时的管道缓冲区大小
+const COPY_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 文件条目信息
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// 符号链接本身（仅 list_dir 返回，stat 会跟随链接）
+    #[serde(default)]
+    pub is_symlink: bool,
+    pub size: u64,
+    pub permissions: Option<String>,
+    pub modified: Option<u64>,
+}
+
+/// 文件详细信息（不跟随符号链接）
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct FileDetails {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    /// 八进制权限位，如 "755"
+    pub permissions: Option<String>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// 属主用户名，由 uid 解析得到
+    pub owner: Option<String>,
+    /// 属组名，由 gid 解析得到
+    pub group: Option<String>,
+    pub accessed: Option<u64>,
+    pub modified: Option<u64>,
+    /// 符号链接指向的路径
+    pub link_target: Option<String>,
+}
+
+/// 文件系统抽象，SFTP、FTP 与本地文件系统均实现此 trait
+///
+/// 路径均为对应文件系统上的路径，不做转换。
+#[async_trait]
+pub trait RemoteFs: Send + Sync {
+    /// 列出目录内容（不含 `.` 与 `..`），符号链接不跟随
+    async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取单个文件或目录的信息（跟随符号链接）
+    async fn stat(&self, path: &str) -> Result<FileEntry, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 从 `offset` 开始读取至多 `len` 字节，到达文件末尾时返回的数据可能更短
+    async fn read_range(&self, path: &str, offset: u64, len: u64) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 从 `offset` 开始把文件内容写入 `sink`，返回写入的字节数
+    async fn read_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        sink: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 从 `source` 读取直到 EOF 并写入文件，返回写入的字节数
+    ///
+    /// `offset` 为 0 时创建或覆盖文件；否则文件必须已存在，从 `offset` 处续写（用于断点续传）。
+    async fn write_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        source: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 重命名或移动
+    async fn rename(&self, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 删除文件或空目录
+    async fn remove(&self, path: &str, is_dir: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 创建目录
+    async fn create_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 修改权限位（如 0o755）
+    async fn chmod(&self, path: &str, mode: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 创建指向 `target` 的符号链接 `link`
+    async fn symlink(&self, target: &str, link: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 读取符号链接指向的路径
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 设置修改时间（Unix 秒）
+    async fn set_modified(&self, path: &str, mtime: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取详细信息（属主、属组、访问时间、链接目标），`owner` / `group` 由调用方解析
+    async fn details(&self, path: &str) -> Result<FileDetails, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 修改属主与属组
+    async fn chown(&self, path: &str, uid: u32, gid: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 解析为绝对路径（`.` 为登录后的初始目录，通常是用户主目录）
+    async fn canonicalize(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 关闭会话（如 FTP 的 QUIT），默认无操作
+    async fn close(&self) {}
+
+    /// 同一会话能否同时进行一个读取与一个写入（FTP 的控制连接同一时间只能进行一个传输）
+    fn concurrent_transfers(&self) -> bool {
+        true
+    }
+}
+
+/// 在任意两个文件系统之间复制文件，边读边写，不把整个文件读入内存
+///
+/// 两端均从 `offset` 开始（0 表示完整复制），每当目标端取走数据时以
+/// 包含 `offset` 在内的累计字节数调用 `on_progress`。返回本次写入的字节数。
+pub async fn copy_file(
+    src: &dyn RemoteFs,
+    src_path: &str,
+    dst: &dyn RemoteFs,
+    dst_path: &str,
+    offset: u64,
+    on_progress: &mut (dyn FnMut(u64) + Send),
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    // 读写两端互相等待：读取端填满管道后阻塞，写入端拿不到会话
+    if std::ptr::addr_eq(src, dst) && !src.concurrent_transfers() {
+        return Err("Copying within the same session is not supported by this protocol, download and upload the file instead".into());
+    }
+
+    let (mut writer, reader) = tokio::io::duplex(COPY_BUFFER_SIZE);
+    let mut reader = ProgressReader { inner: reader, bytes: offset, on_progress };
+
+    let read = async {
+        let bytes = src.read_stream(src_path, offset, &mut writer).await?;
+        // 关闭写端，让目标端读到 EOF
+        writer.shutdown().await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(bytes)
+    };
+    let write = dst.write_stream(dst_path, offset, &mut reader);
+
+    let (_, written) = tokio::try_join!(read, write)?;
+    Ok(written)
+}
+
+/// 统计经过的字节数并回调进度
+struct ProgressReader<'a, R> {
+    inner: R,
+    bytes: u64,
+    on_progress: &'a mut (dyn FnMut(u64) + Send),
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ProgressReader<'_, R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = &mut *self;
+        let before = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        let read = buf.filled().len() - before;
+        if read > 0 {
+            this.bytes += read as u64;
+            (this.on_progress)(this.bytes);
+        }
+        result
+    }
+}
+
+/// 路径的最后一段，根目录返回 `/`
+pub(crate) fn file_name(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rsplit_once('/') {
+        Some((_, name)) => name.to_string(),
+        None if trimmed.is_empty() => "/".to_string(),
+        None => trimmed.to_string(),
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// 传输方向（相对于远程会话）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransferDirection {
+    Download,
+    Upload,
+}
+
+/// 未完成的可续传传输，应用重启后据此继续
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptedTransfer {
+    pub transfer_id: String,
+    pub direction: TransferDirection,
+    /// 所属连接配置 ID，前端据此在重新连接后提示续传
+    pub connection_id: Option<String>,
+    pub remote_path: String,
+    pub local_path: String,
+    /// 文件总大小（开始传输时获取）
+    pub total: Option<u64>,
+    /// 最后更新时间（Unix 秒）
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JournalFile {
+    transfers: Vec<InterruptedTransfer>,
+}
+
+/// 可续传传输的记录文件（配置目录下的 transfers.json）
+///
+/// 传输开始时写入，成功或用户放弃后删除；出错、暂停、取消或异常退出时保留，之后可从 `.part` 文件继续。
+pub struct TransferJournal {
+    path: PathBuf,
+}
+
+/// 共享的传输记录
+pub type TransferJournalState = Arc<Mutex<TransferJournal>>;
+
+impl TransferJournal {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_dir = dirs::config_dir()
+            .ok_or("无法获取配置目录")?
+            .join("konnect");
+        fs::create_dir_all(&config_dir)?;
+
+        Ok(Self { path: config_dir.join("transfers.json") })
+    }
+
+    pub fn load(&self) -> Result<Vec<InterruptedTransfer>, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        let journal: JournalFile = serde_json::from_str(&content)?;
+        Ok(journal.transfers)
+    }
+
+    fn save(&self, transfers: Vec<InterruptedTransfer>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let content = serde_json::to_string_pretty(&JournalFile { transfers })?;
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    /// 写入或更新一条记录
+    pub fn record(&self, mut transfer: InterruptedTransfer) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        transfer.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut transfers = self.load()?;
+        transfers.retain(|t| t.transfer_id != transfer.transfer_id);
+        transfers.push(transfer);
+        self.save(transfers)
+    }
+
+    /// 删除一条记录
+    pub fn remove(&self, transfer_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut transfers = self.load()?;
+        let before = transfers.len();
+        transfers.retain(|t| t.transfer_id != transfer_id);
+        if transfers.len() != before {
+            self.save(transfers)?;
+        }
+        Ok(())
+    }
+}
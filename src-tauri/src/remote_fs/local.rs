@@ -0,0 +1,251 @@
+use crate::remote_fs::{FileDetails, FileEntry, RemoteFs};
+use async_trait::async_trait;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+/// 本地文件系统，用于双栏传输中的本机一侧
+pub struct LocalFs;
+
+#[async_trait]
+impl RemoteFs for LocalFs {
+    async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut read_dir = tokio::fs::read_dir(path).await
+            .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+        let mut entries = Vec::new();
+        while let Some(entry) = read_dir.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            match entry.metadata().await {
+                Ok(metadata) => entries.push(to_entry(name, &metadata)),
+                // 无权限访问等情况，跳过该条目
+                Err(e) => debug_log!("[LOCAL-FS] Skipping {}: {}", name, e),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn stat(&self, path: &str) -> Result<FileEntry, Box<dyn std::error::Error + Send + Sync>> {
+        let metadata = tokio::fs::metadata(path).await
+            .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        Ok(to_entry(name, &metadata))
+    }
+
+    async fn read_range(&self, path: &str, offset: u64, len: u64) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut file = tokio::fs::File::open(path).await
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut data = Vec::new();
+        file.take(len).read_to_end(&mut data).await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        Ok(data)
+    }
+
+    async fn read_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        sink: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut file = tokio::fs::File::open(path).await
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let bytes = tokio::io::copy(&mut file, sink).await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        Ok(bytes)
+    }
+
+    async fn write_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        source: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut file = if offset == 0 {
+            tokio::fs::File::create(path).await
+                .map_err(|e| format!("Failed to create file: {}", e))?
+        } else {
+            let mut file = tokio::fs::OpenOptions::new().write(true).open(path).await
+                .map_err(|e| format!("Failed to open file: {}", e))?;
+            // 丢弃 offset 之后可能残留的数据
+            file.set_len(offset).await?;
+            file.seek(SeekFrom::Start(offset)).await?;
+            file
+        };
+        let bytes = tokio::io::copy(source, &mut file).await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        file.flush().await?;
+        Ok(bytes)
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::fs::rename(from, to).await
+            .map_err(|e| format!("Failed to rename: {}", e))?;
+        Ok(())
+    }
+
+    async fn remove(&self, path: &str, is_dir: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if is_dir {
+            tokio::fs::remove_dir(path).await
+                .map_err(|e| format!("Failed to remove directory: {}", e))?;
+        } else {
+            tokio::fs::remove_file(path).await
+                .map_err(|e| format!("Failed to remove file: {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::fs::create_dir(path).await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        Ok(())
+    }
+
+    async fn chmod(&self, path: &str, mode: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(unix)]
+        let permissions = {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::Permissions::from_mode(mode)
+        };
+        // Windows 只有只读属性：属主没有写权限时设为只读
+        #[cfg(not(unix))]
+        let permissions = {
+            let mut permissions = tokio::fs::metadata(path).await?.permissions();
+            permissions.set_readonly(mode & 0o200 == 0);
+            permissions
+        };
+
+        tokio::fs::set_permissions(path, permissions).await
+            .map_err(|e| format!("Failed to change permissions: {}", e))?;
+        Ok(())
+    }
+
+    async fn symlink(&self, target: &str, link: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(unix)]
+        tokio::fs::symlink(target, link).await
+            .map_err(|e| format!("Failed to create symlink: {}", e))?;
+        #[cfg(windows)]
+        {
+            let is_dir = tokio::fs::metadata(target).await.map(|m| m.is_dir()).unwrap_or(false);
+            let result = if is_dir {
+                tokio::fs::symlink_dir(target, link).await
+            } else {
+                tokio::fs::symlink_file(target, link).await
+            };
+            result.map_err(|e| format!("Failed to create symlink: {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let target = tokio::fs::read_link(path).await
+            .map_err(|e| format!("Failed to read symlink: {}", e))?;
+        Ok(target.to_string_lossy().to_string())
+    }
+
+    async fn set_modified(&self, path: &str, mtime: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&path)?;
+            file.set_modified(UNIX_EPOCH + Duration::from_secs(mtime))
+        })
+        .await?
+        .map_err(|e| format!("Failed to set modification time: {}", e))?;
+        Ok(())
+    }
+
+    async fn details(&self, path: &str) -> Result<FileDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let metadata = tokio::fs::symlink_metadata(path).await
+            .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let entry = to_entry(name, &metadata);
+        let link_target = if entry.is_symlink {
+            tokio::fs::read_link(path).await.ok().map(|t| t.to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        #[cfg(unix)]
+        let (uid, gid) = {
+            use std::os::unix::fs::MetadataExt;
+            (Some(metadata.uid()), Some(metadata.gid()))
+        };
+        #[cfg(not(unix))]
+        let (uid, gid) = (None, None);
+
+        Ok(FileDetails {
+            path: path.to_string(),
+            name: entry.name,
+            is_dir: entry.is_dir,
+            is_symlink: entry.is_symlink,
+            size: entry.size,
+            permissions: entry.permissions,
+            uid,
+            gid,
+            owner: None,
+            group: None,
+            accessed: metadata.accessed().ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            modified: entry.modified,
+            link_target,
+        })
+    }
+
+    async fn chown(&self, path: &str, uid: u32, gid: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(unix)]
+        {
+            let path = path.to_string();
+            tokio::task::spawn_blocking(move || std::os::unix::fs::chown(path, Some(uid), Some(gid)))
+                .await?
+                .map_err(|e| format!("Failed to change owner: {}", e))?;
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, uid, gid);
+            Err("Changing ownership is not supported on this platform".into())
+        }
+    }
+
+    async fn canonicalize(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        // `.` 对本地会话而言是用户主目录
+        let resolved = if path.is_empty() || path == "." {
+            dirs::home_dir().ok_or("无法获取用户主目录")?
+        } else {
+            tokio::fs::canonicalize(path).await
+                .map_err(|e| format!("Failed to resolve {}: {}", path, e))?
+        };
+        Ok(resolved.to_string_lossy().to_string())
+    }
+}
+
+fn to_entry(name: String, metadata: &std::fs::Metadata) -> FileEntry {
+    #[cfg(unix)]
+    let permissions = {
+        use std::os::unix::fs::PermissionsExt;
+        Some(format!("{:o}", metadata.permissions().mode() & 0o7777))
+    };
+    #[cfg(not(unix))]
+    let permissions = None;
+
+    FileEntry {
+        name,
+        is_dir: metadata.is_dir(),
+        is_symlink: metadata.file_type().is_symlink(),
+        size: metadata.len(),
+        permissions,
+        modified: metadata.modified().ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+    }
+}
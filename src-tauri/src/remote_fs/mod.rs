@@ -0,0 +1,17 @@
+// 统一文件系统模块：SFTP / FTP / 本地文件系统共用同一组文件命令
+pub mod filesystem;
+pub mod local;
+pub mod attrs;
+pub mod transfer;
+pub mod journal;
+pub mod queue;
+pub mod recursive;
+pub mod commands;
+
+pub use filesystem::{copy_file, FileDetails, FileEntry, RemoteFs};
+pub use local::LocalFs;
+pub use transfer::{create_transfer_map, TransferJob, TransferMap, TransferProgress};
+pub use journal::{InterruptedTransfer, TransferDirection, TransferJournal, TransferJournalState};
+pub use queue::{create_transfer_queue, JobStatus, QueueContext, QueuedTransfer, TransferQueueState};
+pub use recursive::{SymlinkPolicy, TreeJob, TreeOptions, TreeSummary};
+pub use commands::{create_fs_registry, FsRegistry};
@@ -0,0 +1,476 @@
+use crate::remote_fs::{
+    transfer, FsRegistry, InterruptedTransfer, LocalFs, TransferDirection, TransferJob,
+    TransferJournalState, TransferMap,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+/// 任务状态变化事件名，负载为 [`QueuedTransfer`]
+pub const JOB_EVENT: &str = "transfer-job-updated";
+
+/// 每个会话默认同时进行的传输数
+const DEFAULT_CONCURRENCY: usize = 3;
+
+/// 队列任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// 队列中的一个上传或下载任务
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTransfer {
+    /// 任务 ID，同时用作传输 ID（进度事件、续传记录）
+    pub id: String,
+    pub session_id: String,
+    pub direction: TransferDirection,
+    pub remote_path: String,
+    pub local_path: String,
+    pub connection_id: Option<String>,
+    pub status: JobStatus,
+    /// 已传输字节数（含续传起点）
+    pub bytes: u64,
+    pub total: Option<u64>,
+    /// 最近一次失败的原因
+    pub error: Option<String>,
+    /// 已开始执行的次数，同时作为本次执行的代数，用于丢弃旧执行的状态更新
+    pub attempts: u32,
+}
+
+/// 传输队列：按加入顺序调度，每个会话最多同时运行 `concurrency` 个任务
+///
+/// 任务均以续传方式执行，暂停即取消当前传输并保留 `.part` 文件，恢复时从断点继续。
+/// 暂停后立即恢复时，旧的执行退出之前不会开始新的执行，避免两次执行同时写入 `.part` 文件。
+pub struct TransferQueue {
+    jobs: Vec<QueuedTransfer>,
+    progress: HashMap<String, Arc<AtomicU64>>,
+    /// 执行尚未退出的任务 ID 及其会话（任务可能已被暂停、取消或移除）
+    active: HashMap<String, String>,
+    concurrency: usize,
+}
+
+/// 共享的传输队列
+pub type TransferQueueState = Arc<Mutex<TransferQueue>>;
+
+/// 创建传输队列
+pub fn create_transfer_queue() -> TransferQueueState {
+    Arc::new(Mutex::new(TransferQueue {
+        jobs: Vec::new(),
+        progress: HashMap::new(),
+        active: HashMap::new(),
+        concurrency: DEFAULT_CONCURRENCY,
+    }))
+}
+
+impl TransferQueue {
+    /// 任务快照（已传输字节数取最新值）
+    fn snapshot(&self, job: &QueuedTransfer) -> QueuedTransfer {
+        let mut job = job.clone();
+        if let Some(progress) = self.progress.get(&job.id) {
+            job.bytes = progress.load(Ordering::Relaxed);
+        }
+        job
+    }
+
+    pub fn list(&self) -> Vec<QueuedTransfer> {
+        self.jobs.iter().map(|job| self.snapshot(job)).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<QueuedTransfer> {
+        self.jobs.iter().find(|job| job.id == id).map(|job| self.snapshot(job))
+    }
+
+    /// 移除已结束的任务
+    pub fn clear_finished(&mut self) {
+        let progress = &mut self.progress;
+        self.jobs.retain(|job| {
+            let keep = !job.status.is_finished();
+            if !keep {
+                progress.remove(&job.id);
+            }
+            keep
+        });
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut QueuedTransfer, String> {
+        self.jobs.iter_mut()
+            .find(|job| job.id == id)
+            .ok_or_else(|| format!("Transfer {} not found", id))
+    }
+
+    /// 把排队中或进行中的任务标记为暂停
+    fn pause(&mut self, id: &str) -> Result<QueuedTransfer, String> {
+        let job = self.get_mut(id)?;
+        if !matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+            return Err(format!("Transfer {} is not active", id));
+        }
+        job.status = JobStatus::Paused;
+        let job = job.clone();
+        Ok(self.snapshot(&job))
+    }
+
+    /// 把处于 `from` 状态之一的任务重新排队
+    fn requeue(&mut self, id: &str, from: &[JobStatus]) -> Result<QueuedTransfer, String> {
+        let job = self.get_mut(id)?;
+        if !from.contains(&job.status) {
+            return Err(format!("Transfer {} cannot be restarted in state {:?}", id, job.status));
+        }
+        job.status = JobStatus::Queued;
+        job.error = None;
+        Ok(job.clone())
+    }
+
+    /// 按会话并发上限把排队中的任务标记为进行中，返回需要启动的任务
+    /// 上一次执行尚未退出的任务留在队列中，等其退出后再启动
+    fn start_queued(&mut self) -> Vec<QueuedTransfer> {
+        let limit = self.concurrency;
+        // 按尚未退出的执行计数：已暂停或取消但仍在收尾的执行同样占用并发名额
+        let mut running: HashMap<String, usize> = HashMap::new();
+        for session_id in self.active.values() {
+            *running.entry(session_id.clone()).or_default() += 1;
+        }
+
+        let mut started = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| job.status == JobStatus::Queued) {
+            if self.active.contains_key(&job.id) {
+                continue;
+            }
+            let count = running.entry(job.session_id.clone()).or_default();
+            if *count >= limit {
+                continue;
+            }
+            *count += 1;
+            job.status = JobStatus::Running;
+            job.attempts += 1;
+            started.push(job.clone());
+        }
+        for job in &started {
+            self.active.insert(job.id.clone(), job.session_id.clone());
+            self.progress.entry(job.id.clone()).or_default();
+        }
+        started
+    }
+
+    /// 记录一次执行的结果，返回更新后的任务
+    /// 任务已被移除，或结果来自被新执行取代的旧执行时返回 None
+    fn finish(&mut self, job: &QueuedTransfer, result: Result<u64, String>) -> Option<QueuedTransfer> {
+        self.active.remove(&job.id);
+        let entry = self.get_mut(&job.id).ok()?;
+        if entry.attempts != job.attempts {
+            return None;
+        }
+        match result {
+            Ok(total) => {
+                entry.status = JobStatus::Completed;
+                entry.total = Some(total);
+            }
+            // 暂停 / 取消时状态已由对应操作设置
+            Err(_) if entry.status != JobStatus::Running => {}
+            Err(e) => {
+                debug_log!("[QUEUE] Transfer {} failed: {}", job.id, e);
+                entry.status = JobStatus::Failed;
+                entry.error = Some(e);
+            }
+        }
+        let entry = entry.clone();
+        Some(self.snapshot(&entry))
+    }
+}
+
+/// 调度任务所需的共享状态
+#[derive(Clone)]
+pub struct QueueContext {
+    pub queue: TransferQueueState,
+    pub registry: FsRegistry,
+    pub transfers: TransferMap,
+    pub journal: TransferJournalState,
+    pub app_handle: AppHandle,
+}
+
+impl QueueContext {
+    fn emit(&self, job: &QueuedTransfer) {
+        let _ = self.app_handle.emit(JOB_EVENT, job);
+    }
+
+    /// 加入队列并尝试立即开始
+    pub async fn enqueue(&self, mut job: QueuedTransfer) -> String {
+        let id = job.id.clone();
+        job.status = JobStatus::Queued;
+        self.emit(&job);
+        self.queue.lock().await.jobs.push(job);
+        self.schedule().await;
+        id
+    }
+
+    /// 设置每个会话的并发数
+    pub async fn set_concurrency(&self, limit: usize) {
+        self.queue.lock().await.concurrency = limit.max(1);
+        self.schedule().await;
+    }
+
+    /// 暂停排队中或进行中的任务
+    pub async fn pause(&self, id: &str) -> Result<(), String> {
+        let job = self.queue.lock().await.pause(id)?;
+        // 进行中的传输收到取消信号后由 run_job 收尾
+        let _ = transfer::cancel(&self.transfers, id).await;
+        self.emit(&job);
+        self.schedule().await;
+        Ok(())
+    }
+
+    /// 恢复已暂停的任务（从断点继续）
+    pub async fn resume(&self, id: &str) -> Result<(), String> {
+        self.requeue(id, &[JobStatus::Paused]).await
+    }
+
+    /// 重新执行失败或已取消的任务
+    pub async fn retry(&self, id: &str) -> Result<(), String> {
+        self.requeue(id, &[JobStatus::Failed, JobStatus::Cancelled]).await
+    }
+
+    async fn requeue(&self, id: &str, from: &[JobStatus]) -> Result<(), String> {
+        let job = self.queue.lock().await.requeue(id, from)?;
+        self.emit(&job);
+        self.schedule().await;
+        Ok(())
+    }
+
+    /// 取消任务，已传输的 `.part` 文件与续传记录保留，可稍后重试或在重启后续传
+    pub async fn cancel(&self, id: &str) -> Result<(), String> {
+        let (job, was_running) = {
+            let mut queue = self.queue.lock().await;
+            let job = queue.get_mut(id)?;
+            if job.status.is_finished() {
+                return Ok(());
+            }
+            let was_running = job.status == JobStatus::Running;
+            job.status = JobStatus::Cancelled;
+            (job.clone(), was_running)
+        };
+        if was_running {
+            let _ = transfer::cancel(&self.transfers, id).await;
+        }
+        self.emit(&job);
+        self.schedule().await;
+        Ok(())
+    }
+
+    /// 为每个会话启动排队中的任务，直到达到并发上限
+    ///
+    /// 任务结束时会再次调度，返回装箱的 future 以打断 schedule 与 run_job 之间的类型递归。
+    fn schedule(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(self.schedule_inner())
+    }
+
+    async fn schedule_inner(&self) {
+        let started = self.queue.lock().await.start_queued();
+        for job in started {
+            // 先登记取消信号，开始前到达的暂停 / 取消同样生效
+            transfer::register(&self.transfers, &job.id).await;
+            self.emit(&job);
+            let ctx = self.clone();
+            tokio::spawn(async move { ctx.run_job(job).await });
+        }
+    }
+
+    /// 执行一个任务并记录结果，随后调度下一个
+    async fn run_job(&self, job: QueuedTransfer) {
+        debug_log!("[QUEUE] Starting {:?} {} (attempt {})", job.direction, job.id, job.attempts);
+        let result = self.execute(&job).await;
+
+        let updated = self.queue.lock().await.finish(&job, result);
+        let Some(updated) = updated else {
+            // 任务已被移除；仍需调度，暂停后立即恢复的任务在此时才开始
+            self.schedule().await;
+            return;
+        };
+
+        self.emit(&updated);
+        self.schedule().await;
+    }
+
+    async fn execute(&self, job: &QueuedTransfer) -> Result<u64, String> {
+        let fs = self.registry.lock().await
+            .get(&job.session_id)
+            .cloned()
+            .ok_or_else(|| format!("File session {} not found", job.session_id))?;
+
+        let progress = self.queue.lock().await.progress.get(&job.id).cloned();
+        let (src, src_path, dst, dst_path) = match job.direction {
+            TransferDirection::Download => (fs.as_ref(), &job.remote_path, &LocalFs as _, &job.local_path),
+            TransferDirection::Upload => (&LocalFs as _, &job.local_path, fs.as_ref(), &job.remote_path),
+        };
+
+        let total = src.stat(src_path).await.ok().map(|entry| entry.size);
+        if let Ok(entry) = self.queue.lock().await.get_mut(&job.id) {
+            entry.total = total;
+        }
+
+        let transfer_job = TransferJob {
+            id: job.id.clone(),
+            src,
+            src_path,
+            dst,
+            dst_path,
+            resume: true,
+            verify: false,
+            progress,
+        };
+        transfer::run_journaled(&self.transfers, &self.journal, self.app_handle.clone(), transfer_job, Some(journal_record(job)))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn journal_record(job: &QueuedTransfer) -> InterruptedTransfer {
+    InterruptedTransfer {
+        transfer_id: job.id.clone(),
+        direction: job.direction,
+        connection_id: job.connection_id.clone(),
+        remote_path: job.remote_path.clone(),
+        local_path: job.local_path.clone(),
+        total: job.total,
+        updated_at: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_fs::create_transfer_map;
+    use crate::remote_fs::transfer::CANCELLED;
+    use std::time::Duration;
+
+    fn job(id: &str) -> QueuedTransfer {
+        QueuedTransfer {
+            id: id.to_string(),
+            session_id: "session".to_string(),
+            direction: TransferDirection::Download,
+            remote_path: format!("/remote/{}", id),
+            local_path: format!("/local/{}", id),
+            connection_id: None,
+            status: JobStatus::Queued,
+            bytes: 0,
+            total: None,
+            error: None,
+            attempts: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn resume_waits_for_paused_attempt_to_exit() {
+        let transfers = create_transfer_map();
+        let queue = create_transfer_queue();
+        let mut queue = queue.lock().await;
+        queue.jobs.push(job("a"));
+
+        let first = queue.start_queued();
+        assert_eq!(first.len(), 1);
+        transfer::register(&transfers, "a").await;
+
+        // 慢速传输：只有被取消时才会结束
+        let old = {
+            let transfers = transfers.clone();
+            tokio::spawn(async move {
+                transfer::cancellable(&transfers, "a", async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(0)
+                })
+                .await
+                .map_err(|e| e.to_string())
+            })
+        };
+
+        // 暂停后立即恢复，旧执行尚未退出
+        queue.pause("a").unwrap();
+        transfer::cancel(&transfers, "a").await.unwrap();
+        queue.requeue("a", &[JobStatus::Paused]).unwrap();
+        assert!(queue.start_queued().is_empty());
+
+        let result = old.await.unwrap();
+        assert_eq!(result.as_ref().unwrap_err(), CANCELLED);
+        let updated = queue.finish(&first[0], result).unwrap();
+        assert_eq!(updated.status, JobStatus::Queued);
+        assert_eq!(updated.error, None);
+
+        let second = queue.start_queued();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].attempts, 2);
+
+        // 旧执行已注销取消信号，新执行登记的信号不受影响
+        transfer::register(&transfers, "a").await;
+        let total = transfer::cancellable(&transfers, "a", async { Ok(42) }).await.unwrap();
+        let done = queue.finish(&second[0], Ok(total)).unwrap();
+        assert_eq!(done.status, JobStatus::Completed);
+        assert_eq!(done.total, Some(42));
+    }
+
+    #[test]
+    fn stale_attempt_result_is_ignored() {
+        let mut queue = TransferQueue {
+            jobs: vec![job("a")],
+            progress: HashMap::new(),
+            active: HashMap::new(),
+            concurrency: 1,
+        };
+        let current = queue.start_queued().remove(0);
+        let mut stale = current.clone();
+        stale.attempts -= 1;
+
+        assert!(queue.finish(&stale, Err(CANCELLED.to_string())).is_none());
+        assert_eq!(queue.get("a").unwrap().status, JobStatus::Running);
+        assert_eq!(queue.finish(&current, Ok(1)).unwrap().status, JobStatus::Completed);
+    }
+
+    #[test]
+    fn start_queued_respects_session_concurrency() {
+        let mut queue = TransferQueue {
+            jobs: vec![job("a"), job("b"), job("c")],
+            progress: HashMap::new(),
+            active: HashMap::new(),
+            concurrency: 1,
+        };
+        queue.jobs[2].session_id = "other".to_string();
+
+        let started: Vec<String> = queue.start_queued().into_iter().map(|job| job.id).collect();
+        assert_eq!(started, ["a", "c"]);
+        assert_eq!(queue.get("b").unwrap().status, JobStatus::Queued);
+    }
+
+    #[test]
+    fn paused_attempt_still_counts_until_it_exits() {
+        let mut queue = TransferQueue {
+            jobs: vec![job("a"), job("b")],
+            progress: HashMap::new(),
+            active: HashMap::new(),
+            concurrency: 1,
+        };
+        let first = queue.start_queued().remove(0);
+        assert_eq!(first.id, "a");
+
+        // 暂停后旧执行仍在退出，b 不能立即启动
+        queue.pause("a").unwrap();
+        assert!(queue.start_queued().is_empty());
+
+        queue.finish(&first, Err(CANCELLED.to_string()));
+        let started: Vec<String> = queue.start_queued().into_iter().map(|job| job.id).collect();
+        assert_eq!(started, ["b"]);
+    }
+}
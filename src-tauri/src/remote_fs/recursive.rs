@@ -0,0 +1,304 @@
+use crate::remote_fs::transfer::{self, ProgressReporter};
+use crate::remote_fs::{copy_file, FileEntry, RemoteFs, TransferMap};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// 跟随符号链接时允许的最大目录深度，防止链接成环时无限递归
+const MAX_DEPTH: usize = 64;
+
+/// 遇到符号链接时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SymlinkPolicy {
+    /// 按链接指向的文件或目录处理（与 scp -r 一致）
+    #[default]
+    Follow,
+    /// 忽略
+    Skip,
+    /// 在目标端创建相同的链接
+    Copy,
+}
+
+/// 目录传输选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TreeOptions {
+    /// 保留修改时间
+    pub preserve_times: bool,
+    /// 保留权限位
+    pub preserve_permissions: bool,
+    pub symlinks: SymlinkPolicy,
+}
+
+/// 目录传输结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TreeSummary {
+    pub files: u64,
+    pub dirs: u64,
+    pub links: u64,
+    pub bytes: u64,
+    /// 跳过的条目（按策略忽略的链接、失效的链接）
+    pub skipped: u64,
+    /// 未能保留时间或权限的条目数
+    pub attr_failures: u64,
+}
+
+enum ItemKind {
+    Dir,
+    File,
+    Link,
+}
+
+/// 遍历得到的条目，`relative` 为相对根目录的路径（以 `/` 分隔）
+struct TreeItem {
+    relative: String,
+    kind: ItemKind,
+    entry: FileEntry,
+}
+
+/// 拼接路径，远程与本地均使用 `/`（Windows 同样接受）
+fn join(base: &str, relative: &str) -> String {
+    if relative.is_empty() {
+        base.to_string()
+    } else if base.ends_with('/') {
+        format!("{}{}", base, relative)
+    } else {
+        format!("{}/{}", base, relative)
+    }
+}
+
+/// 深度优先遍历目录树，父目录总在其子项之前
+async fn walk(
+    fs: &dyn RemoteFs,
+    root: &str,
+    policy: SymlinkPolicy,
+    summary: &mut TreeSummary,
+) -> Result<Vec<TreeItem>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut items = Vec::new();
+    let mut pending = vec![(String::new(), 0usize)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        for entry in fs.list_dir(&join(root, &dir)).await? {
+            let relative = join(&dir, &entry.name).trim_start_matches('/').to_string();
+            let mut entry = entry;
+
+            if entry.is_symlink {
+                match policy {
+                    SymlinkPolicy::Skip => {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    SymlinkPolicy::Copy => {
+                        items.push(TreeItem { relative, kind: ItemKind::Link, entry });
+                        continue;
+                    }
+                    SymlinkPolicy::Follow => match fs.stat(&join(root, &relative)).await {
+                        Ok(target) => entry = FileEntry { name: entry.name, ..target },
+                        Err(e) => {
+                            debug_log!("[TREE] Skipping dangling symlink {}: {}", relative, e);
+                            summary.skipped += 1;
+                            continue;
+                        }
+                    },
+                }
+            }
+
+            if entry.is_dir {
+                if depth + 1 > MAX_DEPTH {
+                    return Err(format!("Directory tree too deep at {} (symlink loop?)", relative).into());
+                }
+                pending.push((relative.clone(), depth + 1));
+                items.push(TreeItem { relative, kind: ItemKind::Dir, entry });
+            } else {
+                items.push(TreeItem { relative, kind: ItemKind::File, entry });
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// 目录不存在时创建
+async fn ensure_dir(fs: &dyn RemoteFs, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match fs.stat(path).await {
+        Ok(entry) if entry.is_dir => Ok(()),
+        Ok(_) => Err(format!("{} exists and is not a directory", path).into()),
+        Err(_) => fs.create_dir(path).await,
+    }
+}
+
+/// 按选项把源条目的时间与权限应用到目标，失败只计数不中止
+async fn apply_attrs(fs: &dyn RemoteFs, path: &str, entry: &FileEntry, options: &TreeOptions, summary: &mut TreeSummary) {
+    if options.preserve_permissions {
+        if let Some(mode) = entry.permissions.as_deref().and_then(|p| u32::from_str_radix(p, 8).ok()) {
+            if let Err(e) = fs.chmod(path, mode).await {
+                debug_log!("[TREE] Failed to preserve permissions of {}: {}", path, e);
+                summary.attr_failures += 1;
+            }
+        }
+    }
+    if options.preserve_times {
+        if let Some(mtime) = entry.modified {
+            if let Err(e) = fs.set_modified(path, mtime).await {
+                debug_log!("[TREE] Failed to preserve mtime of {}: {}", path, e);
+                summary.attr_failures += 1;
+            }
+        }
+    }
+}
+
+/// 一次目录传输
+pub struct TreeJob<'a> {
+    pub id: String,
+    pub src: &'a dyn RemoteFs,
+    pub src_path: &'a str,
+    pub dst: &'a dyn RemoteFs,
+    pub dst_path: &'a str,
+    pub options: TreeOptions,
+}
+
+/// 递归复制目录，保持相对结构；进度以整棵树的字节数与文件数计算，可通过传输 ID 取消
+pub async fn copy_tree(
+    transfers: &TransferMap,
+    app_handle: AppHandle,
+    job: TreeJob<'_>,
+) -> Result<TreeSummary, Box<dyn std::error::Error + Send + Sync>> {
+    transfer::cancellable(transfers, &job.id, copy_tree_inner(app_handle, &job)).await
+}
+
+async fn copy_tree_inner(
+    app_handle: AppHandle,
+    job: &TreeJob<'_>,
+) -> Result<TreeSummary, Box<dyn std::error::Error + Send + Sync>> {
+    let mut summary = TreeSummary::default();
+    let root = job.src.stat(job.src_path).await?;
+    if !root.is_dir {
+        return Err(format!("{} is not a directory", job.src_path).into());
+    }
+
+    let items = walk(job.src, job.src_path, job.options.symlinks, &mut summary).await?;
+    let total_bytes = items.iter()
+        .filter(|item| matches!(item.kind, ItemKind::File))
+        .map(|item| item.entry.size)
+        .sum();
+    let total_files = items.iter().filter(|item| matches!(item.kind, ItemKind::File)).count() as u64;
+    debug_log!("[TREE] {}: {} files, {} bytes", job.id, total_files, total_bytes);
+
+    let mut reporter = ProgressReporter::new(app_handle, job.id.clone(), Some(total_bytes), 0);
+    reporter.set_files(0, total_files);
+
+    ensure_dir(job.dst, job.dst_path).await?;
+    let result = async {
+        for item in &items {
+            let src_path = join(job.src_path, &item.relative);
+            let dst_path = join(job.dst_path, &item.relative);
+            match item.kind {
+                ItemKind::Dir => {
+                    ensure_dir(job.dst, &dst_path).await?;
+                    summary.dirs += 1;
+                }
+                ItemKind::File => {
+                    let done = summary.bytes;
+                    let mut on_progress = |bytes| reporter.advance(done + bytes);
+                    summary.bytes += copy_file(job.src, &src_path, job.dst, &dst_path, 0, &mut on_progress).await
+                        .map_err(|e| format!("{}: {}", src_path, e))?;
+                    summary.files += 1;
+                    reporter.set_files(summary.files, total_files);
+                    apply_attrs(job.dst, &dst_path, &item.entry, &job.options, &mut summary).await;
+                }
+                ItemKind::Link => {
+                    let target = job.src.read_link(&src_path).await?;
+                    job.dst.symlink(&target, &dst_path).await?;
+                    summary.links += 1;
+                }
+            }
+        }
+
+        // 写入文件会改变目录的修改时间，目录属性最后自内向外设置
+        for item in items.iter().rev().filter(|item| matches!(item.kind, ItemKind::Dir)) {
+            apply_attrs(job.dst, &join(job.dst_path, &item.relative), &item.entry, &job.options, &mut summary).await;
+        }
+        apply_attrs(job.dst, job.dst_path, &root, &job.options, &mut summary).await;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+    }
+    .await;
+
+    reporter.emit(true);
+    result.map(|_| summary)
+}
+
+/// 递归删除目录；符号链接只删除链接本身，不进入其指向的目录
+pub async fn remove_tree(fs: &dyn RemoteFs, path: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    // 根路径本身是链接时遍历会进入其指向的目录，只删除链接
+    if fs.details(path).await.is_ok_and(|details| details.is_symlink) {
+        fs.remove(path, false).await?;
+        return Ok(1);
+    }
+
+    let mut summary = TreeSummary::default();
+    let items = walk(fs, path, SymlinkPolicy::Copy, &mut summary).await?;
+
+    // 遍历顺序中父目录在前，倒序即可先删除子项
+    let mut removed = 0;
+    for item in items.iter().rev() {
+        let is_dir = matches!(item.kind, ItemKind::Dir);
+        fs.remove(&join(path, &item.relative), is_dir).await?;
+        removed += 1;
+    }
+    fs.remove(path, true).await?;
+    Ok(removed + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_fs::LocalFs;
+    use std::fs;
+
+    fn path(path: &std::path::Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn remove_tree_removes_nested_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tree");
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a/b/file"), b"data").unwrap();
+        fs::write(root.join("top"), b"data").unwrap();
+
+        let removed = remove_tree(&LocalFs, &path(&root)).await.unwrap();
+        assert_eq!(removed, 5);
+        assert!(!root.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn remove_tree_on_symlink_keeps_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep"), b"data").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let removed = remove_tree(&LocalFs, &path(&link)).await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(target.join("keep").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn remove_tree_removes_nested_links_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep"), b"data").unwrap();
+        let root = dir.path().join("tree");
+        fs::create_dir(&root).unwrap();
+        std::os::unix::fs::symlink(&target, root.join("link")).unwrap();
+
+        remove_tree(&LocalFs, &path(&root)).await.unwrap();
+        assert!(!root.exists());
+        assert!(target.join("keep").exists());
+    }
+}
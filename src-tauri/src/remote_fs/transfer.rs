@@ -0,0 +1,349 @@
+use crate::remote_fs::{copy_file, InterruptedTransfer, RemoteFs, TransferJournalState};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWrite;
+use tokio::sync::{Mutex, Notify};
+
+/// 传输进度事件名
+pub const PROGRESS_EVENT: &str = "sftp-transfer-progress";
+
+/// 两次进度事件之间的最小间隔，避免大量事件拖慢前端
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 取消传输时返回的错误信息
+pub const CANCELLED: &str = "Transfer cancelled";
+
+/// 续传时未完成文件的后缀，传输完成后重命名为目标文件名
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// 进行中的传输，按传输 ID 索引，用于取消
+pub type TransferMap = Arc<Mutex<HashMap<String, Arc<Notify>>>>;
+
+/// 创建传输表
+pub fn create_transfer_map() -> TransferMap {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// 传输进度，随 `sftp-transfer-progress` 事件发送给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    /// 已传输字节数
+    pub bytes: u64,
+    /// 文件总大小，无法获取时为 None
+    pub total: Option<u64>,
+    /// 平均速率（字节/秒）
+    pub rate: f64,
+    /// 预计剩余秒数
+    pub eta_secs: Option<u64>,
+    /// 目录传输中已完成的文件数
+    pub files_done: Option<u64>,
+    /// 目录传输的文件总数
+    pub files_total: Option<u64>,
+    /// 传输已结束（成功、失败或取消）
+    pub done: bool,
+}
+
+/// 按时间间隔节流发送进度事件
+pub(crate) struct ProgressReporter {
+    app_handle: AppHandle,
+    transfer_id: String,
+    total: Option<u64>,
+    started: Instant,
+    last_emit: Option<Instant>,
+    /// 续传起点，速率只按本次传输的数据计算
+    start: u64,
+    bytes: u64,
+    /// 目录传输的文件计数（已完成, 总数）
+    files: Option<(u64, u64)>,
+}
+
+impl ProgressReporter {
+    pub(crate) fn new(app_handle: AppHandle, transfer_id: String, total: Option<u64>, start: u64) -> Self {
+        Self {
+            app_handle,
+            transfer_id,
+            total,
+            started: Instant::now(),
+            last_emit: None,
+            start,
+            bytes: start,
+            files: None,
+        }
+    }
+
+    pub(crate) fn set_files(&mut self, done: u64, total: u64) {
+        self.files = Some((done, total));
+    }
+
+    pub(crate) fn advance(&mut self, bytes: u64) {
+        self.bytes = bytes;
+        let due = self.last_emit.is_none_or(|last| last.elapsed() >= EMIT_INTERVAL);
+        if due {
+            self.emit(false);
+        }
+    }
+
+    pub(crate) fn emit(&mut self, done: bool) {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { (self.bytes - self.start) as f64 / elapsed } else { 0.0 };
+        let eta_secs = match self.total {
+            Some(total) if rate > 0.0 => Some((total.saturating_sub(self.bytes) as f64 / rate).ceil() as u64),
+            _ => None,
+        };
+
+        let progress = TransferProgress {
+            transfer_id: self.transfer_id.clone(),
+            bytes: self.bytes,
+            total: self.total,
+            rate,
+            eta_secs,
+            files_done: self.files.map(|(done, _)| done),
+            files_total: self.files.map(|(_, total)| total),
+            done,
+        };
+        let _ = self.app_handle.emit(PROGRESS_EVENT, &progress);
+        self.last_emit = Some(Instant::now());
+    }
+}
+
+/// 一次文件传输
+pub struct TransferJob<'a> {
+    pub id: String,
+    pub src: &'a dyn RemoteFs,
+    pub src_path: &'a str,
+    pub dst: &'a dyn RemoteFs,
+    pub dst_path: &'a str,
+    /// 写入 `<dst_path>.part`，已存在时从其末尾继续，完成后重命名
+    pub resume: bool,
+    /// 续传前比较两端已传输部分的 SHA-256，不一致则从头传输
+    pub verify: bool,
+    /// 已传输字节数（含续传起点），供传输队列查询
+    pub progress: Option<Arc<AtomicU64>>,
+}
+
+/// 执行一次可取消的文件复制，期间发送进度事件
+///
+/// 可以事先用 [`register`] 登记传输 ID，使开始前到达的取消请求同样生效。
+pub async fn run(
+    transfers: &TransferMap,
+    app_handle: AppHandle,
+    job: TransferJob<'_>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    cancellable(transfers, &job.id, transfer(app_handle, &job)).await
+}
+
+/// 以 `transfer_id` 登记并执行 `task`，收到取消请求时中止
+pub async fn cancellable<T>(
+    transfers: &TransferMap,
+    transfer_id: &str,
+    task: impl Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    let cancel = register(transfers, transfer_id).await;
+
+    let result = tokio::select! {
+        result = task => result,
+        _ = cancel.notified() => Err(CANCELLED.into()),
+    };
+
+    transfers.lock().await.remove(transfer_id);
+    result
+}
+
+async fn transfer(
+    app_handle: AppHandle,
+    job: &TransferJob<'_>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    // 获取大小失败不影响传输，只是没有百分比与剩余时间
+    let total = job.src.stat(job.src_path).await.ok().map(|entry| entry.size);
+
+    let partial_path = format!("{}{}", job.dst_path, PARTIAL_SUFFIX);
+    let (dst_path, offset) = if job.resume {
+        let offset = resume_offset(job, &partial_path, total).await?;
+        (partial_path.as_str(), offset)
+    } else {
+        (job.dst_path, 0)
+    };
+    if offset > 0 {
+        debug_log!("[TRANSFER] {} resuming at {} bytes", job.id, offset);
+    }
+
+    let mut reporter = ProgressReporter::new(app_handle, job.id.clone(), total, offset);
+
+    let result = {
+        let mut on_progress = |bytes| {
+            if let Some(progress) = &job.progress {
+                progress.store(bytes, Ordering::Relaxed);
+            }
+            reporter.advance(bytes);
+        };
+        copy_file(job.src, job.src_path, job.dst, dst_path, offset, &mut on_progress).await
+    };
+    reporter.emit(true);
+    let written = result?;
+
+    if job.resume {
+        // 部分服务器的 rename 不会覆盖已存在的文件
+        if job.dst.stat(job.dst_path).await.is_ok_and(|entry| !entry.is_dir) {
+            job.dst.remove(job.dst_path, false).await?;
+        }
+        job.dst.rename(&partial_path, job.dst_path).await?;
+    }
+    Ok(offset + written)
+}
+
+/// 执行传输并维护续传记录：开始时写入，只在成功后删除
+///
+/// 出错、暂停或取消时保留记录与 `.part` 文件，重启后仍可续传，用户放弃时由
+/// `fs_discard_interrupted_transfer` 删除。
+pub async fn run_journaled(
+    transfers: &TransferMap,
+    journal: &TransferJournalState,
+    app_handle: AppHandle,
+    job: TransferJob<'_>,
+    record: Option<InterruptedTransfer>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(mut record) = record else {
+        return run(transfers, app_handle, job).await;
+    };
+
+    record.total = job.src.stat(job.src_path).await.ok().map(|entry| entry.size);
+    if let Err(e) = journal.lock().await.record(record.clone()) {
+        debug_log!("[TRANSFER] Failed to record transfer {}: {}", record.transfer_id, e);
+    }
+
+    let result = run(transfers, app_handle, job).await;
+    if result.is_ok() {
+        if let Err(e) = journal.lock().await.remove(&record.transfer_id) {
+            debug_log!("[TRANSFER] Failed to update transfer journal: {}", e);
+        }
+    } else {
+        // 刷新更新时间
+        let _ = journal.lock().await.record(record);
+    }
+    result
+}
+
+/// 根据已存在的未完成文件确定续传起点，无法续传时返回 0
+async fn resume_offset(
+    job: &TransferJob<'_>,
+    partial_path: &str,
+    total: Option<u64>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let existing = match job.dst.stat(partial_path).await {
+        Ok(entry) if !entry.is_dir => entry.size,
+        _ => return Ok(0),
+    };
+
+    // 比源文件还大，说明不是同一文件的前缀
+    if total.is_some_and(|total| existing > total) {
+        debug_log!("[TRANSFER] {} partial file larger than source, restarting", job.id);
+        return Ok(0);
+    }
+
+    if job.verify && existing > 0 {
+        let (src_hash, dst_hash) = tokio::try_join!(
+            hash_prefix(job.src, job.src_path, existing),
+            hash_prefix(job.dst, partial_path, existing),
+        )?;
+        if src_hash != dst_hash {
+            debug_log!("[TRANSFER] {} partial file does not match source, restarting", job.id);
+            return Ok(0);
+        }
+    }
+    Ok(existing)
+}
+
+/// 计算文件前 `len` 字节的 SHA-256，文件较短时计算整个文件
+///
+/// 只发起一次流式读取：SFTP 的 read_range 每次都重新打开文件，FTP 每次都要 REST + RETR + ABOR。
+async fn hash_prefix(
+    fs: &dyn RemoteFs,
+    path: &str,
+    len: u64,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut sink = HashSink { hasher: Sha256::new(), remaining: len };
+    if let Err(e) = fs.read_stream(path, 0, &mut sink).await {
+        // 读满 len 字节后由 sink 返回错误提前结束读取
+        if sink.remaining > 0 {
+            return Err(e);
+        }
+    }
+    Ok(sink.hasher.finalize().to_vec())
+}
+
+/// 对写入的前 `remaining` 字节计算哈希，之后的写入返回错误
+struct HashSink {
+    hasher: Sha256,
+    remaining: u64,
+}
+
+impl AsyncWrite for HashSink {
+    fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if self.remaining == 0 {
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::WriteZero, "hashed prefix complete")));
+        }
+        let n = (buf.len() as u64).min(self.remaining) as usize;
+        self.hasher.update(&buf[..n]);
+        self.remaining -= n as u64;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 登记传输 ID 并返回其取消信号，已登记时返回原有信号
+pub async fn register(transfers: &TransferMap, transfer_id: &str) -> Arc<Notify> {
+    transfers.lock().await
+        .entry(transfer_id.to_string())
+        .or_default()
+        .clone()
+}
+
+/// 取消进行中的传输，传输在下一次让出时中止
+pub async fn cancel(transfers: &TransferMap, transfer_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let cancel = transfers.lock().await
+        .get(transfer_id)
+        .cloned()
+        .ok_or_else(|| format!("Transfer {} not found", transfer_id))?;
+    cancel.notify_one();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_fs::LocalFs;
+
+    #[tokio::test]
+    async fn hash_prefix_stops_at_len() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        let data: Vec<u8> = (0..300_000u32).map(|i| i as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+        let path = path.to_string_lossy().into_owned();
+
+        for len in [0, 1, 65_536, 200_001, 300_000] {
+            let hash = hash_prefix(&LocalFs, &path, len).await.unwrap();
+            assert_eq!(hash, Sha256::digest(&data[..len as usize]).to_vec(), "len {}", len);
+        }
+        // 文件比 len 短时计算整个文件
+        let hash = hash_prefix(&LocalFs, &path, 400_000).await.unwrap();
+        assert_eq!(hash, Sha256::digest(&data).to_vec());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::models::Connection;
+use crate::serial::{session, SerialPortEntry, SerialSession};
+use crate::terminal::commands::lookup_as;
+use crate::terminal::TerminalRegistry;
+use tauri::{AppHandle, State};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 列出可用串口
+#[tauri::command]
+pub async fn list_serial_ports() -> Result<Vec<SerialPortEntry>, String> {
+    session::available_ports().map_err(|e| format!("Failed to list serial ports: {}", e))
+}
+
+/// 打开串口终端会话，之后通过通用终端命令读写
+#[tauri::command]
+pub async fn create_serial_terminal(
+    config: Connection,
+    terminal_id: Option<String>,
+    sessions: State<'_, TerminalRegistry>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[CMD] create_serial_terminal called for connection: {}", config.name);
+
+    let terminal_id = terminal_id.unwrap_or_else(|| config.id.clone());
+
+    // 检查是否已存在
+    if sessions.lock().await.contains_key(&terminal_id) {
+        debug_log!("[CMD] Serial terminal {} already exists, skipping creation", terminal_id);
+        return Ok(());
+    }
+
+    let serial_config = config.serial_config
+        .ok_or("Serial config is required for Serial connection")?;
+
+    // 打开串口是阻塞操作（部分驱动会等待设备就绪），放到阻塞线程池中执行，不持有会话表锁
+    let id = terminal_id.clone();
+    let session = tokio::task::spawn_blocking(move || {
+        SerialSession::new(serial_config, id, app_handle)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Failed to create serial session: {}", e))?
+    .map_err(|e| format!("Failed to create serial session: {}", e))?;
+
+    sessions.lock().await.insert(terminal_id.clone(), Arc::new(session));
+    debug_log!("[CMD] create_serial_terminal completed successfully for id: {}", terminal_id);
+    Ok(())
+}
+
+/// 发送 BREAK 信号，默认持续 250ms
+#[tauri::command]
+pub async fn send_serial_break(
+    id: String,
+    duration_ms: Option<u64>,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<(), String> {
+    let session = lookup_as::<SerialSession>(&sessions, &id).await?;
+    session
+        .send_break(Duration::from_millis(duration_ms.unwrap_or(250)))
+        .await
+        .map_err(|e| format!("Failed to send BREAK: {}", e))
+}
@@ -0,0 +1,4 @@
+mod session;
+pub mod commands;
+
+pub use session::{SerialSession, SerialPortEntry};
@@ -0,0 +1,275 @@
+use crate::models::{SerialConfig, SerialFlowControl, SerialParity};
+use crate::terminal::{TerminalBackend, TerminalStatus};
+use async_trait::async_trait;
+use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+use serde::Serialize;
+use std::any::Any;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 读取超时，超时后检查会话是否已关闭
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// 可用串口信息，返回给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct SerialPortEntry {
+    pub name: String,
+    /// USB / PCI / Bluetooth / Unknown
+    pub port_type: String,
+    /// USB 设备的厂商与产品名称
+    pub description: Option<String>,
+}
+
+/// 列出系统中的串口
+pub fn available_ports() -> Result<Vec<SerialPortEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    let ports = serialport::available_ports()?;
+    Ok(ports.into_iter()
+        .map(|port| {
+            let (port_type, description) = match port.port_type {
+                serialport::SerialPortType::UsbPort(usb) => {
+                    let description = [usb.manufacturer, usb.product]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    ("USB", (!description.is_empty()).then_some(description))
+                }
+                serialport::SerialPortType::PciPort => ("PCI", None),
+                serialport::SerialPortType::BluetoothPort => ("Bluetooth", None),
+                serialport::SerialPortType::Unknown => ("Unknown", None),
+            };
+            SerialPortEntry {
+                name: port.port_name,
+                port_type: port_type.to_string(),
+                description,
+            }
+        })
+        .collect())
+}
+
+pub struct SerialSession {
+    #[allow(dead_code)]
+    id: String,
+    /// 关闭后为 None，释放设备以便其他程序打开
+    port: Arc<Mutex<Option<Box<dyn SerialPort>>>>,
+    /// 会话关闭或设备断开后置位，读取线程在下一次读取超时时退出
+    closed: Arc<AtomicBool>,
+}
+
+impl SerialSession {
+    /// 打开串口并启动读取线程
+    pub fn new(
+        config: SerialConfig,
+        terminal_id: String,
+        app_handle: AppHandle,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        debug_log!("[SERIAL] Opening {} at {} baud", config.device, config.baud_rate);
+
+        let port = serialport::new(&config.device, config.baud_rate)
+            .data_bits(data_bits(config.data_bits)?)
+            .parity(match config.parity {
+                SerialParity::None => Parity::None,
+                SerialParity::Odd => Parity::Odd,
+                SerialParity::Even => Parity::Even,
+            })
+            .stop_bits(match config.stop_bits {
+                1 => StopBits::One,
+                2 => StopBits::Two,
+                other => return Err(format!("Unsupported stop bits: {}", other).into()),
+            })
+            .flow_control(match config.flow_control {
+                SerialFlowControl::None => FlowControl::None,
+                SerialFlowControl::Software => FlowControl::Software,
+                SerialFlowControl::Hardware => FlowControl::Hardware,
+            })
+            .timeout(READ_TIMEOUT)
+            .open()
+            .map_err(|e| format!("Failed to open {}: {}", config.device, e))?;
+
+        debug_log!("[SERIAL] ✅ Opened {}", config.device);
+
+        let mut reader = port.try_clone()?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let closed_clone = closed.clone();
+        let terminal_id_clone = terminal_id.clone();
+
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                if closed_clone.load(Ordering::Relaxed) {
+                    break;
+                }
+                match reader.read(&mut buf) {
+                    Ok(0) => {}
+                    Ok(n) => {
+                        let output = String::from_utf8_lossy(&buf[..n]).to_string();
+                        let _ = app_handle.emit(&format!("terminal-output-{}", terminal_id_clone), output);
+                    }
+                    Err(e) if e.kind() == ErrorKind::TimedOut || e.kind() == ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        // 设备被拔出等情况
+                        debug_log!("[SERIAL-READ] terminal={} - Read error: {}", terminal_id_clone, e);
+                        if !closed_clone.swap(true, Ordering::Relaxed) {
+                            let _ = app_handle.emit(&format!("terminal-exit-{}", terminal_id_clone), ());
+                        }
+                        break;
+                    }
+                }
+            }
+            debug_log!("[SERIAL-READ] terminal={} - Reader thread exiting", terminal_id_clone);
+        });
+
+        Ok(Self {
+            id: terminal_id,
+            port: Arc::new(Mutex::new(Some(port))),
+            closed,
+        })
+    }
+
+    /// 发送 BREAK 信号，持续 `duration`（常用于进入设备的 ROMMON / 引导菜单）
+    ///
+    /// 整个 BREAK 期间持有串口锁，避免写入的数据夹在 BREAK 中间。
+    pub async fn send_break(&self, duration: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        debug_log!("[SERIAL] Sending BREAK for {:?}", duration);
+        self.with_port(move |port| {
+            port.set_break()?;
+            std::thread::sleep(duration);
+            Ok(port.clear_break()?)
+        })
+        .await
+    }
+
+    /// 在阻塞线程池中操作串口：写入在流控暂停时可能长时间持有串口锁，不能在异步线程上等待
+    async fn with_port<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Box<dyn SerialPort>) -> Result<T, Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        let port = self.port.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut port = port.lock().unwrap();
+            f(port.as_mut().ok_or("Serial port is closed")?)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for SerialSession {
+    /// 串口写入是阻塞操作（流控暂停时可能长时间阻塞），放到阻塞线程池中执行
+    async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let data = data.to_vec();
+        self.with_port(move |port| {
+            port.write_all(&data)?;
+            port.flush()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 串口没有窗口大小
+    async fn resize(&self, _rows: u16, _cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// 释放串口；读取线程持有的副本在下一次读取超时时随线程退出一并释放
+    async fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        let port = self.port.clone();
+        let _ = tokio::task::spawn_blocking(move || drop(port.lock().unwrap().take())).await;
+    }
+
+    fn status(&self) -> TerminalStatus {
+        if self.closed.load(Ordering::Relaxed) {
+            TerminalStatus::Closed
+        } else {
+            TerminalStatus::Connected
+        }
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+impl Drop for SerialSession {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+fn data_bits(bits: u8) -> Result<DataBits, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match bits {
+        5 => DataBits::Five,
+        6 => DataBits::Six,
+        7 => DataBits::Seven,
+        8 => DataBits::Eight,
+        other => return Err(format!("Unsupported data bits: {}", other).into()),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serialport::TTYPort;
+
+    fn session_on(port: TTYPort) -> SerialSession {
+        SerialSession {
+            id: "serial-test".to_string(),
+            port: Arc::new(Mutex::new(Some(Box::new(port)))),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[tokio::test]
+    async fn write_reaches_device_and_close_releases_it() {
+        let (mut master, slave) = TTYPort::pair().unwrap();
+        master.set_timeout(Duration::from_secs(2)).unwrap();
+        let session = session_on(slave);
+
+        session.write(b"hello").await.unwrap();
+        let mut buf = [0u8; 5];
+        master.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+
+        session.close().await;
+        assert!(matches!(session.status(), TerminalStatus::Closed));
+        assert!(session.write(b"again").await.is_err());
+        assert!(session.send_break(Duration::from_millis(1)).await.is_err());
+
+        // 从设备一端已关闭时，主设备读取会收到挂断而不是超时
+        let err = master.read(&mut buf).unwrap_err();
+        assert_ne!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn break_waits_for_the_port_off_the_runtime() {
+        let (_master, slave) = TTYPort::pair().unwrap();
+        let session = Arc::new(session_on(slave));
+
+        // 模拟被流控阻塞的写入长时间持有串口锁
+        let port = session.port.clone();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || {
+            let _guard = port.lock().unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(300));
+        });
+        locked_rx.recv().unwrap();
+
+        let started = std::time::Instant::now();
+        let pending = tokio::spawn({
+            let session = session.clone();
+            async move { session.send_break(Duration::from_millis(1)).await }
+        });
+        // 单线程运行时：等待串口锁若阻塞了运行时，这次睡眠会被推迟到锁释放之后
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert!(!pending.is_finished());
+
+        pending.await.unwrap().unwrap();
+        holder.join().unwrap();
+    }
+}
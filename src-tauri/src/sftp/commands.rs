@@ -1,171 +1,68 @@
 use crate::models::Connection;
-use crate::sftp::{SftpSessionWrapper, session::FileEntry};
-use tauri::State;
+use crate::remote_fs::FsRegistry;
+use crate::sftp::SftpSessionWrapper;
+use crate::ssh::{pool, HostKeyResponseMap, MfaResponseMap, SshTransportPool};
+use crate::vault::VaultState;
+use tauri::{AppHandle, State};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use std::collections::HashMap;
 
-pub type SftpSessionMap = Arc<Mutex<HashMap<String, SftpSessionWrapper>>>;
-
-/// 创建 SFTP 会话映射
-pub fn create_sftp_session_map() -> SftpSessionMap {
-    Arc::new(Mutex::new(HashMap::new()))
-}
-
-/// 创建 SFTP 会话
+/// 创建 SFTP 会话并注册到文件系统注册表，之后通过 fs_* 命令操作
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn sftp_connect(
-    config: Connection,
-    sessions: State<'_, SftpSessionMap>,
+    mut config: Connection,
+    session_id: Option<String>,
+    registry: State<'_, FsRegistry>,
+    pool: State<'_, SshTransportPool>,
+    mfa_channels: State<'_, MfaResponseMap>,
+    host_key_channels: State<'_, HostKeyResponseMap>,
+    vault: State<'_, VaultState>,
+    app_handle: AppHandle,
 ) -> Result<(), String> {
     debug_log!("[SFTP-CMD] sftp_connect called for connection: {}", config.name);
 
+    // 会话 ID 缺省为连接 ID；底层 SSH 连接按连接 ID 共享
+    let session_id = session_id.unwrap_or_else(|| config.id.clone());
+
     // 检查是否已存在
     {
-        let sessions_guard = sessions.lock().await;
-        if sessions_guard.contains_key(&config.id) {
-            debug_log!("[SFTP-CMD] SFTP session {} already exists", config.id);
+        let registry_guard = registry.lock().await;
+        if registry_guard.contains_key(&session_id) {
+            debug_log!("[SFTP-CMD] SFTP session {} already exists", session_id);
             return Ok(());
         }
     }
 
+    // 把保险库引用替换为明文凭据
+    vault.lock().await
+        .resolve_connection(&mut config)
+        .map_err(|e| format!("Failed to load credentials: {}", e))?;
+
     // 获取 SSH 配置
     let ssh_config = config.ssh_config
         .ok_or("SSH config is required for SFTP connection")?;
 
+    // 复用（或建立）该连接的 SSH 传输层
+    let transport = pool::acquire(
+        pool.inner(),
+        &config.id,
+        &ssh_config,
+        &session_id,
+        &app_handle,
+        mfa_channels.inner(),
+        host_key_channels.inner(),
+    )
+    .await
+    .map_err(|e| format!("Failed to create SFTP session: {}", e))?;
+
     // 创建 SFTP 会话
-    let session = SftpSessionWrapper::new(ssh_config, config.id.clone())
+    let session = SftpSessionWrapper::new(ssh_config, session_id.clone(), transport)
         .await
         .map_err(|e| format!("Failed to create SFTP session: {}", e))?;
 
-    // 保存会话
-    sessions.lock().await.insert(config.id.clone(), session);
-    
-    debug_log!("[SFTP-CMD] sftp_connect completed successfully for id: {}", config.id);
-    Ok(())
-}
-
-/// 列出目录内容
-#[tauri::command]
-pub async fn sftp_list_dir(
-    id: String,
-    path: String,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<Vec<FileEntry>, String> {
-    debug_log!("[SFTP-CMD] sftp_list_dir: id={}, path={}", id, path);
-    
-    let sessions = sessions.lock().await;
-    let session = sessions.get(&id)
-        .ok_or_else(|| format!("SFTP session {} not found", id))?;
-
-    let entries = session.list_dir(&path).await
-        .map_err(|e| format!("Failed to list directory: {}", e))?;
-
-    debug_log!("[SFTP-CMD] Listed {} entries in {}", entries.len(), path);
-    Ok(entries)
-}
-
-/// 下载文件
-#[tauri::command]
-pub async fn sftp_download_file(
-    id: String,
-    remote_path: String,
-    local_path: String,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SFTP-CMD] sftp_download_file: id={}, remote={}, local={}", id, remote_path, local_path);
-    
-    let sessions = sessions.lock().await;
-    let session = sessions.get(&id)
-        .ok_or_else(|| format!("SFTP session {} not found", id))?;
-
-    let data = session.read_file(&remote_path).await
-        .map_err(|e| format!("Failed to read remote file: {}", e))?;
-
-    std::fs::write(&local_path, &data)
-        .map_err(|e| format!("Failed to write local file: {}", e))?;
-
-    debug_log!("[SFTP-CMD] Downloaded {} bytes to {}", data.len(), local_path);
-    Ok(())
-}
-
-/// 上传文件
-#[tauri::command]
-pub async fn sftp_upload_file(
-    id: String,
-    local_path: String,
-    remote_path: String,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SFTP-CMD] sftp_upload_file: id={}, local={}, remote={}", id, local_path, remote_path);
-    
-    let data = std::fs::read(&local_path)
-        .map_err(|e| format!("Failed to read local file: {}", e))?;
-
-    let sessions = sessions.lock().await;
-    let session = sessions.get(&id)
-        .ok_or_else(|| format!("SFTP session {} not found", id))?;
-
-    session.write_file(&remote_path, &data).await
-        .map_err(|e| format!("Failed to write remote file: {}", e))?;
-
-    debug_log!("[SFTP-CMD] Uploaded {} bytes to {}", data.len(), remote_path);
-    Ok(())
-}
-
-/// 删除文件或目录
-#[tauri::command]
-pub async fn sftp_remove(
-    id: String,
-    path: String,
-    is_dir: bool,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SFTP-CMD] sftp_remove: id={}, path={}, is_dir={}", id, path, is_dir);
-    
-    let sessions = sessions.lock().await;
-    let session = sessions.get(&id)
-        .ok_or_else(|| format!("SFTP session {} not found", id))?;
-
-    if is_dir {
-        session.remove_dir(&path).await
-            .map_err(|e| format!("Failed to remove directory: {}", e))?;
-    } else {
-        session.remove_file(&path).await
-            .map_err(|e| format!("Failed to remove file: {}", e))?;
-    }
-
-    debug_log!("[SFTP-CMD] Removed {}", path);
-    Ok(())
-}
-
-/// 创建目录
-#[tauri::command]
-pub async fn sftp_create_dir(
-    id: String,
-    path: String,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SFTP-CMD] sftp_create_dir: id={}, path={}", id, path);
+    // 注册会话
+    registry.lock().await.insert(session_id.clone(), Arc::new(session));
     
-    let sessions = sessions.lock().await;
-    let session = sessions.get(&id)
-        .ok_or_else(|| format!("SFTP session {} not found", id))?;
-
-    session.create_dir(&path).await
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    debug_log!("[SFTP-CMD] Created directory {}", path);
-    Ok(())
-}
-
-/// 关闭 SFTP 会话
-#[tauri::command]
-pub async fn sftp_disconnect(
-    id: String,
-    sessions: State<'_, SftpSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SFTP-CMD] sftp_disconnect: id={}", id);
-    sessions.lock().await.remove(&id);
+    debug_log!("[SFTP-CMD] sftp_connect completed successfully for id: {}", session_id);
     Ok(())
 }
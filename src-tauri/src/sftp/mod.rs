@@ -1,5 +1,6 @@
 // SFTP 模块
 pub mod session;
+mod pipeline;
 pub mod commands;
 
 pub use session::SftpSessionWrapper;
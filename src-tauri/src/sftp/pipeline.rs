@@ -0,0 +1,142 @@
+use futures::stream::{self, FuturesUnordered, StreamExt};
+use russh_sftp::client::error::Error as SftpError;
+use russh_sftp::client::RawSftpSession;
+use russh_sftp::protocol::{FileAttributes, OpenFlags, StatusCode};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// 单个读写请求的数据量（OpenSSH 等服务器均支持）
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 同时在途的请求数，内存占用上限约为 CHUNK_SIZE * MAX_IN_FLIGHT
+const MAX_IN_FLIGHT: usize = 32;
+
+/// 流水线下载：同时发出多个 READ 请求，按偏移顺序写入 `sink`
+pub async fn download(
+    raw: &RawSftpSession,
+    path: &str,
+    offset: u64,
+    sink: &mut (dyn AsyncWrite + Send + Unpin),
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let handle = raw.open(path, OpenFlags::READ, FileAttributes::empty()).await
+        .map_err(|e| format!("Failed to open file: {}", e))?
+        .handle;
+
+    let result = async {
+        // 已知文件大小时不再请求末尾之后的数据
+        let end = raw.fstat(handle.as_str()).await.ok()
+            .and_then(|attrs| attrs.attrs.size)
+            .unwrap_or(u64::MAX);
+
+        let mut chunks = stream::iter((offset..end).step_by(CHUNK_SIZE))
+            .map(|chunk_offset| read_chunk(raw, &handle, chunk_offset))
+            .buffered(MAX_IN_FLIGHT);
+
+        let mut total = 0u64;
+        while let Some(chunk) = chunks.next().await {
+            let data = chunk?;
+            sink.write_all(&data).await?;
+            total += data.len() as u64;
+            // 不足一块说明已到文件末尾
+            if data.len() < CHUNK_SIZE {
+                break;
+            }
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(total)
+    }
+    .await;
+
+    let _ = raw.close(handle).await;
+    result
+}
+
+/// 流水线上传：从 `source` 顺序读取，同时发出多个 WRITE 请求
+///
+/// `offset` 为 0 时创建或覆盖文件，否则在已有文件的 `offset` 处续写。
+pub async fn upload(
+    raw: &RawSftpSession,
+    path: &str,
+    offset: u64,
+    source: &mut (dyn AsyncRead + Send + Unpin),
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let flags = if offset == 0 {
+        OpenFlags::CREATE | OpenFlags::TRUNCATE | OpenFlags::WRITE
+    } else {
+        OpenFlags::WRITE
+    };
+    let handle = raw.open(path, flags, FileAttributes::empty()).await
+        .map_err(|e| format!("Failed to create file: {}", e))?
+        .handle;
+
+    let result = async {
+        let mut in_flight = FuturesUnordered::new();
+        let mut position = offset;
+        loop {
+            let mut buf = vec![0u8; CHUNK_SIZE];
+            let n = read_full(source, &mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            buf.truncate(n);
+
+            in_flight.push(raw.write(handle.as_str(), position, buf));
+            position += n as u64;
+
+            // 窗口已满时等待任一请求完成
+            if in_flight.len() >= MAX_IN_FLIGHT {
+                if let Some(result) = in_flight.next().await {
+                    result.map_err(|e| format!("Failed to write data: {}", e))?;
+                }
+            }
+
+            if n < CHUNK_SIZE {
+                break;
+            }
+        }
+        while let Some(result) = in_flight.next().await {
+            result.map_err(|e| format!("Failed to write data: {}", e))?;
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(position - offset)
+    }
+    .await;
+
+    // 服务器在 CLOSE 时才可能报告写入错误（如磁盘已满）
+    let closed = raw.close(handle).await;
+    let bytes = result?;
+    closed.map_err(|e| format!("Failed to close file: {}", e))?;
+    Ok(bytes)
+}
+
+/// 读取一整块；服务器可能返回较短的数据，需要继续请求剩余部分
+async fn read_chunk(
+    raw: &RawSftpSession,
+    handle: &str,
+    offset: u64,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut data = Vec::with_capacity(CHUNK_SIZE);
+    while data.len() < CHUNK_SIZE {
+        let remaining = (CHUNK_SIZE - data.len()) as u32;
+        match raw.read(handle, offset + data.len() as u64, remaining).await {
+            Ok(chunk) if chunk.data.is_empty() => break,
+            Ok(chunk) => data.extend_from_slice(&chunk.data),
+            Err(SftpError::Status(status)) if status.status_code == StatusCode::Eof => break,
+            Err(e) => return Err(format!("Failed to read data: {}", e).into()),
+        }
+    }
+    Ok(data)
+}
+
+/// 读满缓冲区或直到 EOF，返回读取的字节数
+async fn read_full(
+    source: &mut (dyn AsyncRead + Send + Unpin),
+    buf: &mut [u8],
+) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = source.read(&mut buf[filled..]).await?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
@@ -1,16 +1,26 @@
-use crate::models::{SshConfig, SshAuth};
-use russh::*;
-use russh::keys::key::PrivateKeyWithHashAlg;
-use russh::keys::PublicKey;
-use russh_sftp::client::SftpSession;
+use crate::models::SshConfig;
+use crate::remote_fs::filesystem::file_name;
+use crate::remote_fs::{FileDetails, FileEntry, RemoteFs};
+use crate::sftp::pipeline;
+use crate::ssh::SharedTransport;
+use async_trait::async_trait;
+use russh_sftp::client::fs::Metadata;
+use russh_sftp::client::{RawSftpSession, SftpSession};
+use std::io::SeekFrom;
 use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite};
 use tokio::sync::Mutex;
-use tokio::io::AsyncWriteExt;
 
 /// SFTP 会话包装器，用于管理 SFTP 连接
 pub struct SftpSessionWrapper {
     pub id: String,
     pub sftp: Arc<Mutex<SftpSession>>,
+    /// 底层协议会话，用于流水线传输（同时发出多个读写请求）
+    raw: RawSftpSession,
+    /// 服务器按 OpenSSH 的顺序（targetpath 在前）解析 SSH_FXP_SYMLINK
+    openssh_symlink_order: bool,
+    /// 共享的 SSH 连接，SFTP 关闭后若无其他使用者则断开
+    _transport: Arc<SharedTransport>,
 }
 
 impl SftpSessionWrapper {
@@ -18,89 +28,71 @@ impl SftpSessionWrapper {
     pub async fn new(
         config: SshConfig,
         session_id: String,
+        transport: Arc<SharedTransport>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         debug_log!("[SFTP] ===== Starting SFTP Connection =====");
         debug_log!("[SFTP] Target: {}@{}:{}", config.username, config.host, config.port);
 
-        // 创建 SSH 配置
-        let ssh_config = client::Config {
-            inactivity_timeout: Some(std::time::Duration::from_secs(3600)),
-            ..Default::default()
-        };
-
-        // SSH 客户端处理器
-        struct Client {}
-
-        impl client::Handler for Client {
-            type Error = russh::Error;
-
-            fn check_server_key(
-                &mut self,
-                _server_public_key: &PublicKey,
-            ) -> impl std::future::Future<Output = Result<bool, Self::Error>> + Send {
-                async { Ok(true) }
-            }
-        }
-
-        // 创建客户端并连接
-        let mut session = client::connect(
-            Arc::new(ssh_config),
-            (config.host.as_str(), config.port),
-            Client {},
-        ).await.map_err(|e| format!("Connection failed: {}", e))?;
-
-        debug_log!("[SFTP] ✅ TCP connection established");
-
-        // 认证
-        let auth_result = match &config.auth {
-            SshAuth::Password(password) => {
-                session.authenticate_password(config.username.clone(), password.clone()).await
-                    .map_err(|e| format!("Authentication error: {}", e))?
-            }
-            SshAuth::PublicKey { private_key_path, passphrase } => {
-                let key_pair = russh::keys::decode_secret_key(
-                    &std::fs::read_to_string(private_key_path)?,
-                    passphrase.as_deref(),
-                )?;
-                // russh 0.56 需要使用 PrivateKeyWithHashAlg
-                let key_with_alg = PrivateKeyWithHashAlg::new(
-                    Arc::new(key_pair),
-                    session.best_supported_rsa_hash().await?.flatten(),
-                );
-                session.authenticate_publickey(config.username.clone(), key_with_alg).await?
-            }
-        };
-
-        if !auth_result.success() {
-            return Err("SSH authentication failed".into());
-        }
-
-        debug_log!("[SFTP] ✅ Authenticated successfully");
-
-        // 打开 SFTP 子系统通道
-        let channel = session.channel_open_session().await
-            .map_err(|e| format!("Failed to open channel: {}", e))?;
-
-        debug_log!("[SFTP] Requesting SFTP subsystem...");
-        channel.request_subsystem(false, "sftp").await
-            .map_err(|e| format!("Failed to request SFTP subsystem: {}", e))?;
-
-        debug_log!("[SFTP] ✅ SFTP subsystem requested");
-
         // 创建 SFTP 会话
-        let sftp = SftpSession::new(channel.into_stream()).await
+        let sftp = SftpSession::new(open_subsystem(&transport).await?).await
             .map_err(|e| format!("Failed to create SFTP session: {}", e))?;
 
+        // 文件传输使用独立的子系统通道，不阻塞目录浏览
+        let raw = RawSftpSession::new(open_subsystem(&transport).await?);
+        let version = raw.init().await
+            .map_err(|e| format!("Failed to initialize SFTP transfer channel: {}", e))?;
+        let openssh_symlink_order = is_openssh_server(version.extensions.keys());
+        debug_log!("[SFTP] Server extensions: {:?}", version.extensions.keys().collect::<Vec<_>>());
+
         debug_log!("[SFTP] ===== SFTP Session Established =====");
 
         Ok(Self {
             id: session_id,
             sftp: Arc::new(Mutex::new(sftp)),
+            raw,
+            openssh_symlink_order,
+            _transport: transport,
         })
     }
+}
+
+/// 打开 SFTP 子系统通道
+async fn open_subsystem(
+    transport: &SharedTransport,
+) -> Result<russh::ChannelStream<russh::client::Msg>, Box<dyn std::error::Error + Send + Sync>> {
+    // 在共享连接上打开会话通道
+    let channel = transport.handle.read().await.channel_open_session().await
+        .map_err(|e| format!("Failed to open channel: {}", e))?;
 
-    /// 列出目录内容
-    pub async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    debug_log!("[SFTP] Requesting SFTP subsystem...");
+    channel.request_subsystem(false, "sftp").await
+        .map_err(|e| format!("Failed to request SFTP subsystem: {}", e))?;
+
+    debug_log!("[SFTP] ✅ SFTP subsystem requested");
+    Ok(channel.into_stream())
+}
+
+/// 服务器是否为 OpenSSH sftp-server（或兼容实现）：以是否声明 @openssh.com 扩展判断
+fn is_openssh_server<'a>(mut extensions: impl Iterator<Item = &'a String>) -> bool {
+    extensions.any(|name| name.ends_with("@openssh.com"))
+}
+
+/// SSH_FXP_SYMLINK 请求中依次发送的两个路径
+///
+/// 协议草案规定 linkpath 在前，但 OpenSSH 的 sftp-server 把第一个参数当作链接目标，
+/// 兼容它的服务器也沿用了这一顺序。
+fn symlink_request_paths<'a>(openssh_order: bool, target: &'a str, link: &'a str) -> (&'a str, &'a str) {
+    if openssh_order {
+        (target, link)
+    } else {
+        (link, target)
+    }
+}
+
+/// 数据传输期间不持有会话锁，同一会话上的多个传输可以并发进行
+#[async_trait]
+impl RemoteFs for SftpSessionWrapper {
+    async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, Box<dyn std::error::Error + Send + Sync>> {
         let sftp = self.sftp.lock().await;
         let read_dir = sftp.read_dir(path).await
             .map_err(|e| format!("Failed to read directory: {}", e))?;
@@ -109,70 +101,193 @@ impl SftpSessionWrapper {
         for entry in read_dir {
             let file_name = entry.file_name();
             let attrs = entry.metadata();
-            entries.push(FileEntry {
-                name: file_name,
-                is_dir: attrs.is_dir(),
-                size: attrs.size.unwrap_or(0),
-                permissions: attrs.permissions.map(|p| format!("{:o}", p)),
-                modified: attrs.mtime.map(|t| t as u64),
-            });
+            entries.push(to_entry(file_name, &attrs));
         }
 
         Ok(entries)
     }
 
-    /// 读取文件内容
-    pub async fn read_file(&self, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn stat(&self, path: &str) -> Result<FileEntry, Box<dyn std::error::Error + Send + Sync>> {
         let sftp = self.sftp.lock().await;
-        let data = sftp.read(path).await
+        let attrs = sftp.metadata(path).await
+            .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+        Ok(to_entry(file_name(path), &attrs))
+    }
+
+    async fn read_range(&self, path: &str, offset: u64, len: u64) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut file = self.sftp.lock().await.open(path).await
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut data = Vec::new();
+        file.take(len).read_to_end(&mut data).await
             .map_err(|e| format!("Failed to read file: {}", e))?;
         Ok(data)
     }
 
-    /// 写入文件内容（创建或覆盖）
-    pub async fn write_file(&self, path: &str, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let sftp = self.sftp.lock().await;
-        // 使用 create 方法创建文件（会创建新文件或截断已存在的文件）
-        let mut file = sftp.create(path).await
-            .map_err(|e| format!("Failed to create file: {}", e))?;
-        // 写入数据
-        file.write_all(data).await
-            .map_err(|e| format!("Failed to write data: {}", e))?;
-        // 文件会在 drop 时自动关闭
-        Ok(())
+    async fn read_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        sink: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        pipeline::download(&self.raw, path, offset, sink).await
+    }
+
+    async fn write_stream(
+        &self,
+        path: &str,
+        offset: u64,
+        source: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        pipeline::upload(&self.raw, path, offset, source).await
     }
 
-    /// 删除文件
-    pub async fn remove_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn rename(&self, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let sftp = self.sftp.lock().await;
-        sftp.remove_file(path).await
-            .map_err(|e| format!("Failed to remove file: {}", e))?;
+        sftp.rename(from, to).await
+            .map_err(|e| format!("Failed to rename: {}", e))?;
         Ok(())
     }
 
-    /// 删除目录
-    pub async fn remove_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn remove(&self, path: &str, is_dir: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let sftp = self.sftp.lock().await;
-        sftp.remove_dir(path).await
-            .map_err(|e| format!("Failed to remove directory: {}", e))?;
+        if is_dir {
+            sftp.remove_dir(path).await
+                .map_err(|e| format!("Failed to remove directory: {}", e))?;
+        } else {
+            sftp.remove_file(path).await
+                .map_err(|e| format!("Failed to remove file: {}", e))?;
+        }
         Ok(())
     }
 
-    /// 创建目录
-    pub async fn create_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn create_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let sftp = self.sftp.lock().await;
         sftp.create_dir(path).await
             .map_err(|e| format!("Failed to create directory: {}", e))?;
         Ok(())
     }
+
+    async fn chmod(&self, path: &str, mode: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let attrs = Metadata {
+            permissions: Some(mode & 0o7777),
+            ..Metadata::empty()
+        };
+        sftp.set_metadata(path, attrs).await
+            .map_err(|e| format!("Failed to change permissions: {}", e))?;
+        Ok(())
+    }
+
+    async fn symlink(&self, target: &str, link: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (first, second) = symlink_request_paths(self.openssh_symlink_order, target, link);
+        let sftp = self.sftp.lock().await;
+        sftp.symlink(first, second).await
+            .map_err(|e| format!("Failed to create symlink: {}", e))?;
+        Ok(())
+    }
+
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let target = sftp.read_link(path).await
+            .map_err(|e| format!("Failed to read symlink: {}", e))?;
+        Ok(target)
+    }
+
+    async fn details(&self, path: &str) -> Result<FileDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let attrs = sftp.symlink_metadata(path).await
+            .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+        let is_symlink = attrs.file_type().is_symlink();
+        let link_target = if is_symlink {
+            sftp.read_link(path).await.ok()
+        } else {
+            None
+        };
+
+        Ok(FileDetails {
+            path: path.to_string(),
+            name: file_name(path),
+            is_dir: attrs.is_dir(),
+            is_symlink,
+            size: attrs.size.unwrap_or(0),
+            permissions: attrs.permissions.map(|p| format!("{:o}", p & 0o7777)),
+            uid: attrs.uid,
+            gid: attrs.gid,
+            owner: attrs.user.clone(),
+            group: attrs.group.clone(),
+            accessed: attrs.atime.map(|t| t as u64),
+            modified: attrs.mtime.map(|t| t as u64),
+            link_target,
+        })
+    }
+
+    /// SFTP v3 的 UIDGID 必须同时设置属主与属组
+    async fn chown(&self, path: &str, uid: u32, gid: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let attrs = Metadata {
+            uid: Some(uid),
+            gid: Some(gid),
+            ..Metadata::empty()
+        };
+        sftp.set_metadata(path, attrs).await
+            .map_err(|e| format!("Failed to change owner: {}", e))?;
+        Ok(())
+    }
+
+    async fn canonicalize(&self, path: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let resolved = sftp.canonicalize(path).await
+            .map_err(|e| format!("Failed to resolve {}: {}", path, e))?;
+        Ok(resolved)
+    }
+
+    /// SFTP 的 ACMODTIME 必须同时设置访问时间，取相同值
+    async fn set_modified(&self, path: &str, mtime: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sftp = self.sftp.lock().await;
+        let attrs = Metadata {
+            atime: Some(mtime as u32),
+            mtime: Some(mtime as u32),
+            ..Metadata::empty()
+        };
+        sftp.set_metadata(path, attrs).await
+            .map_err(|e| format!("Failed to set modification time: {}", e))?;
+        Ok(())
+    }
+}
+
+fn to_entry(name: String, attrs: &Metadata) -> FileEntry {
+    FileEntry {
+        name,
+        is_dir: attrs.is_dir(),
+        is_symlink: attrs.file_type().is_symlink(),
+        size: attrs.size.unwrap_or(0),
+        permissions: attrs.permissions.map(|p| format!("{:o}", p)),
+        modified: attrs.mtime.map(|t| t as u64),
+    }
 }
 
-/// 文件条目信息
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct FileEntry {
-    pub name: String,
-    pub is_dir: bool,
-    pub size: u64,
-    pub permissions: Option<String>,
-    pub modified: Option<u64>,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_openssh_from_extensions() {
+        let openssh = ["posix-rename@openssh.com".to_string(), "hardlink@openssh.com".to_string()];
+        assert!(is_openssh_server(openssh.iter()));
+
+        let other = ["limits".to_string(), "check-file".to_string()];
+        assert!(!is_openssh_server(other.iter()));
+        assert!(!is_openssh_server([].iter()));
+    }
+
+    #[test]
+    fn openssh_receives_target_first() {
+        assert_eq!(symlink_request_paths(true, "/data/real", "/home/u/link"), ("/data/real", "/home/u/link"));
+    }
+
+    #[test]
+    fn other_servers_receive_linkpath_first() {
+        assert_eq!(symlink_request_paths(false, "/data/real", "/home/u/link"), ("/home/u/link", "/data/real"));
+    }
 }
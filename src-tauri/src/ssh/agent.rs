@@ -0,0 +1,71 @@
+use russh::client::{self, AuthResult};
+use russh::MethodSet;
+use russh::keys::agent::client::AgentClient;
+
+/// Windows 上 OpenSSH agent 的默认命名管道
+#[cfg(windows)]
+const DEFAULT_AGENT_PIPE: &str = r"\\.\pipe\openssh-ssh-agent";
+
+/// 使用 SSH agent 中的身份进行公钥认证
+///
+/// 依次尝试 agent 提供的每个身份，直到服务器接受为止。
+/// 返回最后一次的认证结果，调用方可据此继续 keyboard-interactive（MFA）；
+/// 无法连接 agent 或 agent 中没有身份时同样返回认证失败，而不是错误。
+/// `socket_path` 为空时使用 SSH_AUTH_SOCK（Windows 上为 OpenSSH agent 命名管道）。
+pub async fn authenticate_with_agent<H: client::Handler>(
+    session: &mut client::Handle<H>,
+    username: &str,
+    socket_path: Option<&str>,
+) -> Result<AuthResult, Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(unix)]
+    let agent = match socket_path {
+        Some(path) => AgentClient::connect_uds(path).await,
+        None => AgentClient::connect_env().await,
+    };
+    #[cfg(windows)]
+    let agent = AgentClient::connect_named_pipe(socket_path.unwrap_or(DEFAULT_AGENT_PIPE)).await;
+
+    let mut agent = match agent {
+        Ok(agent) => agent,
+        Err(e) => {
+            debug_log!("[SSH-AGENT] ❌ Failed to connect to SSH agent: {}", e);
+            return Ok(not_attempted());
+        }
+    };
+
+    let identities = match agent.request_identities().await {
+        Ok(identities) => identities,
+        Err(e) => {
+            debug_log!("[SSH-AGENT] ❌ Failed to list SSH agent identities: {}", e);
+            return Ok(not_attempted());
+        }
+    };
+    debug_log!("[SSH-AGENT] Agent offered {} identities", identities.len());
+
+    let rsa_hash = session.best_supported_rsa_hash().await?.flatten();
+    let mut last_result = None;
+    for key in identities {
+        debug_log!("[SSH-AGENT] Trying {} key {}", key.algorithm(), key.comment());
+        let hash_alg = if key.algorithm().is_rsa() { rsa_hash } else { None };
+        let result = session
+            .authenticate_publickey_with(username, key, hash_alg, &mut agent)
+            .await?;
+        if result.success() {
+            return Ok(result);
+        }
+        last_result = Some(result);
+    }
+
+    Ok(last_result.unwrap_or_else(|| {
+        debug_log!("[SSH-AGENT] ❌ SSH agent has no identities");
+        not_attempted()
+    }))
+}
+
+/// 没有向服务器发送任何公钥时的认证结果
+fn not_attempted() -> AuthResult {
+    AuthResult::Failure {
+        remaining_methods: MethodSet::empty(),
+        partial_success: false,
+    }
+}
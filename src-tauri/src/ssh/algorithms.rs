@@ -0,0 +1,121 @@
+use crate::models::{AlgorithmPreset, SshAdvanced};
+use russh::keys::{Algorithm, EcdsaCurve, HashAlg};
+use russh::{cipher, compression, kex, mac, Preferred};
+use std::borrow::Cow;
+
+/// 兼容预设的密钥交换算法
+/// 某些旧版服务器（如阿里云堡垒机）可能需要较旧的算法
+const COMPATIBLE_KEX: &[kex::Name] = &[
+    kex::CURVE25519,
+    kex::DH_G14_SHA256,
+    kex::DH_G16_SHA512,
+    kex::DH_G14_SHA1,
+    kex::DH_G1_SHA1,
+    kex::EXTENSION_SUPPORT_AS_CLIENT,
+];
+
+/// 现代预设的密钥交换算法（含 strict kex 扩展，防御 Terrapin 攻击）
+const MODERN_KEX: &[kex::Name] = &[
+    kex::MLKEM768X25519_SHA256,
+    kex::CURVE25519,
+    kex::CURVE25519_PRE_RFC_8731,
+    kex::DH_GEX_SHA256,
+    kex::DH_G16_SHA512,
+    kex::DH_G14_SHA256,
+    kex::EXTENSION_SUPPORT_AS_CLIENT,
+    kex::EXTENSION_OPENSSH_STRICT_KEX_AS_CLIENT,
+];
+
+const MODERN_MACS: &[mac::Name] = &[
+    mac::HMAC_SHA512_ETM,
+    mac::HMAC_SHA256_ETM,
+    mac::HMAC_SHA512,
+    mac::HMAC_SHA256,
+];
+
+const MODERN_HOST_KEYS: &[Algorithm] = &[
+    Algorithm::Ed25519,
+    Algorithm::Ecdsa { curve: EcdsaCurve::NistP256 },
+    Algorithm::Ecdsa { curve: EcdsaCurve::NistP384 },
+    Algorithm::Ecdsa { curve: EcdsaCurve::NistP521 },
+    Algorithm::Rsa { hash: Some(HashAlg::Sha512) },
+    Algorithm::Rsa { hash: Some(HashAlg::Sha256) },
+];
+
+/// 自定义密钥交换列表时自动附加的扩展标记（不是真正的算法）
+const KEX_EXTENSIONS: &[kex::Name] = &[
+    kex::EXTENSION_SUPPORT_AS_CLIENT,
+    kex::EXTENSION_OPENSSH_STRICT_KEX_AS_CLIENT,
+];
+
+/// 根据高级设置生成算法偏好：先取预设，再用自定义列表覆盖对应类别
+///
+/// 算法名称无法识别，或在现代预设下使用了 SHA1 算法时返回错误。
+pub fn preferred(advanced: &SshAdvanced) -> Result<Preferred, String> {
+    let modern = advanced.preset == AlgorithmPreset::Modern;
+    let defaults = Preferred::default();
+
+    let kex = if advanced.kex.is_empty() {
+        Cow::Borrowed(if modern { MODERN_KEX } else { COMPATIBLE_KEX })
+    } else {
+        let mut names = parse_list(&advanced.kex, "key exchange", modern, |n| kex::Name::try_from(n).ok())?;
+        names.retain(|n| !KEX_EXTENSIONS.contains(n));
+        names.extend_from_slice(KEX_EXTENSIONS);
+        Cow::Owned(names)
+    };
+
+    let cipher = if advanced.ciphers.is_empty() {
+        defaults.cipher
+    } else {
+        Cow::Owned(parse_list(&advanced.ciphers, "cipher", modern, |n| cipher::Name::try_from(n).ok())?)
+    };
+
+    let mac = if !advanced.macs.is_empty() {
+        Cow::Owned(parse_list(&advanced.macs, "MAC", modern, |n| mac::Name::try_from(n).ok())?)
+    } else if modern {
+        Cow::Borrowed(MODERN_MACS)
+    } else {
+        defaults.mac
+    };
+
+    let key = if !advanced.host_key_algorithms.is_empty() {
+        Cow::Owned(parse_list(&advanced.host_key_algorithms, "host key", modern, |n| {
+            Algorithm::new(n).ok().filter(|a| !matches!(a, Algorithm::Other(_)))
+        })?)
+    } else if modern {
+        Cow::Borrowed(MODERN_HOST_KEYS)
+    } else {
+        defaults.key
+    };
+
+    let compression = if advanced.compression {
+        Cow::Borrowed(&[compression::ZLIB_LEGACY, compression::ZLIB, compression::NONE][..])
+    } else {
+        Cow::Borrowed(&[compression::NONE][..])
+    };
+
+    Ok(Preferred { kex, key, cipher, mac, compression })
+}
+
+/// 解析算法名称列表
+fn parse_list<T>(
+    names: &[String],
+    kind: &str,
+    modern: bool,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<T>, String> {
+    names.iter()
+        .map(|name| {
+            let name = name.trim();
+            if modern && uses_sha1(name) {
+                return Err(format!("{} algorithm {} uses SHA1 and is not allowed by the Modern preset", kind, name));
+            }
+            parse(name).ok_or_else(|| format!("Unsupported {} algorithm: {}", kind, name))
+        })
+        .collect()
+}
+
+/// 判断算法是否基于 SHA1（ssh-rsa 签名使用 SHA1，ssh-dss 只支持 SHA1）
+fn uses_sha1(name: &str) -> bool {
+    name.contains("sha1") || name == "ssh-rsa" || name == "ssh-dss"
+}
@@ -0,0 +1,98 @@
+use crate::ssh::forward::{self, RemoteRoutes};
+use crate::ssh::known_hosts::HostKeyVerifier;
+use russh::{client, Channel};
+use russh::keys::PublicKey;
+use std::path::PathBuf;
+
+/// SSH 客户端错误：在 russh 错误之外区分主机密钥校验失败
+#[derive(Debug)]
+pub enum ClientError {
+    Ssh(russh::Error),
+    /// 服务器密钥与 known_hosts 中的记录不一致
+    HostKeyChanged {
+        host: String,
+        port: u16,
+        fingerprint: String,
+        path: PathBuf,
+        line: usize,
+    },
+    /// 服务器密钥在 known_hosts 中被 @revoked 标记吊销
+    HostKeyRevoked {
+        host: String,
+        port: u16,
+        fingerprint: String,
+        path: PathBuf,
+        line: usize,
+    },
+    /// 用户拒绝信任未知主机（或确认超时）
+    HostKeyRejected { host: String, port: u16 },
+}
+
+impl From<russh::Error> for ClientError {
+    fn from(e: russh::Error) -> Self {
+        ClientError::Ssh(e)
+    }
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Ssh(e) => write!(f, "{}", e),
+            ClientError::HostKeyChanged { host, port, fingerprint, path, line } => write!(
+                f,
+                "HOST KEY CHANGED for {}:{} (now {}), conflicting entry at {}:{}. \
+                 Someone could be eavesdropping on you (man-in-the-middle attack)",
+                host, port, fingerprint, path.display(), line
+            ),
+            ClientError::HostKeyRevoked { host, port, fingerprint, path, line } => write!(
+                f,
+                "HOST KEY REVOKED for {}:{} ({}), marked @revoked at {}:{}",
+                host, port, fingerprint, path.display(), line
+            ),
+            ClientError::HostKeyRejected { host, port } => {
+                write!(f, "Host key for {}:{} was not trusted", host, port)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// SSH 客户端处理器（终端与 SFTP 共用）
+pub struct Client {
+    verifier: HostKeyVerifier,
+    remote_routes: RemoteRoutes,
+}
+
+impl Client {
+    /// `remote_routes` 为远程端口转发路由表，由会话维护，重连后沿用
+    pub fn new(verifier: HostKeyVerifier, remote_routes: RemoteRoutes) -> Self {
+        Self { verifier, remote_routes }
+    }
+}
+
+impl client::Handler for Client {
+    type Error = ClientError;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKey,
+    ) -> Result<bool, Self::Error> {
+        self.verifier.verify(server_public_key).await
+    }
+
+    async fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<client::Msg>,
+        connected_address: &str,
+        connected_port: u32,
+        originator_address: &str,
+        originator_port: u32,
+        _session: &mut client::Session,
+    ) -> Result<(), Self::Error> {
+        debug_log!("[SSH-FWD] Forwarded connection {}:{} from {}:{}",
+            connected_address, connected_port, originator_address, originator_port);
+        forward::accept_forwarded(&self.remote_routes, channel, connected_address, connected_port);
+        Ok(())
+    }
+}
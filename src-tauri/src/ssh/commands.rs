@@ -1,12 +1,17 @@
-use crate::models::{SshConfig, Connection};
-use crate::ssh::SshSession;
+use crate::models::{SshConfig, Connection, ForwardKind, PortForwardConfig};
+use crate::ssh::{pool, SshSession, SshTransportPool};
+use crate::ssh::diagnostics::{self, DiagnosticReport};
+use crate::ssh::forward::ForwardStatus;
+use crate::ssh::reconnect::Reconnector;
+use crate::ssh::known_hosts::HostKeyDecision;
+use crate::terminal::commands::lookup_as;
+use crate::terminal::TerminalRegistry;
+use crate::vault::VaultState;
 use tauri::{AppHandle, State};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::collections::HashMap;
 
-pub type SshSessionMap = Arc<Mutex<HashMap<String, SshSession>>>;
-
 // MFA 响应通道类型：用于等待前端提交的 MFA 响应
 pub type MfaResponseMap = Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<Vec<String>>>>>;
 
@@ -15,37 +20,94 @@ pub fn create_mfa_response_map() -> MfaResponseMap {
     Arc::new(Mutex::new(HashMap::new()))
 }
 
+// 主机密钥确认通道类型：用于等待前端对未知主机的信任决定
+pub type HostKeyResponseMap = Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<HostKeyDecision>>>>;
+
+/// 创建主机密钥确认通道管理器
+pub fn create_host_key_response_map() -> HostKeyResponseMap {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_ssh_terminal(
-    config: Connection,
-    sessions: State<'_, SshSessionMap>,
+    mut config: Connection,
+    terminal_id: Option<String>,
+    sessions: State<'_, TerminalRegistry>,
+    pool: State<'_, SshTransportPool>,
     mfa_channels: State<'_, MfaResponseMap>,
+    host_key_channels: State<'_, HostKeyResponseMap>,
+    vault: State<'_, VaultState>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
     debug_log!("[CMD] create_ssh_terminal called for connection: {}", config.name);
 
+    // 终端 ID 缺省为连接 ID；底层 SSH 连接按连接 ID 共享
+    let terminal_id = terminal_id.unwrap_or_else(|| config.id.clone());
+
     // 检查是否已存在
     {
         let sessions_guard = sessions.lock().await;
-        if sessions_guard.contains_key(&config.id) {
-            debug_log!("[CMD] SSH terminal {} already exists, skipping creation", config.id);
+        if sessions_guard.contains_key(&terminal_id) {
+            debug_log!("[CMD] SSH terminal {} already exists, skipping creation", terminal_id);
             return Ok(());
         }
     }
 
+    // 把保险库引用替换为明文凭据
+    vault.lock().await
+        .resolve_connection(&mut config)
+        .map_err(|e| format!("Failed to load credentials: {}", e))?;
+
     // 获取 SSH 配置
     let ssh_config = config.ssh_config
         .ok_or("SSH config is required for SSH connection")?;
 
-    // 创建 SSH 会话（传入 MFA 通道用于 keyboard-interactive 认证）
-    let mfa_channels_clone = mfa_channels.inner().clone();
-    let session = SshSession::new(ssh_config, config.id.clone(), app_handle, mfa_channels_clone)
-        .await
-        .map_err(|e| format!("Failed to create SSH session: {}", e))?;
+    // 复用（或建立）该连接的 SSH 传输层（传入 MFA 通道用于 keyboard-interactive 认证）
+    let transport = pool::acquire(
+        pool.inner(),
+        &config.id,
+        &ssh_config,
+        &terminal_id,
+        &app_handle,
+        mfa_channels.inner(),
+        host_key_channels.inner(),
+    )
+    .await
+    .map_err(|e| format!("Failed to create SSH session: {}", e))?;
+
+    // 启用自动重连时，断线后通过连接池重建连接
+    let reconnector = config.reconnect.map(|policy| Reconnector::new(
+        policy,
+        pool.inner().clone(),
+        config.id.clone(),
+        ssh_config.clone(),
+        app_handle.clone(),
+        mfa_channels.inner().clone(),
+        host_key_channels.inner().clone(),
+    ));
+
+    // 创建 SSH 会话
+    let session = SshSession::new(
+        ssh_config,
+        terminal_id.clone(),
+        app_handle,
+        transport,
+        reconnector,
+    )
+    .await
+    .map_err(|e| format!("Failed to create SSH session: {}", e))?;
+
+    // 自动启动连接配置中的端口转发，单个转发失败不影响终端
+    for forward in config.port_forwards {
+        if let Err(e) = session.add_forward(forward.clone()).await {
+            debug_log!("[CMD] Failed to start forward {}: {}", forward.id, e);
+        }
+    }
 
     // 保存会话
-    let session_id = config.id.clone();
-    sessions.lock().await.insert(session_id.clone(), session);
+    let session_id = terminal_id;
+    sessions.lock().await.insert(session_id.clone(), Arc::new(session));
     
     debug_log!("[CMD] create_ssh_terminal completed successfully for id: {}", session_id);
     debug_log!("[CMD] Session stored in map with id: {}", session_id);
@@ -53,69 +115,20 @@ pub async fn create_ssh_terminal(
     Ok(())
 }
 
+/// 测试 SSH 连接并返回诊断报告（不打开 shell）
+/// 连接或认证失败不会返回 Err，失败原因记录在报告的 error 字段中
 #[tauri::command]
-pub async fn test_ssh_connection(config: SshConfig) -> Result<String, String> {
+pub async fn test_ssh_connection(
+    mut config: SshConfig,
+    vault: State<'_, VaultState>,
+) -> Result<DiagnosticReport, String> {
     debug_log!("[CMD] test_ssh_connection to {}@{}:{}", config.username, config.host, config.port);
 
-    // TODO: 实现真正的连接测试
-    // 由于需要 AppHandle，暂时返回未实现
-    Err("Connection test not implemented yet. Use create_ssh_terminal to test.".to_string())
-}
+    vault.lock().await
+        .resolve_ssh_config(&mut config)
+        .map_err(|e| format!("Failed to load credentials: {}", e))?;
 
-#[tauri::command]
-pub async fn write_to_ssh_terminal(
-    id: String,
-    data: String,
-    sessions: State<'_, SshSessionMap>,
-) -> Result<(), String> {
-    debug_log!("[SSH-CMD] write_to_ssh_terminal: id={}, data_len={}, data_preview={:?}", 
-        id, 
-        data.len(),
-        if data.len() > 20 { &data[..20] } else { &data }
-    );
-    
-    let sessions = sessions.lock().await;
-    if let Some(session) = sessions.get(&id) {
-        debug_log!("[SSH-CMD] Found session {}, calling write", id);
-        session
-            .write(data.as_bytes())
-            .await
-            .map_err(|e| {
-                debug_log!("[SSH-CMD] Write failed for {}: {}", id, e);
-                format!("Write failed: {}", e)
-            })?;
-        debug_log!("[SSH-CMD] Write successful for {}", id);
-    } else {
-        debug_log!("[SSH-CMD] Session {} not found!", id);
-        return Err(format!("Session {} not found", id));
-    }
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn resize_ssh_terminal(
-    id: String,
-    rows: u16,
-    cols: u16,
-    sessions: State<'_, SshSessionMap>,
-) -> Result<(), String> {
-    let sessions = sessions.lock().await;
-    if let Some(session) = sessions.get(&id) {
-        session
-            .resize(rows, cols)
-            .await
-            .map_err(|e| format!("Resize failed: {}", e))?;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn close_ssh_terminal(
-    id: String,
-    sessions: State<'_, SshSessionMap>,
-) -> Result<(), String> {
-    sessions.lock().await.remove(&id);
-    Ok(())
+    Ok(diagnostics::diagnose(&config).await)
 }
 
 /// 提交 MFA 响应
@@ -152,3 +165,102 @@ pub async fn cancel_ssh_mfa(
     mfa_channels.lock().await.remove(&terminal_id);
     Ok(())
 }
+
+/// 提交主机密钥确认结果
+/// 前端在用户核对指纹并做出选择后调用此命令；关闭对话框时提交 Reject
+#[tauri::command]
+pub async fn submit_ssh_hostkey_response(
+    terminal_id: String,
+    decision: HostKeyDecision,
+    host_key_channels: State<'_, HostKeyResponseMap>,
+) -> Result<(), String> {
+    debug_log!("[SSH-HOSTKEY] Received host key decision for terminal: {} ({:?})", terminal_id, decision);
+
+    let mut channels = host_key_channels.lock().await;
+    if let Some(sender) = channels.remove(&terminal_id) {
+        sender.send(decision).map_err(|_| "Failed to send host key decision: channel closed".to_string())?;
+    } else {
+        debug_log!("[SSH-HOSTKEY] No pending host key prompt found for terminal: {}", terminal_id);
+        return Err(format!("No pending host key prompt for terminal: {}", terminal_id));
+    }
+    Ok(())
+}
+
+/// 在 SSH 会话上启动端口转发
+#[tauri::command]
+pub async fn ssh_forward_add(
+    id: String,
+    forward: PortForwardConfig,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<ForwardStatus, String> {
+    debug_log!("[SSH-CMD] ssh_forward_add: id={}, {}:{} -> {}:{}",
+        id, forward.bind_host, forward.bind_port, forward.target_host, forward.target_port);
+
+    let session = lookup_as::<SshSession>(&sessions, &id).await?;
+    session
+        .add_forward(forward)
+        .await
+        .map_err(|e| format!("Failed to start forward: {}", e))
+}
+
+/// 列出 SSH 会话上的端口转发
+#[tauri::command]
+pub async fn ssh_forward_list(
+    id: String,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<Vec<ForwardStatus>, String> {
+    let session = lookup_as::<SshSession>(&sessions, &id).await?;
+    Ok(session.list_forwards().await)
+}
+
+/// 停止 SSH 会话上的端口转发
+#[tauri::command]
+pub async fn ssh_forward_remove(
+    id: String,
+    forward_id: String,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<(), String> {
+    debug_log!("[SSH-CMD] ssh_forward_remove: id={}, forward={}", id, forward_id);
+
+    let session = lookup_as::<SshSession>(&sessions, &id).await?;
+    if !session.remove_forward(&forward_id).await {
+        return Err(format!("Forward {} not found", forward_id));
+    }
+    Ok(())
+}
+
+/// 在 SSH 会话上启动 SOCKS5 动态代理（-D）
+#[tauri::command]
+pub async fn ssh_socks_start(
+    id: String,
+    bind_host: String,
+    bind_port: u16,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<ForwardStatus, String> {
+    debug_log!("[SSH-CMD] ssh_socks_start: id={}, bind={}:{}", id, bind_host, bind_port);
+
+    let forward = PortForwardConfig {
+        id: String::new(),
+        kind: ForwardKind::Dynamic,
+        bind_host,
+        bind_port,
+        target_host: String::new(),
+        target_port: 0,
+    };
+
+    let session = lookup_as::<SshSession>(&sessions, &id).await?;
+    session
+        .add_forward(forward)
+        .await
+        .map_err(|e| format!("Failed to start SOCKS proxy: {}", e))
+}
+
+/// 停止 SSH 会话上的 SOCKS5 动态代理
+#[tauri::command]
+pub async fn ssh_socks_stop(
+    id: String,
+    forward_id: String,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<(), String> {
+    ssh_forward_remove(id, forward_id, sessions).await
+}
@@ -0,0 +1,407 @@
+use crate::models::{SshConfig, SshAuth, SshAdvanced};
+use crate::ssh::mfa::{MfaPromptPayload, MfaPrompt};
+use crate::ssh::commands::{MfaResponseMap, HostKeyResponseMap};
+use crate::ssh::client::{Client, ClientError};
+use crate::ssh::known_hosts::HostKeyVerifier;
+use crate::ssh::agent;
+use crate::ssh::algorithms;
+use crate::ssh::forward::RemoteRoutes;
+use russh::client::{self, KeyboardInteractiveAuthResponse};
+use russh::keys::key::PrivateKeyWithHashAlg;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::net::TcpStream;
+
+/// 已认证的 SSH 传输层（目标主机连接 + 途经的跳板机连接）
+pub struct SshTransport {
+    pub handle: client::Handle<Client>,
+    /// 跳板机连接，目标连接的数据经由这些连接转发，必须保持存活
+    pub jumps: Vec<client::Handle<Client>>,
+}
+
+/// 终端与 SFTP 共用的 SSH 客户端配置，算法与心跳取自连接的高级设置
+pub fn client_config(advanced: &SshAdvanced) -> Result<client::Config, String> {
+    let keepalive_interval = match advanced.keepalive_interval_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+
+    Ok(client::Config {
+        // 禁用客户端侧不活动超时，完全依赖 keepalive 机制
+        inactivity_timeout: None,
+        // 默认每隔 30 秒发送一次心跳包，为 0 时不发送
+        keepalive_interval,
+        // 默认连续 6 次心跳无响应才关闭连接（约 3 分钟容忍时间）
+        keepalive_max: advanced.keepalive_max,
+        preferred: algorithms::preferred(advanced)?,
+        ..Default::default()
+    })
+}
+
+/// 连接超时，为 0 时不限制
+fn connect_timeout(advanced: &SshAdvanced) -> Option<Duration> {
+    match advanced.connect_timeout_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// 在可选超时内执行 `future`
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl std::future::Future<Output = T>,
+) -> Result<T, tokio::time::error::Elapsed> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future).await,
+        None => Ok(future.await),
+    }
+}
+
+/// 单跳连接参数（跳板机或目标主机）
+struct Hop<'a> {
+    host: &'a str,
+    port: u16,
+    username: &'a str,
+    auth: &'a SshAuth,
+}
+
+/// 建立到目标主机的 SSH 连接，按顺序经过 `config.jump_hosts` 中的跳板机
+///
+/// 第一跳直接 TCP 连接，之后每一跳都通过上一跳的 direct-tcpip 通道建立，
+/// 每一跳都会独立进行主机密钥校验与认证（包括 MFA）。
+/// `prompt_id` 用于关联前端的主机密钥/MFA 提示（通常为终端或 SFTP 会话 ID）。
+/// `remote_routes` 为目标连接上的远程端口转发路由表。
+/// 连接超时只作用于建立 TCP 连接（或跳板机隧道），不包括握手与认证（可能等待用户确认）。
+pub async fn establish(
+    config: &SshConfig,
+    remote_routes: &RemoteRoutes,
+    prompt_id: &str,
+    app_handle: &AppHandle,
+    mfa_channels: &MfaResponseMap,
+    host_key_channels: &HostKeyResponseMap,
+) -> Result<SshTransport, Box<dyn std::error::Error + Send + Sync>> {
+    let client_config = Arc::new(client_config(&config.advanced)?);
+    let timeout = connect_timeout(&config.advanced);
+    let hops = config.jump_hosts.iter()
+        .map(|j| Hop { host: &j.host, port: j.port, username: &j.username, auth: &j.auth })
+        .chain(std::iter::once(Hop {
+            host: &config.host,
+            port: config.port,
+            username: &config.username,
+            auth: &config.auth,
+        }));
+
+    let mut handles: Vec<client::Handle<Client>> = Vec::new();
+    for hop in hops {
+        let is_jump = handles.len() < config.jump_hosts.len();
+        let label = if is_jump { "Jump host" } else { "Target" };
+        debug_log!("[SSH] {} {}@{}:{}", label, hop.username, hop.host, hop.port);
+
+        let verifier = HostKeyVerifier::new(
+            hop.host.to_string(),
+            hop.port,
+            prompt_id.to_string(),
+            app_handle.clone(),
+            host_key_channels.clone(),
+        );
+
+        // 跳板机上不会有远程转发
+        let routes = if is_jump { RemoteRoutes::default() } else { remote_routes.clone() };
+        let client = Client::new(verifier, routes);
+        let session_result = match handles.last() {
+            None => {
+                let stream = with_timeout(timeout, TcpStream::connect((hop.host, hop.port)))
+                    .await
+                    .map_err(|_| format!("Connection to {}:{} timed out", hop.host, hop.port))?
+                    .map_err(|e| format!("Connection to {}:{} failed: {}", hop.host, hop.port, e))?;
+                client::connect_stream(client_config.clone(), stream, client).await
+            }
+            Some(previous) => {
+                debug_log!("[SSH] Opening direct-tcpip tunnel to {}:{}...", hop.host, hop.port);
+                let channel = with_timeout(timeout, previous.channel_open_direct_tcpip(hop.host, hop.port as u32, "127.0.0.1", 0))
+                    .await
+                    .map_err(|_| format!("Tunnel to {}:{} timed out", hop.host, hop.port))?
+                    .map_err(|e| format!("Failed to tunnel to {}:{}: {}", hop.host, hop.port, e))?;
+                client::connect_stream(client_config.clone(), channel.into_stream(), client).await
+            }
+        };
+
+        let mut session = match session_result {
+            Ok(s) => {
+                debug_log!("[SSH] ✅ Connection to {}:{} established", hop.host, hop.port);
+                s
+            }
+            Err(ClientError::Ssh(e)) => {
+                debug_log!("[SSH] ❌ Connection to {}:{} failed: {}", hop.host, hop.port, e);
+                return Err(if is_jump {
+                    format!("Connection to jump host {} failed: {}", hop.host, e)
+                } else {
+                    format!("Connection failed: {}", e)
+                }.into());
+            }
+            Err(e) => {
+                // 主机密钥校验失败，保留独立的错误信息
+                debug_log!("[SSH] ❌ Host key verification failed: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        authenticate(&mut session, hop.username, hop.auth, hop.host, prompt_id, app_handle, mfa_channels)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                if is_jump {
+                    format!("Jump host {}: {}", hop.host, e).into()
+                } else {
+                    e
+                }
+            })?;
+
+        handles.push(session);
+    }
+
+    let handle = handles.pop().ok_or("No SSH hop to connect to")?;
+    Ok(SshTransport { handle, jumps: handles })
+}
+
+/// 认证策略：
+/// 1. 先尝试配置的认证方式（公钥、密码或 agent）
+/// 2. 如果返回 false（可能是 partial success，需要 MFA），尝试 keyboard-interactive
+async fn authenticate(
+    session: &mut client::Handle<Client>,
+    username: &str,
+    auth: &SshAuth,
+    host: &str,
+    prompt_id: &str,
+    app_handle: &AppHandle,
+    mfa_channels: &MfaResponseMap,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug_log!("[SSH] Authenticating user '{}'...", username);
+
+    // 步骤 1：尝试配置的认证方式
+    let auth_result = authenticate_configured(session, username, auth).await?;
+
+    // 步骤 2：如果首次认证返回 false，尝试 keyboard-interactive（可能是 MFA）
+    if auth_result.success() {
+        debug_log!("[SSH] ✅ Authentication successful (first method)");
+        return Ok(());
+    }
+
+    debug_log!("[SSH] First auth returned false, attempting keyboard-interactive (MFA)...");
+
+    // 直接尝试 keyboard-interactive，不使用短超时（可能导致连接问题）
+    let kbi_result = perform_keyboard_interactive_auth(
+        session,
+        username,
+        host,
+        prompt_id,
+        app_handle,
+        mfa_channels,
+    ).await;
+
+    match kbi_result {
+        Ok(true) => {
+            debug_log!("[SSH] ✅ MFA authentication successful");
+            Ok(())
+        }
+        Ok(false) => {
+            debug_log!("[SSH] ❌ MFA authentication rejected");
+            Err("SSH MFA authentication failed".into())
+        }
+        Err(e) => {
+            debug_log!("[SSH] ❌ MFA authentication error: {}", e);
+            Err(format!("MFA authentication error: {}", e).into())
+        }
+    }
+}
+
+/// 使用连接配置中的认证方式（密码、公钥或 agent）进行认证，不处理 keyboard-interactive
+pub async fn authenticate_configured<H: client::Handler>(
+    session: &mut client::Handle<H>,
+    username: &str,
+    auth: &SshAuth,
+) -> Result<client::AuthResult, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match auth {
+        SshAuth::Password(password) => {
+            debug_log!("[SSH] Using password authentication (len={})", password.len());
+            match session.authenticate_password(username, password.clone()).await {
+                Ok(result) => {
+                    debug_log!("[SSH] Password auth result: {:?}", result.success());
+                    result
+                }
+                Err(e) => {
+                    debug_log!("[SSH] ❌ Password auth error: {}", e);
+                    return Err(format!("Authentication error: {}", e).into());
+                }
+            }
+        }
+        SshAuth::PublicKey {
+            private_key_path,
+            passphrase,
+        } => {
+            debug_log!("[SSH] Using public key: {}", private_key_path);
+            let key_pair = russh::keys::decode_secret_key(
+                &std::fs::read_to_string(private_key_path)?,
+                passphrase.as_deref(),
+            )?;
+            // russh 0.56 需要使用 PrivateKeyWithHashAlg
+            let key_with_alg = PrivateKeyWithHashAlg::new(
+                Arc::new(key_pair),
+                session.best_supported_rsa_hash().await?.flatten(),
+            );
+            match session.authenticate_publickey(username, key_with_alg).await {
+                Ok(result) => {
+                    debug_log!("[SSH] Public key auth result: {:?}", result.success());
+                    result
+                }
+                Err(e) => {
+                    debug_log!("[SSH] ❌ Public key auth error: {}", e);
+                    return Err(format!("Authentication error: {}", e).into());
+                }
+            }
+        }
+        SshAuth::Agent { socket_path } => {
+            debug_log!("[SSH] Using SSH agent: {}", socket_path.as_deref().unwrap_or("$SSH_AUTH_SOCK"));
+            match agent::authenticate_with_agent(session, username, socket_path.as_deref()).await {
+                Ok(result) => {
+                    debug_log!("[SSH] Agent auth result: {:?}", result.success());
+                    result
+                }
+                Err(e) => {
+                    debug_log!("[SSH] ❌ Agent auth error: {}", e);
+                    return Err(format!("Authentication error: {}", e).into());
+                }
+            }
+        }
+    })
+}
+
+/// 执行 keyboard-interactive 认证（用于 MFA）
+///
+/// 此方法处理多轮 keyboard-interactive 交互：
+/// 1. 启动 keyboard-interactive 认证
+/// 2. 当收到 InfoRequest 时，向前端发送 ssh-mfa-prompt 事件
+/// 3. 等待前端通过 submit_ssh_mfa_response 命令返回响应
+/// 4. 将响应发送给服务器，循环直到成功或失败
+async fn perform_keyboard_interactive_auth(
+    session: &mut client::Handle<Client>,
+    username: &str,
+    host: &str,
+    terminal_id: &str,
+    app_handle: &AppHandle,
+    mfa_channels: &MfaResponseMap,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    debug_log!("[SSH-MFA] Starting keyboard-interactive authentication for user: {}", username);
+
+    // 启动 keyboard-interactive 认证
+    debug_log!("[SSH-MFA] Calling authenticate_keyboard_interactive_start...");
+    let kbi_result = session
+        .authenticate_keyboard_interactive_start(username.to_string(), None)
+        .await;
+
+    let mut kbi_response = match kbi_result {
+        Ok(resp) => {
+            debug_log!("[SSH-MFA] authenticate_keyboard_interactive_start returned: {:?}",
+                match &resp {
+                    KeyboardInteractiveAuthResponse::Success => "Success",
+                    KeyboardInteractiveAuthResponse::Failure { .. } => "Failure",
+                    KeyboardInteractiveAuthResponse::InfoRequest { .. } => "InfoRequest",
+                });
+            resp
+        }
+        Err(e) => {
+            debug_log!("[SSH-MFA] authenticate_keyboard_interactive_start error: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    // 处理多轮交互
+    debug_log!("[SSH-MFA] Entering response loop...");
+    loop {
+        match kbi_response {
+            KeyboardInteractiveAuthResponse::Success => {
+                debug_log!("[SSH-MFA] Keyboard-interactive authentication succeeded");
+                return Ok(true);
+            }
+            KeyboardInteractiveAuthResponse::Failure { .. } => {
+                debug_log!("[SSH-MFA] Keyboard-interactive authentication failed");
+                return Ok(false);
+            }
+            KeyboardInteractiveAuthResponse::InfoRequest { name, instructions, prompts } => {
+                debug_log!("[SSH-MFA] Received InfoRequest: name='{}', instructions='{}', prompts={}",
+                    name, instructions, prompts.len());
+
+                // 如果没有提示，发送空响应继续
+                if prompts.is_empty() {
+                    debug_log!("[SSH-MFA] Empty prompts, sending empty response");
+                    kbi_response = session
+                        .authenticate_keyboard_interactive_respond(vec![])
+                        .await?;
+                    continue;
+                }
+
+                // 创建用于等待前端响应的 oneshot 通道
+                let (tx, rx) = tokio::sync::oneshot::channel::<Vec<String>>();
+
+                // 将发送端存入全局 map
+                {
+                    let mut channels = mfa_channels.lock().await;
+                    channels.insert(terminal_id.to_string(), tx);
+                }
+
+                // 构建 MFA 提示 payload
+                let mfa_prompts: Vec<MfaPrompt> = prompts
+                    .iter()
+                    .map(|p| MfaPrompt {
+                        prompt: p.prompt.clone(),
+                        echo: p.echo,
+                    })
+                    .collect();
+
+                let payload = MfaPromptPayload {
+                    terminal_id: terminal_id.to_string(),
+                    host: host.to_string(),
+                    name: name.clone(),
+                    instructions: instructions.clone(),
+                    prompts: mfa_prompts,
+                };
+
+                // 向前端发送 MFA 提示事件
+                debug_log!("[SSH-MFA] Emitting ssh-mfa-prompt event to frontend");
+                app_handle.emit("ssh-mfa-prompt", &payload)?;
+
+                // 等待前端响应（带超时）
+                debug_log!("[SSH-MFA] Waiting for frontend MFA response...");
+                let responses = tokio::time::timeout(
+                    std::time::Duration::from_secs(120), // 2 分钟超时
+                    rx
+                ).await;
+
+                let responses = match responses {
+                    Ok(Ok(r)) => {
+                        debug_log!("[SSH-MFA] Received {} responses from frontend", r.len());
+                        r
+                    }
+                    Ok(Err(_)) => {
+                        // 通道被关闭（用户取消）
+                        debug_log!("[SSH-MFA] MFA cancelled by user");
+                        return Err("MFA authentication cancelled by user".into());
+                    }
+                    Err(_) => {
+                        // 超时
+                        debug_log!("[SSH-MFA] MFA response timeout");
+                        // 清理通道
+                        mfa_channels.lock().await.remove(terminal_id);
+                        return Err("MFA authentication timeout".into());
+                    }
+                };
+
+                // 发送响应给服务器
+                debug_log!("[SSH-MFA] Sending responses to server");
+                kbi_response = session
+                    .authenticate_keyboard_interactive_respond(responses)
+                    .await?;
+            }
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::models::{SshAuth, SshConfig};
+use crate::ssh::connect;
+use crate::ssh::known_hosts::{self, HostKeyStatus};
+use russh::client::{self, AuthResult};
+use russh::MethodKind;
+use russh::keys::{HashAlg, PublicKey};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+/// 每个诊断步骤（DNS、TCP、握手、认证）的超时时间
+const STEP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 协商得到的算法
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NegotiatedAlgorithms {
+    pub kex: String,
+    pub host_key: String,
+    pub cipher: String,
+    pub client_mac: String,
+    pub server_mac: String,
+}
+
+/// 连接诊断报告，返回给前端
+///
+/// 诊断在第一个失败的步骤停止，之前步骤的结果保留，失败原因写入 `error`。
+/// 经过跳板机时，DNS 与 TCP 指标针对第一跳，其余字段针对目标主机。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiagnosticReport {
+    pub host: String,
+    pub port: u16,
+    /// 途经的跳板机（user@host:port）
+    pub jump_hosts: Vec<String>,
+    pub resolved_addresses: Vec<String>,
+    pub dns_ms: Option<u64>,
+    pub tcp_connect_ms: Option<u64>,
+    pub handshake_ms: Option<u64>,
+    /// 服务器标识串（如 SSH-2.0-OpenSSH_9.6）
+    pub server_banner: Option<String>,
+    pub algorithms: Option<NegotiatedAlgorithms>,
+    pub host_key_type: Option<String>,
+    pub host_key_fingerprint: Option<String>,
+    pub host_key_status: Option<HostKeyStatus>,
+    /// 认证前服务器发送的提示信息（SSH_MSG_USERAUTH_BANNER）
+    pub auth_banner: Option<String>,
+    /// 服务器提供的认证方式
+    pub auth_methods: Vec<String>,
+    pub auth_succeeded: bool,
+    pub auth_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 握手过程中观察到的信息
+#[derive(Default)]
+struct Observed {
+    banner: Option<String>,
+    algorithms: Option<NegotiatedAlgorithms>,
+    key: Option<PublicKey>,
+    status: Option<HostKeyStatus>,
+    auth_banner: Option<String>,
+}
+
+/// 诊断用的 SSH 处理器：记录握手信息，不弹出主机密钥确认
+///
+/// 未知主机仅记录状态（不写入 known_hosts），之后只查询认证方式，不发送凭据；
+/// 密钥与记录不一致或已被吊销时拒绝握手，避免把凭据发给可疑主机。
+struct Probe {
+    host: String,
+    port: u16,
+    observed: Arc<std::sync::Mutex<Observed>>,
+}
+
+impl client::Handler for Probe {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKey,
+    ) -> Result<bool, Self::Error> {
+        let status = known_hosts::lookup(&self.host, self.port, server_public_key);
+        let trusted = !matches!(status, HostKeyStatus::Changed { .. } | HostKeyStatus::Revoked { .. });
+        let mut observed = self.observed.lock().unwrap();
+        observed.key = Some(server_public_key.clone());
+        observed.status = Some(status);
+        Ok(trusted)
+    }
+
+    async fn kex_done(
+        &mut self,
+        _shared_secret: Option<&[u8]>,
+        names: &russh::Names,
+        session: &mut client::Session,
+    ) -> Result<(), Self::Error> {
+        let mut observed = self.observed.lock().unwrap();
+        observed.banner = Some(String::from_utf8_lossy(session.remote_sshid()).trim_end().to_string());
+        observed.algorithms = Some(NegotiatedAlgorithms {
+            kex: names.kex.as_ref().to_string(),
+            host_key: names.key.to_string(),
+            cipher: names.cipher.as_ref().to_string(),
+            client_mac: names.client_mac.as_ref().to_string(),
+            server_mac: names.server_mac.as_ref().to_string(),
+        });
+        Ok(())
+    }
+
+    async fn auth_banner(
+        &mut self,
+        banner: &str,
+        _session: &mut client::Session,
+    ) -> Result<(), Self::Error> {
+        self.observed.lock().unwrap().auth_banner = Some(banner.trim_end().to_string());
+        Ok(())
+    }
+}
+
+/// 单跳连接参数（跳板机或目标主机）
+struct Hop<'a> {
+    host: &'a str,
+    port: u16,
+    username: &'a str,
+    auth: &'a SshAuth,
+}
+
+/// 测试到 `config` 的连接并生成诊断报告，不打开 shell
+///
+/// 只使用配置中的认证方式（密码、公钥或 agent），不进行 keyboard-interactive（MFA），
+/// 也不会向前端弹出主机密钥确认。主机密钥不在 known_hosts 中的主机（含跳板机）不会收到凭据，
+/// 诊断在查询认证方式后停止。
+pub async fn diagnose(config: &SshConfig) -> DiagnosticReport {
+    let mut report = DiagnosticReport {
+        host: config.host.clone(),
+        port: config.port,
+        jump_hosts: config.jump_hosts.iter()
+            .map(|j| format!("{}@{}:{}", j.username, j.host, j.port))
+            .collect(),
+        ..Default::default()
+    };
+
+    if let Err(e) = run(config, &mut report).await {
+        debug_log!("[SSH-DIAG] ❌ {}:{} - {}", config.host, config.port, e);
+        report.error = Some(e.to_string());
+    }
+    report
+}
+
+async fn run(
+    config: &SshConfig,
+    report: &mut DiagnosticReport,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client_config = Arc::new(connect::client_config(&config.advanced)?);
+    let hops: Vec<Hop> = config.jump_hosts.iter()
+        .map(|j| Hop { host: &j.host, port: j.port, username: &j.username, auth: &j.auth })
+        .chain(std::iter::once(Hop {
+            host: &config.host,
+            port: config.port,
+            username: &config.username,
+            auth: &config.auth,
+        }))
+        .collect();
+
+    let mut sessions: Vec<client::Handle<Probe>> = Vec::new();
+    let result = async {
+        for (index, hop) in hops.iter().enumerate() {
+            let is_target = index + 1 == hops.len();
+            let observed = Arc::new(std::sync::Mutex::new(Observed::default()));
+            let probe = Probe {
+                host: hop.host.to_string(),
+                port: hop.port,
+                observed: observed.clone(),
+            };
+
+            let (session, started) = match sessions.last() {
+                None => {
+                    let stream = connect_tcp(hop.host, hop.port, report).await?;
+                    let started = Instant::now();
+                    let session = tokio::time::timeout(
+                        STEP_TIMEOUT,
+                        client::connect_stream(client_config.clone(), stream, probe),
+                    ).await;
+                    (session, started)
+                }
+                Some(previous) => {
+                    let channel = previous
+                        .channel_open_direct_tcpip(hop.host, hop.port as u32, "127.0.0.1", 0)
+                        .await
+                        .map_err(|e| format!("Failed to tunnel to {}:{}: {}", hop.host, hop.port, e))?;
+                    let started = Instant::now();
+                    let session = tokio::time::timeout(
+                        STEP_TIMEOUT,
+                        client::connect_stream(client_config.clone(), channel.into_stream(), probe),
+                    ).await;
+                    (session, started)
+                }
+            };
+
+            if is_target {
+                report.handshake_ms = Some(elapsed_ms(started));
+                record_handshake(report, &observed.lock().unwrap());
+            }
+
+            let mut session = match session {
+                Ok(Ok(session)) => session,
+                Ok(Err(e)) => {
+                    let status = observed.lock().unwrap().status.clone();
+                    let reason = match status {
+                        Some(HostKeyStatus::Changed { path, line }) => format!(
+                            "HOST KEY CHANGED (conflicting entry at {}:{}), authentication not attempted",
+                            path.display(), line
+                        ),
+                        Some(HostKeyStatus::Revoked { path, line }) => format!(
+                            "HOST KEY REVOKED (@revoked entry at {}:{}), authentication not attempted",
+                            path.display(), line
+                        ),
+                        _ => format!("SSH handshake failed: {}", e),
+                    };
+                    return Err(hop_error(is_target, hop, reason));
+                }
+                Err(_) => return Err(hop_error(is_target, hop, "SSH handshake timed out".to_string())),
+            };
+
+            let trusted = matches!(observed.lock().unwrap().status, Some(HostKeyStatus::Known { .. }));
+            let started = Instant::now();
+            let auth_result = authenticate(
+                &mut session,
+                hop,
+                trusted,
+                if is_target { Some(&mut *report) } else { None },
+            ).await;
+            if is_target {
+                report.auth_ms = Some(elapsed_ms(started));
+                report.auth_banner = observed.lock().unwrap().auth_banner.clone();
+                report.auth_succeeded = auth_result.is_ok();
+            }
+            sessions.push(session);
+            auth_result.map_err(|e| hop_error(is_target, hop, e.to_string()))?;
+        }
+        Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    }.await;
+
+    // 从目标主机开始逐跳断开
+    for session in sessions.iter().rev() {
+        let _ = session.disconnect(russh::Disconnect::ByApplication, "", "en").await;
+    }
+    result
+}
+
+/// 解析地址并建立 TCP 连接，依次尝试每个解析结果
+async fn connect_tcp(
+    host: &str,
+    port: u16,
+    report: &mut DiagnosticReport,
+) -> Result<TcpStream, Box<dyn std::error::Error + Send + Sync>> {
+    let started = Instant::now();
+    let addrs: Vec<SocketAddr> = tokio::time::timeout(STEP_TIMEOUT, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| format!("DNS resolution of {} timed out", host))?
+        .map_err(|e| format!("DNS resolution of {} failed: {}", host, e))?
+        .collect();
+    report.dns_ms = Some(elapsed_ms(started));
+    report.resolved_addresses = addrs.iter().map(|a| a.ip().to_string()).collect();
+    debug_log!("[SSH-DIAG] {} resolved to {:?}", host, report.resolved_addresses);
+
+    let mut last_error = format!("{} did not resolve to any address", host);
+    for addr in addrs {
+        let started = Instant::now();
+        match tokio::time::timeout(STEP_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => {
+                report.tcp_connect_ms = Some(elapsed_ms(started));
+                return Ok(stream);
+            }
+            Ok(Err(e)) => last_error = format!("TCP connection to {} failed: {}", addr, e),
+            Err(_) => last_error = format!("TCP connection to {} timed out", addr),
+        }
+    }
+    Err(last_error.into())
+}
+
+/// 查询服务器提供的认证方式并使用配置的方式认证
+///
+/// `trusted` 为 false（主机密钥未经 known_hosts 确认）时只查询认证方式，不发送凭据。
+/// `report` 仅在目标主机上传入，用于记录认证方式。
+async fn authenticate(
+    session: &mut client::Handle<Probe>,
+    hop: &Hop<'_>,
+    trusted: bool,
+    report: Option<&mut DiagnosticReport>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // "none" 认证的失败响应中包含服务器接受的认证方式
+    let methods = match session.authenticate_none(hop.username).await? {
+        AuthResult::Success => return Ok(()),
+        AuthResult::Failure { remaining_methods, .. } => remaining_methods,
+    };
+    let offered: Vec<String> = methods.iter().map(String::from).collect();
+    debug_log!("[SSH-DIAG] {} offers auth methods: {:?}", hop.host, offered);
+    if let Some(report) = report {
+        report.auth_methods = offered;
+    }
+    if !trusted {
+        return Err("Host key not trusted (no known_hosts entry), credentials not sent. Connect once to verify the host key".into());
+    }
+
+    let result = tokio::time::timeout(STEP_TIMEOUT, connect::authenticate_configured(session, hop.username, hop.auth))
+        .await
+        .map_err(|_| "Authentication timed out")??;
+
+    match result {
+        AuthResult::Success => Ok(()),
+        AuthResult::Failure { remaining_methods, partial_success } => {
+            if partial_success || remaining_methods.contains(&MethodKind::KeyboardInteractive) {
+                Err("Server requires keyboard-interactive authentication (MFA), which is not attempted during a connection test".into())
+            } else {
+                Err("Authentication failed".into())
+            }
+        }
+    }
+}
+
+fn record_handshake(report: &mut DiagnosticReport, observed: &Observed) {
+    report.server_banner = observed.banner.clone();
+    report.algorithms = observed.algorithms.clone();
+    report.host_key_status = observed.status.clone();
+    if let Some(key) = &observed.key {
+        report.host_key_type = Some(key.algorithm().to_string());
+        report.host_key_fingerprint = Some(key.fingerprint(HashAlg::Sha256).to_string());
+    }
+}
+
+fn hop_error(is_target: bool, hop: &Hop<'_>, reason: String) -> Box<dyn std::error::Error + Send + Sync> {
+    if is_target {
+        reason.into()
+    } else {
+        format!("Jump host {}: {}", hop.host, reason).into()
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
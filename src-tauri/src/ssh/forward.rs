@@ -0,0 +1,333 @@
+use crate::models::{ForwardKind, PortForwardConfig};
+use crate::ssh::pool::TransportHandle;
+use crate::ssh::socks;
+use russh::{client, Channel};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+
+/// 转发流量统计
+#[derive(Debug, Default)]
+pub struct ForwardCounters {
+    /// 本地 → SSH 通道的字节数
+    pub bytes_sent: AtomicU64,
+    /// SSH 通道 → 本地的字节数
+    pub bytes_received: AtomicU64,
+    pub total_connections: AtomicU64,
+    pub active_connections: AtomicU64,
+}
+
+/// 转发状态，返回给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct ForwardStatus {
+    #[serde(flatten)]
+    pub config: PortForwardConfig,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub total_connections: u64,
+    pub active_connections: u64,
+}
+
+/// 会话上运行中的端口转发
+pub enum PortForward {
+    Local(LocalForward),
+    Remote(RemoteForward),
+}
+
+impl PortForward {
+    pub async fn start(
+        config: PortForwardConfig,
+        handle: TransportHandle,
+        routes: RemoteRoutes,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match config.kind {
+            ForwardKind::Local | ForwardKind::Dynamic => PortForward::Local(LocalForward::start(config, handle).await?),
+            ForwardKind::Remote => PortForward::Remote(RemoteForward::start(config, handle, routes).await?),
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            PortForward::Local(f) => f.id(),
+            PortForward::Remote(f) => f.id(),
+        }
+    }
+
+    pub fn status(&self) -> ForwardStatus {
+        match self {
+            PortForward::Local(f) => f.status(),
+            PortForward::Remote(f) => f.status(),
+        }
+    }
+
+    /// 连接重建后恢复转发：本地转发每次都通过当前连接打开通道，无需处理；
+    /// 远程转发需要在新连接上重新请求服务器监听
+    pub async fn resume(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            PortForward::Local(_) => Ok(()),
+            PortForward::Remote(f) => f.resume().await,
+        }
+    }
+}
+
+/// 本地监听的转发：监听本地端口，每个连接通过 direct-tcpip 通道转发
+///
+/// -L 转发到固定的 target_host:target_port；
+/// -D 作为 SOCKS5 代理，目标地址由每个客户端在握手时指定。
+pub struct LocalForward {
+    config: PortForwardConfig,
+    counters: Arc<ForwardCounters>,
+    /// 监听任务，内部持有所有转发连接，abort 时一并关闭
+    task: JoinHandle<()>,
+}
+
+impl LocalForward {
+    pub async fn start(
+        config: PortForwardConfig,
+        handle: TransportHandle,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let listener = TcpListener::bind((config.bind_host.as_str(), config.bind_port)).await
+            .map_err(|e| format!("Failed to bind {}:{}: {}", config.bind_host, config.bind_port, e))?;
+        debug_log!("[SSH-FWD] Listening on {}:{} ({:?}) -> {}:{}",
+            config.bind_host, config.bind_port, config.kind, config.target_host, config.target_port);
+
+        let counters = Arc::new(ForwardCounters::default());
+        let task = tokio::spawn(accept_loop(listener, config.clone(), handle, counters.clone()));
+
+        Ok(Self { config, counters, task })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    pub fn status(&self) -> ForwardStatus {
+        status_of(&self.config, &self.counters)
+    }
+}
+
+impl Drop for LocalForward {
+    fn drop(&mut self) {
+        debug_log!("[SSH-FWD] Stopping forward {}", self.config.id);
+        self.task.abort();
+    }
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    config: PortForwardConfig,
+    handle: TransportHandle,
+    counters: Arc<ForwardCounters>,
+) {
+    let mut connections = JoinSet::new();
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                debug_log!("[SSH-FWD] Accept failed on {}: {}", config.id, e);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        // 回收已结束的连接任务
+        while connections.try_join_next().is_some() {}
+
+        let handle = handle.clone();
+        let counters = counters.clone();
+        let is_socks = config.kind == ForwardKind::Dynamic;
+        let fixed_target = (config.target_host.clone(), config.target_port);
+        connections.spawn(async move {
+            let mut socket = socket;
+            let (target_host, target_port) = if is_socks {
+                match socks::handshake(&mut socket).await {
+                    Ok(target) => target,
+                    Err(e) => {
+                        debug_log!("[SSH-FWD] SOCKS handshake with {} failed: {}", peer, e);
+                        return;
+                    }
+                }
+            } else {
+                fixed_target
+            };
+
+            let channel = handle.read().await
+                .channel_open_direct_tcpip(target_host.as_str(), target_port as u32, peer.ip().to_string(), peer.port() as u32)
+                .await;
+            match channel {
+                Ok(channel) => {
+                    if is_socks && socks::reply(&mut socket, socks::REP_SUCCEEDED).await.is_err() {
+                        return;
+                    }
+                    bridge(socket, channel.into_stream(), &counters).await
+                }
+                Err(e) => {
+                    debug_log!("[SSH-FWD] Failed to open channel to {}:{}: {}", target_host, target_port, e);
+                    if is_socks {
+                        let _ = socks::reply(&mut socket, socks::REP_HOST_UNREACHABLE).await;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// 远程转发路由：服务器监听地址 (bind_host, bind_port) → 本地目标
+#[derive(Clone)]
+pub struct RemoteRoute {
+    pub target_host: String,
+    pub target_port: u16,
+    pub counters: Arc<ForwardCounters>,
+}
+
+/// 远程转发路由表，由 SSH Client 处理器在服务器打开 forwarded-tcpip 通道时查询
+pub type RemoteRoutes = Arc<std::sync::Mutex<HashMap<(String, u32), RemoteRoute>>>;
+
+/// 远程端口转发（-R）：请求服务器监听端口，服务器转来的连接桥接到本地目标
+pub struct RemoteForward {
+    config: PortForwardConfig,
+    counters: Arc<ForwardCounters>,
+    handle: TransportHandle,
+    routes: RemoteRoutes,
+}
+
+impl RemoteForward {
+    pub async fn start(
+        mut config: PortForwardConfig,
+        handle: TransportHandle,
+        routes: RemoteRoutes,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let bound_port = handle.write().await
+            .tcpip_forward(config.bind_host.as_str(), config.bind_port as u32)
+            .await
+            .map_err(|e| format!("Server refused to listen on {}:{}: {}", config.bind_host, config.bind_port, e))?;
+        // 请求端口为 0 时由服务器分配，记录实际端口
+        if config.bind_port == 0 {
+            config.bind_port = bound_port as u16;
+        }
+        debug_log!("[SSH-FWD] Remote listening on {}:{} -> {}:{}",
+            config.bind_host, config.bind_port, config.target_host, config.target_port);
+
+        let counters = Arc::new(ForwardCounters::default());
+        routes.lock().unwrap().insert(
+            (config.bind_host.clone(), config.bind_port as u32),
+            RemoteRoute {
+                target_host: config.target_host.clone(),
+                target_port: config.target_port,
+                counters: counters.clone(),
+            },
+        );
+
+        Ok(Self { config, counters, handle, routes })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    pub fn status(&self) -> ForwardStatus {
+        status_of(&self.config, &self.counters)
+    }
+
+    async fn resume(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.handle.write().await
+            .tcpip_forward(self.config.bind_host.as_str(), self.config.bind_port as u32)
+            .await
+            .map_err(|e| format!("Server refused to listen on {}:{}: {}", self.config.bind_host, self.config.bind_port, e))?;
+        Ok(())
+    }
+}
+
+impl Drop for RemoteForward {
+    fn drop(&mut self) {
+        debug_log!("[SSH-FWD] Stopping remote forward {}", self.config.id);
+        let key = (self.config.bind_host.clone(), self.config.bind_port as u32);
+        self.routes.lock().unwrap().remove(&key);
+
+        // 通知服务器停止监听（会话已关闭时忽略）
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let handle = self.handle.clone();
+            runtime.spawn(async move {
+                let _ = handle.read().await.cancel_tcpip_forward(key.0, key.1).await;
+            });
+        }
+    }
+}
+
+/// 处理服务器打开的 forwarded-tcpip 通道：查找路由并连接本地目标
+pub fn accept_forwarded(
+    routes: &RemoteRoutes,
+    channel: Channel<client::Msg>,
+    connected_address: &str,
+    connected_port: u32,
+) {
+    let route = {
+        let routes = routes.lock().unwrap();
+        // 服务器回报的地址可能与请求时不同（如 "localhost" 与 "127.0.0.1"），退而按端口匹配
+        routes.get(&(connected_address.to_string(), connected_port))
+            .or_else(|| routes.iter().find(|((_, port), _)| *port == connected_port).map(|(_, r)| r))
+            .cloned()
+    };
+
+    let Some(route) = route else {
+        debug_log!("[SSH-FWD] No route for forwarded connection to {}:{}", connected_address, connected_port);
+        return;
+    };
+
+    tokio::spawn(async move {
+        match TcpStream::connect((route.target_host.as_str(), route.target_port)).await {
+            Ok(socket) => bridge(socket, channel.into_stream(), &route.counters).await,
+            Err(e) => debug_log!("[SSH-FWD] Failed to connect to {}:{}: {}", route.target_host, route.target_port, e),
+        }
+    });
+}
+
+/// 在本地流与 SSH 通道之间双向转发数据，并累计字节数
+pub async fn bridge<L, R>(local: L, remote: R, counters: &ForwardCounters)
+where
+    L: AsyncRead + AsyncWrite + Unpin,
+    R: AsyncRead + AsyncWrite + Unpin,
+{
+    counters.total_connections.fetch_add(1, Ordering::Relaxed);
+    counters.active_connections.fetch_add(1, Ordering::Relaxed);
+
+    let (local_read, local_write) = tokio::io::split(local);
+    let (remote_read, remote_write) = tokio::io::split(remote);
+    let _ = tokio::join!(
+        pipe(local_read, remote_write, &counters.bytes_sent),
+        pipe(remote_read, local_write, &counters.bytes_received),
+    );
+
+    counters.active_connections.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// 单向拷贝，读到 EOF 后关闭写端，让对端也能感知连接结束
+async fn pipe<R, W>(mut reader: R, mut writer: W, counter: &AtomicU64) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; 32 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            writer.shutdown().await?;
+            return Ok(());
+        }
+        writer.write_all(&buf[..n]).await?;
+        counter.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+pub fn status_of(config: &PortForwardConfig, counters: &ForwardCounters) -> ForwardStatus {
+    ForwardStatus {
+        config: config.clone(),
+        bytes_sent: counters.bytes_sent.load(Ordering::Relaxed),
+        bytes_received: counters.bytes_received.load(Ordering::Relaxed),
+        total_connections: counters.total_connections.load(Ordering::Relaxed),
+        active_connections: counters.active_connections.load(Ordering::Relaxed),
+    }
+}
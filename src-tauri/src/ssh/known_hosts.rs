@@ -0,0 +1,441 @@
+use crate::ssh::client::ClientError;
+use crate::ssh::commands::HostKeyResponseMap;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use russh::keys::{known_hosts, HashAlg, PublicKey};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// 主机密钥确认提示，发送到前端
+#[derive(Debug, Clone, Serialize)]
+pub struct HostKeyPromptPayload {
+    pub terminal_id: String,
+    pub host: String,
+    pub port: u16,
+    pub key_type: String,
+    /// SHA256 指纹，格式与 OpenSSH 一致（SHA256:xxxx）
+    pub fingerprint: String,
+}
+
+/// 用户对未知主机密钥的决定
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum HostKeyDecision {
+    /// 拒绝连接
+    Reject,
+    /// 仅本次信任，不写入 known_hosts
+    AcceptOnce,
+    /// 信任并写入 Konnect 的 known_hosts
+    AcceptAndSave,
+}
+
+/// 用户自己的 ~/.ssh/known_hosts（只读）
+fn user_known_hosts_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh").join("known_hosts"))
+}
+
+/// Konnect 自己维护的 known_hosts，信任的新主机写入这里
+pub fn konnect_known_hosts_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("konnect").join("known_hosts"))
+}
+
+/// 主机密钥在 known_hosts 中的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status")]
+pub enum HostKeyStatus {
+    /// 存在匹配记录
+    Known { path: PathBuf },
+    /// 同算法的记录与服务器密钥不一致
+    Changed { path: PathBuf, line: usize },
+    /// 密钥被 @revoked 标记吊销
+    Revoked { path: PathBuf, line: usize },
+    /// 没有记录
+    Unknown,
+}
+
+/// 在 ~/.ssh/known_hosts 与 Konnect known_hosts 中查找主机密钥（不提示、不写入）
+pub fn lookup(host: &str, port: u16, key: &PublicKey) -> HostKeyStatus {
+    let paths: Vec<PathBuf> = [user_known_hosts_path(), konnect_known_hosts_path()]
+        .into_iter()
+        .flatten()
+        .collect();
+    lookup_in(&paths, host, port, key)
+}
+
+/// 依次在给定文件中查找主机密钥；任一文件吊销该密钥时优先返回 Revoked
+///
+/// 带 @cert-authority 标记的行不参与匹配（不支持主机证书），未知主机仍需用户确认。
+fn lookup_in(paths: &[PathBuf], host: &str, port: u16, key: &PublicKey) -> HostKeyStatus {
+    for path in paths {
+        if let Some(line) = revoked_line(path, host, port, key) {
+            return HostKeyStatus::Revoked { path: path.clone(), line };
+        }
+    }
+    for path in paths {
+        match known_hosts::check_known_hosts_path(host, port, key, path) {
+            Ok(true) => return HostKeyStatus::Known { path: path.clone() },
+            Ok(false) => {}
+            Err(russh::keys::Error::KeyChanged { line }) => {
+                let line = physical_line(path, line).unwrap_or(line);
+                return HostKeyStatus::Changed { path: path.clone(), line };
+            }
+            Err(e) => {
+                // 文件损坏或包含无法解析的密钥时，视为没有记录
+                debug_log!("[SSH-HOSTKEY] Skipping {}: {}", path.display(), e);
+            }
+        }
+    }
+    HostKeyStatus::Unknown
+}
+
+/// russh 报告的行号不计 # 注释行，换算为文件中的实际行号
+fn physical_line(path: &Path, line: usize) -> Option<usize> {
+    let content = std::fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.starts_with('#'))
+        .nth(line.checked_sub(1)?)
+        .map(|(index, _)| index + 1)
+}
+
+/// 查找吊销该密钥的 @revoked 行，返回行号（从 1 开始）
+///
+/// russh 会忽略带标记的行，因此这里单独解析。
+fn revoked_line(path: &Path, host: &str, port: u16, key: &PublicKey) -> Option<usize> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let host_port = if port == 22 { host.to_string() } else { format!("[{}]:{}", host, port) };
+
+    content.lines().enumerate().find_map(|(index, line)| {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some("@revoked") {
+            return None;
+        }
+        let (patterns, algorithm, data) = (fields.next()?, fields.next()?, fields.next()?);
+        if !match_host_patterns(&host_port, patterns) {
+            return None;
+        }
+        let revoked = PublicKey::from_openssh(&format!("{} {}", algorithm, data)).ok()?;
+        (revoked.key_data() == key.key_data()).then_some(index + 1)
+    })
+}
+
+/// 按 known_hosts 规则匹配逗号分隔的主机模式：支持 |1| 散列、* 与 ? 通配符和 ! 否定
+fn match_host_patterns(host: &str, patterns: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns.split(',') {
+        if let Some(hashed) = pattern.strip_prefix("|1|") {
+            matched |= match_hashed(host, hashed);
+        } else if let Some(negated) = pattern.strip_prefix('!') {
+            if match_wildcard(host, negated) {
+                return false;
+            }
+        } else {
+            matched |= match_wildcard(host, pattern);
+        }
+    }
+    matched
+}
+
+/// 匹配 OpenSSH 散列主机名（`salt|hash`，均为 base64，hash 为以 salt 为密钥的 HMAC-SHA1）
+fn match_hashed(host: &str, hashed: &str) -> bool {
+    let Some((salt, hash)) = hashed.split_once('|') else {
+        return false;
+    };
+    let (Ok(salt), Ok(hash)) = (BASE64.decode(salt), BASE64.decode(hash)) else {
+        return false;
+    };
+    let Ok(mac) = Hmac::<Sha1>::new_from_slice(&salt) else {
+        return false;
+    };
+    mac.chain_update(host.as_bytes()).verify_slice(&hash).is_ok()
+}
+
+/// 不区分大小写的通配符匹配：* 匹配任意字符串，? 匹配单个字符
+fn match_wildcard(host: &str, pattern: &str) -> bool {
+    fn matches(host: &[u8], pattern: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => host.is_empty(),
+            Some((b'*', rest)) => (0..=host.len()).any(|skip| matches(&host[skip..], rest)),
+            Some((b'?', rest)) => !host.is_empty() && matches(&host[1..], rest),
+            Some((c, rest)) => host.first().is_some_and(|h| h.eq_ignore_ascii_case(c))
+                && matches(&host[1..], rest),
+        }
+    }
+    matches(host.as_bytes(), pattern.as_bytes())
+}
+
+/// 主机密钥校验器
+///
+/// 校验顺序：
+/// 1. ~/.ssh/known_hosts 与 Konnect known_hosts 中存在匹配记录 → 直接通过
+/// 2. 任一文件中同算法的记录与服务器密钥不一致，或密钥被 @revoked 吊销 → 直接失败（可能是中间人攻击）
+/// 3. 没有记录 → 向前端发送 ssh-hostkey-prompt 事件，等待用户确认
+#[derive(Clone)]
+pub struct HostKeyVerifier {
+    host: String,
+    port: u16,
+    terminal_id: String,
+    app_handle: AppHandle,
+    responses: HostKeyResponseMap,
+}
+
+impl HostKeyVerifier {
+    pub fn new(
+        host: String,
+        port: u16,
+        terminal_id: String,
+        app_handle: AppHandle,
+        responses: HostKeyResponseMap,
+    ) -> Self {
+        Self { host, port, terminal_id, app_handle, responses }
+    }
+
+    pub async fn verify(&self, key: &PublicKey) -> Result<bool, ClientError> {
+        let fingerprint = key.fingerprint(HashAlg::Sha256).to_string();
+        debug_log!("[SSH-HOSTKEY] {}:{} presented {} {}", self.host, self.port, key.algorithm(), fingerprint);
+
+        match lookup(&self.host, self.port, key) {
+            HostKeyStatus::Known { path } => {
+                debug_log!("[SSH-HOSTKEY] ✅ Host key matches {}", path.display());
+                return Ok(true);
+            }
+            HostKeyStatus::Changed { path, line } => {
+                debug_log!("[SSH-HOSTKEY] ❌ Host key changed ({}:{})", path.display(), line);
+                return Err(ClientError::HostKeyChanged {
+                    host: self.host.clone(),
+                    port: self.port,
+                    fingerprint,
+                    path,
+                    line,
+                });
+            }
+            HostKeyStatus::Revoked { path, line } => {
+                debug_log!("[SSH-HOSTKEY] ❌ Host key revoked ({}:{})", path.display(), line);
+                return Err(ClientError::HostKeyRevoked {
+                    host: self.host.clone(),
+                    port: self.port,
+                    fingerprint,
+                    path,
+                    line,
+                });
+            }
+            HostKeyStatus::Unknown => {}
+        }
+
+        match self.prompt(key, fingerprint).await {
+            HostKeyDecision::AcceptAndSave => {
+                if let Some(path) = konnect_known_hosts_path() {
+                    if let Err(e) = known_hosts::learn_known_hosts_path(&self.host, self.port, key, &path) {
+                        debug_log!("[SSH-HOSTKEY] Failed to save host key: {}", e);
+                    }
+                }
+                Ok(true)
+            }
+            HostKeyDecision::AcceptOnce => Ok(true),
+            HostKeyDecision::Reject => Err(ClientError::HostKeyRejected {
+                host: self.host.clone(),
+                port: self.port,
+            }),
+        }
+    }
+
+    /// 向前端询问是否信任未知主机，超时或取消都视为拒绝
+    async fn prompt(&self, key: &PublicKey, fingerprint: String) -> HostKeyDecision {
+        let (tx, rx) = tokio::sync::oneshot::channel::<HostKeyDecision>();
+        self.responses.lock().await.insert(self.terminal_id.clone(), tx);
+
+        let payload = HostKeyPromptPayload {
+            terminal_id: self.terminal_id.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            key_type: key.algorithm().to_string(),
+            fingerprint,
+        };
+
+        debug_log!("[SSH-HOSTKEY] Emitting ssh-hostkey-prompt event to frontend");
+        if let Err(e) = self.app_handle.emit("ssh-hostkey-prompt", &payload) {
+            debug_log!("[SSH-HOSTKEY] Failed to emit prompt: {}", e);
+            self.responses.lock().await.remove(&self.terminal_id);
+            return HostKeyDecision::Reject;
+        }
+
+        match tokio::time::timeout(std::time::Duration::from_secs(120), rx).await {
+            Ok(Ok(decision)) => {
+                debug_log!("[SSH-HOSTKEY] User decision: {:?}", decision);
+                decision
+            }
+            Ok(Err(_)) => {
+                debug_log!("[SSH-HOSTKEY] Host key prompt cancelled");
+                HostKeyDecision::Reject
+            }
+            Err(_) => {
+                debug_log!("[SSH-HOSTKEY] Host key prompt timeout");
+                self.responses.lock().await.remove(&self.terminal_id);
+                HostKeyDecision::Reject
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const ED25519_A: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJdD7y3aLq454yWBdwLWbieU1ebz9/cu7/QEXn9OIeZJ";
+    const ED25519_B: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILIG2T/B0l0gaqj3puu510tu9N1OkQ4znY3LYuEm5zCF";
+    const ECDSA: &str = "ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBLHZGADMdxZg4oXTdOUHe6EhC6NUdnJmjPutZNBBnJuYdQA+0iO5bJXHDo4Q4G0oD0QPLGPzE4e80RyqBIWJraI=";
+
+    fn key(openssh: &str) -> PublicKey {
+        PublicKey::from_openssh(openssh).unwrap()
+    }
+
+    fn known_hosts(content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    /// 按 OpenSSH HashKnownHosts 的格式散列主机名
+    fn hashed(host: &str) -> String {
+        let salt = b"0123456789abcdefghij";
+        let mac = Hmac::<Sha1>::new_from_slice(salt).unwrap().chain_update(host.as_bytes());
+        format!("|1|{}|{}", BASE64.encode(salt), BASE64.encode(mac.finalize().into_bytes()))
+    }
+
+    fn lookup_file(file: &tempfile::NamedTempFile, host: &str, port: u16, key: &PublicKey) -> HostKeyStatus {
+        lookup_in(&[file.path().to_path_buf()], host, port, key)
+    }
+
+    #[test]
+    fn plain_entry_is_known() {
+        let file = known_hosts(&format!("other.example {}\nserver.example {}\n", ED25519_B, ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+    }
+
+    #[test]
+    fn hashed_entry_is_known() {
+        let file = known_hosts(&format!("{} {}\n", hashed("server.example"), ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+        assert!(matches!(lookup_file(&file, "other.example", 22, &key(ED25519_A)), HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn bracketed_entry_matches_only_its_port() {
+        let file = known_hosts(&format!("[server.example]:2222 {}\n", ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 2222, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Unknown));
+        assert!(matches!(lookup_file(&file, "server.example", 2200, &key(ED25519_A)), HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn port_22_entry_does_not_match_other_ports() {
+        let file = known_hosts(&format!("server.example {}\n", ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 2222, &key(ED25519_A)), HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn hashed_bracketed_entry_is_known() {
+        let file = known_hosts(&format!("{} {}\n", hashed("[server.example]:2222"), ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 2222, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn same_algorithm_different_key_is_changed_with_physical_line() {
+        let file = known_hosts(&format!(
+            "# managed by ops\nother.example {}\n# rotated 2024\nserver.example {}\n",
+            ED25519_A, ED25519_B
+        ));
+        match lookup_file(&file, "server.example", 22, &key(ED25519_A)) {
+            HostKeyStatus::Changed { path, line } => {
+                assert_eq!(path, file.path());
+                assert_eq!(line, 4);
+            }
+            other => panic!("expected Changed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn different_algorithm_is_unknown() {
+        let file = known_hosts(&format!("server.example {}\n", ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ECDSA)), HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn missing_file_is_unknown() {
+        let dir = tempfile::tempdir().unwrap();
+        let status = lookup_in(&[dir.path().join("known_hosts")], "server.example", 22, &key(ED25519_A));
+        assert!(matches!(status, HostKeyStatus::Unknown));
+    }
+
+    #[test]
+    fn revoked_key_is_rejected_even_when_listed() {
+        let file = known_hosts(&format!(
+            "server.example {}\n@revoked * {}\n",
+            ED25519_A, ED25519_A
+        ));
+        match lookup_file(&file, "server.example", 22, &key(ED25519_A)) {
+            HostKeyStatus::Revoked { line, .. } => assert_eq!(line, 2),
+            other => panic!("expected Revoked, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn revoked_entry_honours_host_patterns() {
+        let file = known_hosts(&format!(
+            "@revoked {},*.internal,!db.internal {}\n",
+            hashed("[server.example]:2222"), ED25519_A
+        ));
+        let revoked = |host: &str, port: u16| {
+            matches!(lookup_file(&file, host, port, &key(ED25519_A)), HostKeyStatus::Revoked { .. })
+        };
+        assert!(revoked("server.example", 2222));
+        assert!(revoked("WEB.internal", 22));
+        assert!(!revoked("db.internal", 22));
+        assert!(!revoked("server.example", 22));
+    }
+
+    #[test]
+    fn revoked_entry_for_other_key_does_not_affect_lookup() {
+        let file = known_hosts(&format!("@revoked * {}\nserver.example {}\n", ED25519_B, ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+    }
+
+    #[test]
+    fn cert_authority_entry_is_not_trusted_as_host_key() {
+        let file = known_hosts(&format!("@cert-authority server.example {}\n", ED25519_A));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Unknown));
+
+        let file = known_hosts(&format!(
+            "@cert-authority *.example {}\nserver.example {}\n",
+            ED25519_B, ED25519_A
+        ));
+        assert!(matches!(lookup_file(&file, "server.example", 22, &key(ED25519_A)), HostKeyStatus::Known { .. }));
+    }
+
+    #[test]
+    fn known_in_second_file() {
+        let first = known_hosts(&format!("other.example {}\n", ED25519_A));
+        let second = known_hosts(&format!("server.example {}\n", ED25519_A));
+        let paths = [first.path().to_path_buf(), second.path().to_path_buf()];
+        match lookup_in(&paths, "server.example", 22, &key(ED25519_A)) {
+            HostKeyStatus::Known { path } => assert_eq!(path, second.path()),
+            other => panic!("expected Known, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn revocation_in_second_file_wins_over_first_file_match() {
+        let first = known_hosts(&format!("server.example {}\n", ED25519_A));
+        let second = known_hosts(&format!("@revoked server.example {}\n", ED25519_A));
+        let paths = [first.path().to_path_buf(), second.path().to_path_buf()];
+        match lookup_in(&paths, "server.example", 22, &key(ED25519_A)) {
+            HostKeyStatus::Revoked { path, .. } => assert_eq!(path, second.path()),
+            other => panic!("expected Revoked, got {:?}", other),
+        }
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize)]
 pub struct MfaPromptPayload {
     pub terminal_id: String,
+    /// 发起认证的主机（经过跳板机时用于区分是哪一跳）
+    pub host: String,
     pub name: String,
     pub instructions: String,
     pub prompts: Vec<MfaPrompt>,
@@ -1,7 +1,19 @@
 mod session;
 pub mod commands;
 pub mod mfa;
+pub mod client;
+pub mod known_hosts;
+pub mod agent;
+pub mod algorithms;
+pub mod connect;
+pub mod forward;
+pub mod socks;
+pub mod pool;
+pub mod diagnostics;
+pub mod reconnect;
 
 pub use session::SshSession;
+pub use pool::{SharedTransport, SshTransportPool, create_transport_pool};
 pub use commands::{create_ssh_terminal, test_ssh_connection, submit_ssh_mfa_response, cancel_ssh_mfa, create_mfa_response_map, MfaResponseMap};
-
+pub use commands::{submit_ssh_hostkey_response, create_host_key_response_map, HostKeyResponseMap};
+pub use commands::{ssh_forward_add, ssh_forward_list, ssh_forward_remove, ssh_socks_start, ssh_socks_stop};
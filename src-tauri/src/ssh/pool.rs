@@ -0,0 +1,192 @@
+use crate::models::SshConfig;
+use crate::ssh::client::Client;
+use crate::ssh::commands::{MfaResponseMap, HostKeyResponseMap};
+use crate::ssh::connect;
+use crate::ssh::forward::RemoteRoutes;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use russh::client;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::RwLock;
+use tauri::AppHandle;
+
+/// 连接句柄：打开通道只需共享访问，可在同一连接上并发进行；
+/// 仅重连替换句柄、请求远程转发（russh 的 `tcpip_forward` 需要 `&mut`）时独占
+pub type TransportHandle = Arc<RwLock<client::Handle<Client>>>;
+
+/// 共享的已认证 SSH 连接
+///
+/// 终端、SFTP、端口转发各自持有一个 `Arc<SharedTransport>`，
+/// 在同一连接上打开各自的通道；最后一个持有者释放时断开连接。
+pub struct SharedTransport {
+    key: String,
+    pub handle: TransportHandle,
+    pub remote_routes: RemoteRoutes,
+    /// 跳板机连接，目标连接的数据经由这些连接转发
+    jumps: std::sync::Mutex<Vec<client::Handle<Client>>>,
+}
+
+impl SharedTransport {
+    /// 重新建立已断开的连接，替换内部的连接句柄
+    ///
+    /// 持有者（终端、端口转发）拿到的 `handle` 不变，之后打开的通道自动走新连接；
+    /// 远程转发路由表沿用，但服务器端的监听需要重新请求。
+    async fn reestablish(&self, request: &EstablishRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let established = request.establish(&self.remote_routes).await?;
+
+        *self.handle.write().await = established.handle;
+        *self.jumps.lock().unwrap() = established.jumps;
+        Ok(())
+    }
+
+    /// 连接是否已断开；句柄正被独占（远程转发请求中）时视为可用
+    fn is_closed(&self) -> bool {
+        self.handle.try_read().map(|handle| handle.is_closed()).unwrap_or(false)
+    }
+}
+
+
+impl Drop for SharedTransport {
+    fn drop(&mut self) {
+        debug_log!("[SSH-POOL] Last user of transport {} released, disconnecting", self.key);
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let handle = self.handle.clone();
+            runtime.spawn(async move {
+                let _ = handle.read().await
+                    .disconnect(russh::Disconnect::ByApplication, "", "en")
+                    .await;
+            });
+        }
+    }
+}
+
+/// 正在进行的建立 / 重连，并发获取同一连接的调用方等待同一个结果
+type Establishing = Shared<BoxFuture<'static, Result<Arc<SharedTransport>, String>>>;
+
+/// 单个连接的槽位
+pub enum TransportSlot {
+    /// 只保存弱引用，引用计数归零后连接自动释放
+    Ready(Weak<SharedTransport>),
+    /// 建立期间（例如等待主机密钥确认或 MFA）不持有任何锁，
+    /// 之后的调用方复用同一个 future，提示只出现一次
+    Connecting(Establishing, Weak<SharedTransport>),
+}
+
+// SSH 连接池类型：按 Connection.id 共享已认证连接
+pub type SshTransportPool = Arc<std::sync::Mutex<HashMap<String, TransportSlot>>>;
+
+/// 创建 SSH 连接池
+pub fn create_transport_pool() -> SshTransportPool {
+    Arc::new(std::sync::Mutex::new(HashMap::new()))
+}
+
+/// 建立连接所需的参数，复制一份以便在共享的 future 中使用
+#[derive(Clone)]
+struct EstablishRequest {
+    config: SshConfig,
+    prompt_id: String,
+    app_handle: AppHandle,
+    mfa_channels: MfaResponseMap,
+    host_key_channels: HostKeyResponseMap,
+}
+
+impl EstablishRequest {
+    async fn establish(&self, remote_routes: &RemoteRoutes) -> Result<connect::SshTransport, Box<dyn std::error::Error + Send + Sync>> {
+        connect::establish(
+            &self.config,
+            remote_routes,
+            &self.prompt_id,
+            &self.app_handle,
+            &self.mfa_channels,
+            &self.host_key_channels,
+        ).await
+    }
+}
+
+/// 获取（必要时建立）`key` 对应的 SSH 连接
+///
+/// `prompt_id` 为发起方的会话 ID，需要新建连接时主机密钥/MFA 提示发往该会话。
+pub async fn acquire(
+    pool: &SshTransportPool,
+    key: &str,
+    config: &SshConfig,
+    prompt_id: &str,
+    app_handle: &AppHandle,
+    mfa_channels: &MfaResponseMap,
+    host_key_channels: &HostKeyResponseMap,
+) -> Result<Arc<SharedTransport>, Box<dyn std::error::Error + Send + Sync>> {
+    let (establishing, previous) = {
+        let mut slots = pool.lock().unwrap();
+        // 顺便清理已释放的连接（正在建立中的槽位保留）
+        slots.retain(|_, slot| !matches!(slot, TransportSlot::Ready(weak) if weak.strong_count() == 0));
+
+        match slots.get(key) {
+            Some(TransportSlot::Connecting(establishing, previous)) => {
+                debug_log!("[SSH-POOL] Waiting for transport {} being established", key);
+                (establishing.clone(), previous.clone())
+            }
+            slot => {
+                let previous = match slot {
+                    Some(TransportSlot::Ready(weak)) => weak.clone(),
+                    _ => Weak::new(),
+                };
+                let existing = previous.upgrade();
+                if let Some(transport) = &existing {
+                    if !transport.is_closed() {
+                        debug_log!("[SSH-POOL] Reusing transport {}", key);
+                        return Ok(transport.clone());
+                    }
+                    debug_log!("[SSH-POOL] Transport {} is closed, reconnecting", key);
+                }
+
+                let request = EstablishRequest {
+                    config: config.clone(),
+                    prompt_id: prompt_id.to_string(),
+                    app_handle: app_handle.clone(),
+                    mfa_channels: mfa_channels.clone(),
+                    host_key_channels: host_key_channels.clone(),
+                };
+                let establishing = establish_transport(key.to_string(), existing, request).boxed().shared();
+                slots.insert(key.to_string(), TransportSlot::Connecting(establishing.clone(), previous.clone()));
+                (establishing, previous)
+            }
+        }
+    };
+
+    let result = establishing.clone().await;
+
+    // 第一个拿到结果的调用方把槽位切回 Ready；失败时保留原有连接，下次获取时再原地重连
+    {
+        let mut slots = pool.lock().unwrap();
+        if let Some(TransportSlot::Connecting(current, _)) = slots.get(key) {
+            if current.ptr_eq(&establishing) {
+                let weak = result.as_ref().map(Arc::downgrade).unwrap_or(previous);
+                slots.insert(key.to_string(), TransportSlot::Ready(weak));
+            }
+        }
+    }
+    Ok(result?)
+}
+
+async fn establish_transport(
+    key: String,
+    existing: Option<Arc<SharedTransport>>,
+    request: EstablishRequest,
+) -> Result<Arc<SharedTransport>, String> {
+    if let Some(transport) = existing {
+        // 仍有使用者持有该连接，原地重连，让所有使用者共享新连接
+        transport.reestablish(&request).await.map_err(|e| e.to_string())?;
+        return Ok(transport);
+    }
+
+    let remote_routes = RemoteRoutes::default();
+    let established = request.establish(&remote_routes).await.map_err(|e| e.to_string())?;
+    let transport = Arc::new(SharedTransport {
+        key: key.clone(),
+        handle: Arc::new(RwLock::new(established.handle)),
+        remote_routes,
+        jumps: std::sync::Mutex::new(established.jumps),
+    });
+    debug_log!("[SSH-POOL] Transport {} established", key);
+    Ok(transport)
+}
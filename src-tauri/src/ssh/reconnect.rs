@@ -0,0 +1,85 @@
+use crate::models::{ReconnectPolicy, SshConfig};
+use crate::ssh::commands::{HostKeyResponseMap, MfaResponseMap};
+use crate::ssh::pool::{self, SharedTransport, SshTransportPool};
+use rand::Rng;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// 重连进度，随 terminal-reconnecting-{id} 事件发送到前端
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconnectingPayload {
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// 本次重连前的等待时间
+    pub delay_ms: u64,
+    /// 上一次重连失败的原因
+    pub last_error: Option<String>,
+}
+
+/// 断线重连所需的上下文
+pub struct Reconnector {
+    policy: ReconnectPolicy,
+    pool: SshTransportPool,
+    key: String,
+    config: SshConfig,
+    app_handle: AppHandle,
+    mfa_channels: MfaResponseMap,
+    host_key_channels: HostKeyResponseMap,
+}
+
+impl Reconnector {
+    pub fn new(
+        policy: ReconnectPolicy,
+        pool: SshTransportPool,
+        key: String,
+        config: SshConfig,
+        app_handle: AppHandle,
+        mfa_channels: MfaResponseMap,
+        host_key_channels: HostKeyResponseMap,
+    ) -> Self {
+        Self { policy, pool, key, config, app_handle, mfa_channels, host_key_channels }
+    }
+
+    pub fn config(&self) -> &SshConfig {
+        &self.config
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.policy.max_attempts
+    }
+
+    /// 第 `attempt` 次（从 1 开始）重连前的等待时间：指数退避，封顶后加上随机抖动
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let policy = &self.policy;
+        let exponent = attempt.saturating_sub(1).min(20);
+        let base = policy.initial_delay_ms
+            .saturating_mul(1u64 << exponent)
+            .min(policy.max_delay_ms);
+
+        let jitter = policy.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            1.0 + rand::thread_rng().gen_range(-jitter..=jitter)
+        } else {
+            1.0
+        };
+        Duration::from_millis((base as f64 * factor) as u64)
+    }
+
+    /// 通过连接池重建连接；连接仍被其他会话持有时原地重连，所有使用者共享新连接
+    pub async fn reconnect(
+        &self,
+        terminal_id: &str,
+    ) -> Result<Arc<SharedTransport>, Box<dyn std::error::Error + Send + Sync>> {
+        pool::acquire(
+            &self.pool,
+            &self.key,
+            &self.config,
+            terminal_id,
+            &self.app_handle,
+            &self.mfa_channels,
+            &self.host_key_channels,
+        ).await
+    }
+}
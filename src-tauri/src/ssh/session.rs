@@ -1,297 +1,159 @@
-use crate::models::{SshConfig, SshAuth};
-use crate::ssh::mfa::{MfaPromptPayload, MfaPrompt};
-use crate::ssh::commands::MfaResponseMap;
+use crate::models::{SshConfig, PortForwardConfig};
+use crate::ssh::client::Client;
+use crate::ssh::forward::{ForwardStatus, PortForward};
+use crate::ssh::pool::SharedTransport;
+use crate::ssh::reconnect::{Reconnector, ReconnectingPayload};
+use crate::terminal::{StatusCell, TerminalBackend, TerminalStatus};
+use async_trait::async_trait;
+use std::any::Any;
+use std::collections::HashMap;
 use russh::*;
-use russh::client::KeyboardInteractiveAuthResponse;
-use russh::keys::key::PrivateKeyWithHashAlg;
-use russh::keys::PublicKey;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use tauri::{AppHandle, Emitter};
 
-// SSH 客户端处理器
-struct Client {}
-
-impl client::Handler for Client {
-    type Error = russh::Error;
-
-    fn check_server_key(
-        &mut self,
-        _server_public_key: &PublicKey,
-    ) -> impl std::future::Future<Output = Result<bool, Self::Error>> + Send {
-        // 简化版：接受所有服务器密钥
-        // 生产环境应该验证 known_hosts
-        async { Ok(true) }
-    }
-}
+/// PTY 请求中的终端类型，远端据此设置 `$TERM`（与前端 xterm.js 一致）
+const TERMINAL_TYPE: &str = "xterm-256color";
 
 #[derive(Debug)]
 pub enum SshControl {
     Write(Vec<u8>),
     Resize { rows: u16, cols: u16 },
+    /// 主动关闭会话
+    Close,
 }
 
+/// 会话通道结束的原因
+enum ChannelEnd {
+    /// 远端 shell 正常退出（EOF / exit-status）
+    Exited,
+    /// 通道意外关闭（通常是连接断开）
+    Dropped,
+    /// 会话已被关闭（收到 Close 或控制通道已释放）
+    Detached,
+}
+
+type ForwardMap = Arc<Mutex<HashMap<String, PortForward>>>;
+
 pub struct SshSession {
     #[allow(dead_code)]
     id: String,
-    #[allow(dead_code)]
-    handle: Arc<Mutex<client::Handle<Client>>>,
+    /// 共享的 SSH 连接，终端关闭后若无其他使用者则断开
+    transport: Arc<SharedTransport>,
     control_tx: Arc<tokio::sync::mpsc::Sender<SshControl>>,
+    /// 端口转发，按转发 ID 索引，随会话一起关闭；重连后由读写任务恢复
+    forwards: ForwardMap,
+    status: StatusCell,
 }
 
 impl SshSession {
+    /// 创建 SSH 终端会话
+    ///
+    /// `reconnector` 不为空时，连接意外断开后按重连策略自动重连并重新打开 shell。
     pub async fn new(
         config: SshConfig,
         terminal_id: String,
         app_handle: AppHandle,
-        mfa_channels: MfaResponseMap,
+        transport: Arc<SharedTransport>,
+        reconnector: Option<Reconnector>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         debug_log!("[SSH] ===== Starting SSH Connection =====");
         debug_log!("[SSH] Target: {}@{}:{}", config.username, config.host, config.port);
 
-        // 创建 SSH 配置，包含更广泛的密钥交换算法支持
-        // 某些旧版服务器（如阿里云堡垒机）可能需要较旧的算法
-        let mut preferred = russh::Preferred::default();
-        
-        // 添加更多的密钥交换算法，包括旧版算法
-        preferred.kex = std::borrow::Cow::Owned(vec![
-            russh::kex::CURVE25519,
-            russh::kex::DH_G14_SHA256,
-            russh::kex::DH_G16_SHA512,
-            russh::kex::DH_G14_SHA1,
-            russh::kex::DH_G1_SHA1,
-            russh::kex::EXTENSION_SUPPORT_AS_CLIENT,
-        ]);
-
-        
-        let ssh_config = client::Config {
-            // 禁用客户端侧不活动超时，完全依赖 keepalive 机制
-            inactivity_timeout: None,
-            // 每隔 30 秒发送一次心跳包
-            keepalive_interval: Some(std::time::Duration::from_secs(30)),
-            // 连续 6 次心跳无响应才关闭连接（约 3 分钟容忍时间）
-            keepalive_max: 6,
-            preferred,
-            ..Default::default()
-        };
-
-
-
-        
-        debug_log!("[SSH] Config created, attempting TCP connection...");
-
-        // 创建客户端并连接（修复端口类型）
-        let session_result = client::connect(
-            Arc::new(ssh_config),
-            (config.host.as_str(), config.port),
-            Client {},
-        ).await;
-        
-        let mut session = match session_result {
-            Ok(s) => {
-                debug_log!("[SSH] ✅ TCP connection established");
-                s
-            }
-            Err(e) => {
-                debug_log!("[SSH] ❌ TCP connection failed: {}", e);
-                return Err(format!("Connection failed: {}", e).into());
-            }
-        };
-
-        debug_log!("[SSH] Authenticating user '{}'...", config.username);
-
-        // 认证策略：
-        // 1. 先尝试配置的认证方式（公钥或密码）
-        // 2. 如果返回 false（可能是 partial success，需要 MFA），尝试 keyboard-interactive
-        
-        // 步骤 1：尝试配置的认证方式
-        let auth_result = match &config.auth {
-            SshAuth::Password(password) => {
-                debug_log!("[SSH] Using password authentication (len={})", password.len());
-                match session.authenticate_password(config.username.clone(), password.clone()).await {
-                    Ok(result) => {
-                        debug_log!("[SSH] Password auth result: {:?}", result.success());
-                        result
-                    }
-                    Err(e) => {
-                        debug_log!("[SSH] ❌ Password auth error: {}", e);
-                        return Err(format!("Authentication error: {}", e).into());
-                    }
-                }
-            }
-            SshAuth::PublicKey {
-                private_key_path,
-                passphrase,
-            } => {
-                debug_log!("[SSH] Using public key: {}", private_key_path);
-                let key_pair = russh::keys::decode_secret_key(
-                    &std::fs::read_to_string(private_key_path)?,
-                    passphrase.as_deref(),
-                )?;
-                // russh 0.56 需要使用 PrivateKeyWithHashAlg
-                let key_with_alg = PrivateKeyWithHashAlg::new(
-                    Arc::new(key_pair),
-                    session.best_supported_rsa_hash().await?.flatten(),
-                );
-                match session.authenticate_publickey(config.username.clone(), key_with_alg).await {
-                    Ok(result) => {
-                        debug_log!("[SSH] Public key auth result: {:?}", result.success());
-                        result
-                    }
-                    Err(e) => {
-                        debug_log!("[SSH] ❌ Public key auth error: {}", e);
-                        return Err(format!("Authentication error: {}", e).into());
-                    }
-                }
-            }
-        };
-
-        // 步骤 2：如果首次认证返回 false，尝试 keyboard-interactive（可能是 MFA）
-        if auth_result.success() {
-            debug_log!("[SSH] ✅ Authentication successful (first method)");
-        } else {
-            debug_log!("[SSH] First auth returned false, attempting keyboard-interactive (MFA)...");
-            
-            // 直接尝试 keyboard-interactive，不使用短超时（可能导致连接问题）
-            let kbi_result = Self::perform_keyboard_interactive_auth(
-                &mut session,
-                &config.username,
-                &terminal_id,
-                &app_handle,
-                &mfa_channels,
-            ).await;
-            
-            match kbi_result {
-                Ok(true) => {
-                    debug_log!("[SSH] ✅ MFA authentication successful");
-                }
-                Ok(false) => {
-                    debug_log!("[SSH] ❌ MFA authentication rejected");
-                    return Err("SSH MFA authentication failed".into());
-                }
-                Err(e) => {
-                    debug_log!("[SSH] ❌ MFA authentication error: {}", e);
-                    return Err(format!("MFA authentication error: {}", e).into());
-                }
-            }
-        }
-
-        debug_log!("[SSH] ✅ Authenticated successfully");
-        debug_log!("[SSH] Opening session channel...");
-
-        // 打开通道并请求 PTY
-        let channel_result = session.channel_open_session().await;
-        let channel = match channel_result {
-            Ok(ch) => {
-                debug_log!("[SSH] ✅ Session channel opened");
-                ch
-            }
-            Err(e) => {
-                debug_log!("[SSH] ❌ Failed to open channel: {}", e);
-                return Err(format!("Failed to open channel: {}", e).into());
-            }
-        };
-        
-        debug_log!("[SSH] Requesting PTY (80x24)...");
-        
-        match channel
-            .request_pty(
-                false,
-                &config.username,
-                80,  // cols
-                24,  // rows
-                0,   // pixel_width
-                0,   // pixel_height
-                &[],
-            )
-            .await {
-                Ok(_) => debug_log!("[SSH] ✅ PTY allocated"),
-                Err(e) => {
-                    debug_log!("[SSH] ❌ PTY request failed: {}", e);
-                    return Err(format!("PTY request failed: {}", e).into());
-                }
-            }
-
-        debug_log!("[SSH] Requesting shell...");
-        match channel.request_shell(false).await {
-            Ok(_) => debug_log!("[SSH] ✅ Shell started"),
-            Err(e) => {
-                debug_log!("[SSH] ❌ Shell request failed: {}", e);
-                return Err(format!("Shell request failed: {}", e).into());
-            }
-        }
+        let channel = open_shell(&transport.handle, 80, 24).await?;
 
         debug_log!("[SSH] ===== SSH Session Established =====");
 
-        let handle = Arc::new(Mutex::new(session));
-        let channel_arc = Arc::new(Mutex::new(channel));
-
         let (control_tx, mut control_rx) = tokio::sync::mpsc::channel::<SshControl>(100);
+        let forwards = ForwardMap::default();
+        let forwards_clone = forwards.clone();
+        let status = StatusCell::default();
+        let status_clone = status.clone();
         let terminal_id_clone = terminal_id.clone();
         let app_clone = app_handle.clone();
-        
+
         tokio::spawn(async move {
             let mut read_count = 0;
-            // 将 channel 移入任务，由于它不是 Clone，只能由一个任务持有
-            let mut channel = match Arc::try_unwrap(channel_arc) {
-                Ok(mutex) => mutex.into_inner(),
-                Err(_) => {
-                    debug_log!("[SSH-TASK] Error: Could not unwrap channel Arc");
-                    return;
-                }
-            };
+            let mut channel = channel;
+            // 最近一次的终端大小，重连后按此大小重新请求 PTY
+            let mut size = (80u32, 24u32);
 
             loop {
-                tokio::select! {
-                    // 处理控制台指令（写数据、调大小）
-                    Some(cmd) = control_rx.recv() => {
-                        match cmd {
-                            SshControl::Write(data) => {
-                                if let Err(e) = channel.data(&data[..]).await {
-                                    debug_log!("[SSH-WRITE-TASK] Error writing: {}", e);
-                                }
-                            }
-                            SshControl::Resize { rows, cols } => {
-                                let _ = channel.window_change(cols as u32, rows as u32, 0, 0).await;
+                let end = pump(&mut channel, &mut control_rx, &mut size, &mut read_count, &terminal_id_clone, &app_clone).await;
+
+                let reconnector = match (end, &reconnector) {
+                    (ChannelEnd::Dropped, Some(reconnector)) => reconnector,
+                    (ChannelEnd::Detached, _) => break,
+                    _ => {
+                        let _ = app_clone.emit(&format!("terminal-exit-{}", terminal_id_clone), ());
+                        break;
+                    }
+                };
+
+                status_clone.set(TerminalStatus::Reconnecting);
+                match reconnect(reconnector, &mut control_rx, &mut size, &terminal_id_clone, &app_clone).await {
+                    Some(new_channel) => {
+                        channel = new_channel;
+                        status_clone.set(TerminalStatus::Connected);
+                        // 远程转发需要在新连接上重新请求监听
+                        for forward in forwards_clone.lock().await.values() {
+                            if let Err(e) = forward.resume().await {
+                                debug_log!("[SSH-RECONNECT] Failed to resume forward {}: {}", forward.id(), e);
                             }
                         }
+                        let _ = app_clone.emit(&format!("terminal-reconnected-{}", terminal_id_clone), ());
                     }
-                    // 异步读取数据
-                    msg = channel.wait() => {
-                        match msg {
-                            Some(ChannelMsg::Data { ref data }) => {
-                                read_count += 1;
-                                let output = String::from_utf8_lossy(data).to_string();
-                                debug_log!("[SSH-READ #{}] terminal={}, bytes={}, preview: {:?}",
-                                    read_count, terminal_id_clone, data.len(),
-                                    if output.len() > 50 { &output[..50] } else { &output });
-                                
-                                let _ = app_clone.emit(
-                                    &format!("terminal-output-{}", terminal_id_clone),
-                                    output,
-                                );
-                            }
-                            Some(ChannelMsg::Eof) | Some(ChannelMsg::ExitStatus { .. }) | None => {
-                                debug_log!("[SSH-READ] terminal={} - Channel closed/EOF", terminal_id_clone);
-                                let _ = app_clone.emit(&format!("terminal-exit-{}", terminal_id_clone), ());
-                                break;
-                            }
-                            _ => {}
+                    None => {
+                        if !control_rx.is_closed() {
+                            let _ = app_clone.emit(&format!("terminal-exit-{}", terminal_id_clone), ());
                         }
+                        break;
                     }
                 }
             }
+            status_clone.set(TerminalStatus::Closed);
             debug_log!("[SSH-TASK] terminal={} - Task exiting", terminal_id_clone);
         });
 
         Ok(Self {
             id: terminal_id,
-            handle,
+            transport,
             control_tx: Arc::new(control_tx),
+            forwards,
+            status,
         })
     }
 
-    pub async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// 启动端口转发（ID 为空时自动生成）
+    pub async fn add_forward(&self, mut config: PortForwardConfig) -> Result<ForwardStatus, Box<dyn std::error::Error + Send + Sync>> {
+        if config.id.is_empty() {
+            config.id = uuid::Uuid::new_v4().to_string();
+        }
+        let mut forwards = self.forwards.lock().await;
+        if forwards.contains_key(&config.id) {
+            return Err(format!("Forward {} already exists", config.id).into());
+        }
+
+        let forward = PortForward::start(config, self.transport.handle.clone(), self.transport.remote_routes.clone()).await?;
+        let status = forward.status();
+        forwards.insert(forward.id().to_string(), forward);
+        Ok(status)
+    }
+
+    /// 停止端口转发
+    pub async fn remove_forward(&self, forward_id: &str) -> bool {
+        self.forwards.lock().await.remove(forward_id).is_some()
+    }
+
+    /// 列出端口转发及其流量统计
+    pub async fn list_forwards(&self) -> Vec<ForwardStatus> {
+        self.forwards.lock().await.values().map(|f| f.status()).collect()
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for SshSession {
+    async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         debug_log!("[SSH-WRITE] id={}, bytes={}, data: {:?}",
             self.id,
             data.len(),
@@ -301,136 +163,197 @@ impl SshSession {
         Ok(())
     }
 
-    pub async fn resize(&self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn resize(&self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.control_tx.send(SshControl::Resize { rows, cols }).await?;
         Ok(())
     }
 
-    /// 执行 keyboard-interactive 认证（用于 MFA）
-    /// 
-    /// 此方法处理多轮 keyboard-interactive 交互：
-    /// 1. 启动 keyboard-interactive 认证
-    /// 2. 当收到 InfoRequest 时，向前端发送 ssh-mfa-prompt 事件
-    /// 3. 等待前端通过 submit_ssh_mfa_response 命令返回响应
-    /// 4. 将响应发送给服务器，循环直到成功或失败
-    async fn perform_keyboard_interactive_auth(
-        session: &mut client::Handle<Client>,
-        username: &str,
-        terminal_id: &str,
-        app_handle: &AppHandle,
-        mfa_channels: &MfaResponseMap,
-    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        debug_log!("[SSH-MFA] Starting keyboard-interactive authentication for user: {}", username);
-
-        // 启动 keyboard-interactive 认证
-        debug_log!("[SSH-MFA] Calling authenticate_keyboard_interactive_start...");
-        let kbi_result = session
-            .authenticate_keyboard_interactive_start(username.to_string(), None)
-            .await;
-        
-        let mut kbi_response = match kbi_result {
-            Ok(resp) => {
-                debug_log!("[SSH-MFA] authenticate_keyboard_interactive_start returned: {:?}", 
-                    match &resp {
-                        KeyboardInteractiveAuthResponse::Success => "Success",
-                        KeyboardInteractiveAuthResponse::Failure { .. } => "Failure", 
-                        KeyboardInteractiveAuthResponse::InfoRequest { .. } => "InfoRequest",
-                    });
-                resp
-            }
+    /// 停止端口转发并关闭 shell 通道，SSH 连接在没有其他使用者后断开
+    async fn close(&self) {
+        self.forwards.lock().await.clear();
+        let _ = self.control_tx.send(SshControl::Close).await;
+    }
+
+    fn status(&self) -> TerminalStatus {
+        self.status.get()
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// 在连接上打开会话通道，请求 PTY 并启动 shell
+async fn open_shell(
+    handle: &RwLock<client::Handle<Client>>,
+    cols: u32,
+    rows: u32,
+) -> Result<Channel<client::Msg>, Box<dyn std::error::Error + Send + Sync>> {
+    debug_log!("[SSH] Opening session channel...");
+
+    // 在共享连接上打开独立的会话通道并请求 PTY
+    let channel_result = handle.read().await.channel_open_session().await;
+    let channel = match channel_result {
+        Ok(ch) => {
+            debug_log!("[SSH] ✅ Session channel opened");
+            ch
+        }
+        Err(e) => {
+            debug_log!("[SSH] ❌ Failed to open channel: {}", e);
+            return Err(format!("Failed to open channel: {}", e).into());
+        }
+    };
+
+    debug_log!("[SSH] Requesting PTY ({}x{})...", cols, rows);
+
+    match channel
+        .request_pty(
+            false,
+            TERMINAL_TYPE,
+            cols,
+            rows,
+            0,   // pixel_width
+            0,   // pixel_height
+            &[],
+        )
+        .await {
+            Ok(_) => debug_log!("[SSH] ✅ PTY allocated"),
             Err(e) => {
-                debug_log!("[SSH-MFA] authenticate_keyboard_interactive_start error: {}", e);
-                return Err(e.into());
+                debug_log!("[SSH] ❌ PTY request failed: {}", e);
+                return Err(format!("PTY request failed: {}", e).into());
             }
-        };
+        }
 
-        // 处理多轮交互
-        debug_log!("[SSH-MFA] Entering response loop...");
-        loop {
-            match kbi_response {
-                KeyboardInteractiveAuthResponse::Success => {
-                    debug_log!("[SSH-MFA] Keyboard-interactive authentication succeeded");
-                    return Ok(true);
-                }
-                KeyboardInteractiveAuthResponse::Failure { .. } => {
-                    debug_log!("[SSH-MFA] Keyboard-interactive authentication failed");
-                    return Ok(false);
-                }
-                KeyboardInteractiveAuthResponse::InfoRequest { name, instructions, prompts } => {
-                    debug_log!("[SSH-MFA] Received InfoRequest: name='{}', instructions='{}', prompts={}", 
-                        name, instructions, prompts.len());
-
-                    // 如果没有提示，发送空响应继续
-                    if prompts.is_empty() {
-                        debug_log!("[SSH-MFA] Empty prompts, sending empty response");
-                        kbi_response = session
-                            .authenticate_keyboard_interactive_respond(vec![])
-                            .await?;
-                        continue;
-                    }
+    debug_log!("[SSH] Requesting shell...");
+    match channel.request_shell(false).await {
+        Ok(_) => debug_log!("[SSH] ✅ Shell started"),
+        Err(e) => {
+            debug_log!("[SSH] ❌ Shell request failed: {}", e);
+            return Err(format!("Shell request failed: {}", e).into());
+        }
+    }
 
-                    // 创建用于等待前端响应的 oneshot 通道
-                    let (tx, rx) = tokio::sync::oneshot::channel::<Vec<String>>();
-                    
-                    // 将发送端存入全局 map
-                    {
-                        let mut channels = mfa_channels.lock().await;
-                        channels.insert(terminal_id.to_string(), tx);
-                    }
+    Ok(channel)
+}
 
-                    // 构建 MFA 提示 payload
-                    let mfa_prompts: Vec<MfaPrompt> = prompts
-                        .iter()
-                        .map(|p| MfaPrompt {
-                            prompt: p.prompt.clone(),
-                            echo: p.echo,
-                        })
-                        .collect();
-
-                    let payload = MfaPromptPayload {
-                        terminal_id: terminal_id.to_string(),
-                        name: name.clone(),
-                        instructions: instructions.clone(),
-                        prompts: mfa_prompts,
-                    };
-
-                    // 向前端发送 MFA 提示事件
-                    debug_log!("[SSH-MFA] Emitting ssh-mfa-prompt event to frontend");
-                    app_handle.emit("ssh-mfa-prompt", &payload)?;
-
-                    // 等待前端响应（带超时）
-                    debug_log!("[SSH-MFA] Waiting for frontend MFA response...");
-                    let responses = tokio::time::timeout(
-                        std::time::Duration::from_secs(120), // 2 分钟超时
-                        rx
-                    ).await;
-
-                    let responses = match responses {
-                        Ok(Ok(r)) => {
-                            debug_log!("[SSH-MFA] Received {} responses from frontend", r.len());
-                            r
-                        }
-                        Ok(Err(_)) => {
-                            // 通道被关闭（用户取消）
-                            debug_log!("[SSH-MFA] MFA cancelled by user");
-                            return Err("MFA authentication cancelled by user".into());
-                        }
-                        Err(_) => {
-                            // 超时
-                            debug_log!("[SSH-MFA] MFA response timeout");
-                            // 清理通道
-                            mfa_channels.lock().await.remove(terminal_id);
-                            return Err("MFA authentication timeout".into());
+/// 在通道与前端之间转发数据，直到通道结束
+async fn pump(
+    channel: &mut Channel<client::Msg>,
+    control_rx: &mut tokio::sync::mpsc::Receiver<SshControl>,
+    size: &mut (u32, u32),
+    read_count: &mut u64,
+    terminal_id: &str,
+    app_handle: &AppHandle,
+) -> ChannelEnd {
+    loop {
+        tokio::select! {
+            // 处理控制台指令（写数据、调大小）
+            Some(cmd) = control_rx.recv() => {
+                match cmd {
+                    SshControl::Write(data) => {
+                        if let Err(e) = channel.data(&data[..]).await {
+                            debug_log!("[SSH-WRITE-TASK] Error writing: {}", e);
                         }
-                    };
+                    }
+                    SshControl::Resize { rows, cols } => {
+                        *size = (cols as u32, rows as u32);
+                        let _ = channel.window_change(cols as u32, rows as u32, 0, 0).await;
+                    }
+                    SshControl::Close => {
+                        let _ = channel.close().await;
+                        return ChannelEnd::Detached;
+                    }
+                }
+            }
+            // 异步读取数据
+            msg = channel.wait() => {
+                match msg {
+                    Some(ChannelMsg::Data { ref data }) => {
+                        *read_count += 1;
+                        let output = String::from_utf8_lossy(data).to_string();
+                        debug_log!("[SSH-READ #{}] terminal={}, bytes={}, preview: {:?}",
+                            read_count, terminal_id, data.len(),
+                            if output.len() > 50 { &output[..50] } else { &output });
+
+                        let _ = app_handle.emit(
+                            &format!("terminal-output-{}", terminal_id),
+                            output,
+                        );
+                    }
+                    Some(ChannelMsg::Eof) | Some(ChannelMsg::ExitStatus { .. }) => {
+                        debug_log!("[SSH-READ] terminal={} - Channel EOF", terminal_id);
+                        return ChannelEnd::Exited;
+                    }
+                    None => {
+                        debug_log!("[SSH-READ] terminal={} - Channel closed", terminal_id);
+                        // 会话关闭时连接随之断开，不应重连
+                        return if control_rx.is_closed() { ChannelEnd::Detached } else { ChannelEnd::Dropped };
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
 
-                    // 发送响应给服务器
-                    debug_log!("[SSH-MFA] Sending responses to server");
-                    kbi_response = session
-                        .authenticate_keyboard_interactive_respond(responses)
-                        .await?;
+/// 按重连策略重建连接并重新打开 shell，重试用尽或会话已关闭时返回 None
+async fn reconnect(
+    reconnector: &Reconnector,
+    control_rx: &mut tokio::sync::mpsc::Receiver<SshControl>,
+    size: &mut (u32, u32),
+    terminal_id: &str,
+    app_handle: &AppHandle,
+) -> Option<Channel<client::Msg>> {
+    let max_attempts = reconnector.max_attempts();
+    let mut last_error = None;
+
+    for attempt in 1..=max_attempts {
+        let delay = reconnector.delay(attempt);
+        debug_log!("[SSH-RECONNECT] terminal={} - attempt {}/{} in {:?}", terminal_id, attempt, max_attempts, delay);
+        let _ = app_handle.emit(
+            &format!("terminal-reconnecting-{}", terminal_id),
+            ReconnectingPayload {
+                attempt,
+                max_attempts,
+                delay_ms: delay.as_millis() as u64,
+                last_error: last_error.take(),
+            },
+        );
+
+        // 等待期间丢弃输入，但记录终端大小变化
+        let sleep = tokio::time::sleep(delay);
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                cmd = control_rx.recv() => match cmd {
+                    Some(SshControl::Resize { rows, cols }) => *size = (cols as u32, rows as u32),
+                    Some(SshControl::Write(_)) => {}
+                    Some(SshControl::Close) => {
+                        // 关闭接收端，调用方据此判断会话已被关闭
+                        control_rx.close();
+                        return None;
+                    }
+                    None => return None,
                 }
             }
         }
+
+        let result = match reconnector.reconnect(terminal_id).await {
+            Ok(transport) => open_shell(&transport.handle, size.0, size.1).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(channel) => {
+                debug_log!("[SSH-RECONNECT] terminal={} - ✅ Reconnected", terminal_id);
+                return Some(channel);
+            }
+            Err(e) => {
+                debug_log!("[SSH-RECONNECT] terminal={} - ❌ Attempt {} failed: {}", terminal_id, attempt, e);
+                last_error = Some(e.to_string());
+            }
+        }
     }
+
+    None
 }
@@ -0,0 +1,187 @@
+use std::io::{Error, ErrorKind};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// SOCKS5 协议常量（RFC 1928）
+const VERSION: u8 = 0x05;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_NONE_ACCEPTABLE: u8 = 0xFF;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+pub const REP_SUCCEEDED: u8 = 0x00;
+pub const REP_HOST_UNREACHABLE: u8 = 0x04;
+const REP_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const REP_ADDRESS_NOT_SUPPORTED: u8 = 0x08;
+
+/// 完成 SOCKS5 握手（仅支持无认证 + CONNECT），返回客户端请求的目标地址
+///
+/// 成功后调用方需在打开 SSH 通道后通过 `reply` 告知客户端结果。
+pub async fn handshake<S>(stream: &mut S) -> std::io::Result<(String, u16)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // 协商认证方式：VER NMETHODS METHODS...
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    if header[0] != VERSION {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Unsupported SOCKS version {}", header[0])));
+    }
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&METHOD_NO_AUTH) {
+        stream.write_all(&[VERSION, METHOD_NONE_ACCEPTABLE]).await?;
+        return Err(Error::new(ErrorKind::PermissionDenied, "SOCKS client requires authentication"));
+    }
+    stream.write_all(&[VERSION, METHOD_NO_AUTH]).await?;
+
+    // 请求：VER CMD RSV ATYP DST.ADDR DST.PORT
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    if request[0] != VERSION {
+        return Err(Error::new(ErrorKind::InvalidData, "Malformed SOCKS request"));
+    }
+
+    let host = match request[3] {
+        ATYP_IPV4 => {
+            let mut addr = [0u8; 4];
+            stream.read_exact(&mut addr).await?;
+            Ipv4Addr::from(addr).to_string()
+        }
+        ATYP_IPV6 => {
+            let mut addr = [0u8; 16];
+            stream.read_exact(&mut addr).await?;
+            Ipv6Addr::from(addr).to_string()
+        }
+        ATYP_DOMAIN => {
+            let len = stream.read_u8().await?;
+            let mut domain = vec![0u8; len as usize];
+            stream.read_exact(&mut domain).await?;
+            String::from_utf8(domain)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid SOCKS domain name"))?
+        }
+        other => {
+            reply(stream, REP_ADDRESS_NOT_SUPPORTED).await?;
+            return Err(Error::new(ErrorKind::InvalidData, format!("Unsupported SOCKS address type {}", other)));
+        }
+    };
+    let port = stream.read_u16().await?;
+
+    if request[1] != CMD_CONNECT {
+        reply(stream, REP_COMMAND_NOT_SUPPORTED).await?;
+        return Err(Error::new(ErrorKind::Unsupported, format!("Unsupported SOCKS command {}", request[1])));
+    }
+
+    Ok((host, port))
+}
+
+/// 发送 SOCKS5 应答，绑定地址固定为 0.0.0.0:0（通道另一端的地址对客户端无意义）
+pub async fn reply<S>(stream: &mut S, rep: u8) -> std::io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream.write_all(&[VERSION, rep, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_AUTH_GREETING: [u8; 4] = [VERSION, 2, 0x02, METHOD_NO_AUTH];
+    const CONNECT: [u8; 3] = [VERSION, CMD_CONNECT, 0x00];
+
+    /// 客户端一次写入 `input` 后关闭写端，返回握手结果与服务端发回的全部字节
+    async fn run(input: &[u8]) -> (std::io::Result<(String, u16)>, Vec<u8>) {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        client.write_all(input).await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let result = handshake(&mut server).await;
+        drop(server);
+        let mut output = Vec::new();
+        client.read_to_end(&mut output).await.unwrap();
+        (result, output)
+    }
+
+    fn request(atyp: u8, addr: &[u8], port: u16) -> Vec<u8> {
+        [&NO_AUTH_GREETING[..], &CONNECT, &[atyp], addr, &port.to_be_bytes()].concat()
+    }
+
+    #[tokio::test]
+    async fn negotiates_no_auth_and_reads_ipv4_target() {
+        let (result, output) = run(&request(ATYP_IPV4, &[10, 0, 0, 1], 8080)).await;
+        assert_eq!(result.unwrap(), ("10.0.0.1".to_string(), 8080));
+        // 只回复方法选择，连接结果由调用方在打开通道后发送
+        assert_eq!(output, [VERSION, METHOD_NO_AUTH]);
+    }
+
+    #[tokio::test]
+    async fn reads_ipv6_and_domain_targets() {
+        let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let (result, _) = run(&request(ATYP_IPV6, &v6.octets(), 22)).await;
+        assert_eq!(result.unwrap(), ("2001:db8::1".to_string(), 22));
+
+        let domain = b"example.com";
+        let addr = [&[domain.len() as u8][..], domain].concat();
+        let (result, _) = run(&request(ATYP_DOMAIN, &addr, 443)).await;
+        assert_eq!(result.unwrap(), ("example.com".to_string(), 443));
+
+        let (result, _) = run(&request(ATYP_DOMAIN, &[2, 0xFF, 0xFE], 443)).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn rejects_clients_that_require_authentication() {
+        let (result, output) = run(&[VERSION, 1, 0x02]).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::PermissionDenied);
+        assert_eq!(output, [VERSION, METHOD_NONE_ACCEPTABLE]);
+    }
+
+    #[tokio::test]
+    async fn rejects_other_socks_versions() {
+        let (result, output) = run(&[0x04, 0x01, 0x00, 0x50]).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert!(output.is_empty());
+
+        let input = [&NO_AUTH_GREETING[..], &[0x04, CMD_CONNECT, 0x00, ATYP_IPV4]].concat();
+        let (result, _) = run(&input).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn unsupported_command_is_answered() {
+        let input = [&NO_AUTH_GREETING[..], &[VERSION, 0x02, 0x00, ATYP_IPV4, 10, 0, 0, 1, 0, 80]].concat();
+        let (result, output) = run(&input).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::Unsupported);
+        assert_eq!(output, [VERSION, METHOD_NO_AUTH, VERSION, REP_COMMAND_NOT_SUPPORTED, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn unsupported_address_type_is_answered() {
+        let (result, output) = run(&request(0x05, &[], 80)).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(output, [VERSION, METHOD_NO_AUTH, VERSION, REP_ADDRESS_NOT_SUPPORTED, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn truncated_requests_fail_with_eof() {
+        let full = request(ATYP_IPV4, &[10, 0, 0, 1], 8080);
+        let domain = request(ATYP_DOMAIN, &[11, b'e', b'x'], 443);
+        for input in [&full[..1], &full[..3], &full[..6], &full[..10], &full[..full.len() - 1], &domain[..domain.len() - 2]] {
+            let (result, _) = run(input).await;
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof, "input {:?}", input);
+        }
+    }
+
+    #[tokio::test]
+    async fn reply_uses_unspecified_bind_address() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        reply(&mut server, REP_HOST_UNREACHABLE).await.unwrap();
+        drop(server);
+        let mut output = Vec::new();
+        client.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, [VERSION, REP_HOST_UNREACHABLE, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::models::Connection;
+use crate::telnet::TelnetSession;
+use crate::terminal::TerminalRegistry;
+use tauri::{AppHandle, State};
+use std::sync::Arc;
+
+/// 创建 Telnet 终端会话，之后通过通用终端命令读写
+#[tauri::command]
+pub async fn create_telnet_terminal(
+    config: Connection,
+    terminal_id: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    sessions: State<'_, TerminalRegistry>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    debug_log!("[CMD] create_telnet_terminal called for connection: {}", config.name);
+
+    let terminal_id = terminal_id.unwrap_or_else(|| config.id.clone());
+
+    // 检查是否已存在
+    if sessions.lock().await.contains_key(&terminal_id) {
+        debug_log!("[CMD] Telnet terminal {} already exists, skipping creation", terminal_id);
+        return Ok(());
+    }
+
+    let telnet_config = config.telnet_config
+        .ok_or("Telnet config is required for Telnet connection")?;
+
+    // 建立 TCP 连接是阻塞操作，放到阻塞线程池中执行
+    let id = terminal_id.clone();
+    let session = tokio::task::spawn_blocking(move || {
+        TelnetSession::new(telnet_config, id, cols.unwrap_or(80), rows.unwrap_or(24), app_handle)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Failed to create Telnet session: {}", e))?
+    .map_err(|e| format!("Failed to create Telnet session: {}", e))?;
+
+    sessions.lock().await.insert(terminal_id.clone(), Arc::new(session));
+    debug_log!("[CMD] create_telnet_terminal completed successfully for id: {}", terminal_id);
+    Ok(())
+}
@@ -0,0 +1,417 @@
+use crate::models::TelnetConfig;
+use crate::terminal::{StatusCell, TerminalBackend, TerminalStatus};
+use async_trait::async_trait;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use telnet::{Action, Event, Stream, Telnet, TelnetError, TelnetOption};
+
+/// 读取超时，超时后处理一次前端的写入与调整大小指令
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// TTYPE 子协商：服务器请求终端类型（SEND）与客户端回复（IS）
+const TTYPE_IS: u8 = 0;
+const TTYPE_SEND: u8 = 1;
+
+#[derive(Debug)]
+enum TelnetControl {
+    Write(Vec<u8>),
+    Resize { rows: u16, cols: u16 },
+    Close,
+}
+
+pub struct TelnetSession {
+    #[allow(dead_code)]
+    id: String,
+    /// 发送端被释放后读写线程随之退出并断开连接
+    control_tx: Sender<TelnetControl>,
+    status: StatusCell,
+}
+
+impl TelnetSession {
+    /// 建立 Telnet 连接并启动读写线程（阻塞，需在阻塞线程中调用）
+    pub fn new(
+        config: TelnetConfig,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+        app_handle: AppHandle,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        debug_log!("[TELNET] Connecting to {}:{}", config.host, config.port);
+
+        let stream = connect(&config)?;
+
+        debug_log!("[TELNET] ✅ Connected to {}:{}", config.host, config.port);
+
+        let (control_tx, control_rx) = mpsc::channel::<TelnetControl>();
+        let status = StatusCell::default();
+        let status_clone = status.clone();
+        let terminal_id_clone = terminal_id.clone();
+
+        // Telnet 不是 Send，只能在读写线程内创建
+        std::thread::spawn(move || {
+            let stream = EofStream::new(stream);
+            let eof = stream.eof.clone();
+            let mut telnet = Telnet::from_stream(Box::new(stream), 4096);
+            let mut negotiator = Negotiator::new(config.terminal_type, cols, rows);
+            let result = match negotiator.start(&mut telnet) {
+                Ok(()) => run(&mut telnet, &eof, &mut negotiator, &control_rx, &terminal_id_clone, &app_handle),
+                Err(_) => Err("Initial negotiation failed"),
+            };
+            // 会话已被关闭时不再通知前端
+            if let Err(reason) = result {
+                debug_log!("[TELNET-TASK] terminal={} - {}", terminal_id_clone, reason);
+                let _ = app_handle.emit(&format!("terminal-exit-{}", terminal_id_clone), ());
+            }
+            status_clone.set(TerminalStatus::Closed);
+            debug_log!("[TELNET-TASK] terminal={} - Thread exiting", terminal_id_clone);
+        });
+
+        Ok(Self {
+            id: terminal_id,
+            control_tx,
+            status,
+        })
+    }
+}
+
+#[async_trait]
+impl TerminalBackend for TelnetSession {
+    async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.control_tx
+            .send(TelnetControl::Write(data.to_vec()))
+            .map_err(|_| "Telnet connection closed")?;
+        Ok(())
+    }
+
+    async fn resize(&self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.control_tx
+            .send(TelnetControl::Resize { rows, cols })
+            .map_err(|_| "Telnet connection closed")?;
+        Ok(())
+    }
+
+    async fn close(&self) {
+        let _ = self.control_tx.send(TelnetControl::Close);
+    }
+
+    fn status(&self) -> TerminalStatus {
+        self.status.get()
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// 依次尝试每个解析出的地址
+fn connect(config: &TelnetConfig) -> Result<TcpStream, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = (config.host.as_str(), config.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("DNS resolution of {} failed: {}", config.host, e))?
+        .collect();
+
+    let mut last_error = format!("{} did not resolve to any address", config.host);
+    for addr in addrs {
+        let result = match config.connect_timeout_secs {
+            0 => TcpStream::connect(addr),
+            secs => TcpStream::connect_timeout(&addr, Duration::from_secs(secs)),
+        };
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("Connection to {} failed: {}", addr, e),
+        }
+    }
+    Err(last_error.into())
+}
+
+/// 记录是否读到 EOF 的 TCP 连接
+///
+/// telnet 库在一次读取没有产生事件时返回 `InternalQueueErr`，读到 EOF 与 IAC 序列被拆分到两次读取中
+/// 都是这种情况，只能由底层读取是否返回 0 区分。
+struct EofStream {
+    inner: TcpStream,
+    eof: Rc<Cell<bool>>,
+}
+
+impl EofStream {
+    fn new(inner: TcpStream) -> Self {
+        Self { inner, eof: Rc::new(Cell::new(false)) }
+    }
+}
+
+impl Read for EofStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            self.eof.set(true);
+        }
+        Ok(n)
+    }
+}
+
+impl Write for EofStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Stream for EofStream {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(dur)
+    }
+}
+
+/// 读写循环，直到会话被关闭（Ok）或连接结束（Err 为原因）
+fn run(
+    telnet: &mut Telnet,
+    eof: &Cell<bool>,
+    negotiator: &mut Negotiator,
+    control_rx: &Receiver<TelnetControl>,
+    terminal_id: &str,
+    app_handle: &AppHandle,
+) -> Result<(), &'static str> {
+    loop {
+        // 处理前端指令（写数据、调大小）
+        loop {
+            match control_rx.try_recv() {
+                Ok(TelnetControl::Write(data)) => {
+                    if let Err(e) = telnet.write(&encode_input(&data)) {
+                        debug_log!("[TELNET-WRITE] Error writing: {}", e);
+                        return Err("Write failed");
+                    }
+                }
+                Ok(TelnetControl::Resize { rows, cols }) => {
+                    if let Err(e) = negotiator.resize(telnet, cols, rows) {
+                        debug_log!("[TELNET] Failed to send window size: {}", e);
+                    }
+                }
+                Ok(TelnetControl::Close) | Err(TryRecvError::Disconnected) => return Ok(()),
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        let event = match telnet.read_timeout(POLL_INTERVAL) {
+            Ok(event) => event,
+            Err(e) => {
+                debug_log!("[TELNET-READ] terminal={} - Read error: {}", terminal_id, e);
+                return Err("Read failed");
+            }
+        };
+
+        match event {
+            Event::Data(data) => {
+                let output = String::from_utf8_lossy(&data).to_string();
+                let _ = app_handle.emit(&format!("terminal-output-{}", terminal_id), output);
+            }
+            Event::Negotiation(action, option) => {
+                if let Err(e) = negotiator.negotiate(telnet, action, option) {
+                    debug_log!("[TELNET] Negotiation failed: {}", e);
+                }
+            }
+            Event::Subnegotiation(option, data) => {
+                if let Err(e) = negotiator.subnegotiate(telnet, option, &data) {
+                    debug_log!("[TELNET] Subnegotiation failed: {}", e);
+                }
+            }
+            // 本次读取没有产生事件：读到 EOF，或 IAC 序列不完整需要继续读取
+            Event::Error(TelnetError::InternalQueueErr) if eof.get() => return Err("Connection closed by remote host"),
+            Event::Error(TelnetError::InternalQueueErr) => {}
+            Event::Error(e) => debug_log!("[TELNET-READ] terminal={} - Protocol error: {}", terminal_id, e),
+            Event::TimedOut | Event::NoData | Event::UnknownIAC(_) => {}
+        }
+    }
+}
+
+/// NVT 规定单独的 CR 后必须跟 NUL（回车键只发送 \r）
+fn encode_input(data: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(data.len());
+    for (i, &byte) in data.iter().enumerate() {
+        encoded.push(byte);
+        if byte == b'\r' && data.get(i + 1) != Some(&b'\n') {
+            encoded.push(0);
+        }
+    }
+    encoded
+}
+
+/// 选项协商状态
+///
+/// 本端支持 SGA、NAWS、TTYPE，接受服务器端的 ECHO、SGA，其余选项一律拒绝。
+/// 只在状态变化时回复，避免与服务器之间的协商死循环。
+struct Negotiator {
+    terminal_type: String,
+    cols: u16,
+    rows: u16,
+    /// 已启用的本端选项（WILL）
+    local: HashSet<TelnetOption>,
+    /// 已启用的服务器端选项（DO）
+    remote: HashSet<TelnetOption>,
+    /// 已主动请求、等待服务器确认的选项
+    pending_local: HashSet<TelnetOption>,
+    pending_remote: HashSet<TelnetOption>,
+}
+
+impl Negotiator {
+    fn new(terminal_type: String, cols: u16, rows: u16) -> Self {
+        Self {
+            terminal_type,
+            cols,
+            rows,
+            local: HashSet::new(),
+            remote: HashSet::new(),
+            pending_local: HashSet::new(),
+            pending_remote: HashSet::new(),
+        }
+    }
+
+    fn supports_local(option: TelnetOption) -> bool {
+        matches!(option, TelnetOption::SuppressGoAhead | TelnetOption::NAWS | TelnetOption::TTYPE)
+    }
+
+    fn supports_remote(option: TelnetOption) -> bool {
+        matches!(option, TelnetOption::Echo | TelnetOption::SuppressGoAhead)
+    }
+
+    /// 连接建立后主动发起协商
+    fn start(&mut self, telnet: &mut Telnet) -> Result<(), TelnetError> {
+        for option in [TelnetOption::NAWS, TelnetOption::TTYPE] {
+            telnet.negotiate(&Action::Will, option)?;
+            self.pending_local.insert(option);
+        }
+        telnet.negotiate(&Action::Do, TelnetOption::SuppressGoAhead)?;
+        self.pending_remote.insert(TelnetOption::SuppressGoAhead);
+        Ok(())
+    }
+
+    fn negotiate(&mut self, telnet: &mut Telnet, action: Action, option: TelnetOption) -> Result<(), TelnetError> {
+        debug_log!("[TELNET] Received {:?} {:?}", action, option);
+        match action {
+            Action::Do if Self::supports_local(option) => {
+                let requested = self.pending_local.remove(&option);
+                if self.local.insert(option) {
+                    if !requested {
+                        telnet.negotiate(&Action::Will, option)?;
+                    }
+                    if option == TelnetOption::NAWS {
+                        self.send_window_size(telnet)?;
+                    }
+                }
+            }
+            Action::Do => telnet.negotiate(&Action::Wont, option)?,
+            Action::Dont => {
+                let requested = self.pending_local.remove(&option);
+                if self.local.remove(&option) && !requested {
+                    telnet.negotiate(&Action::Wont, option)?;
+                }
+            }
+            Action::Will if Self::supports_remote(option) => {
+                let requested = self.pending_remote.remove(&option);
+                if self.remote.insert(option) && !requested {
+                    telnet.negotiate(&Action::Do, option)?;
+                }
+            }
+            Action::Will => telnet.negotiate(&Action::Dont, option)?,
+            Action::Wont => {
+                let requested = self.pending_remote.remove(&option);
+                if self.remote.remove(&option) && !requested {
+                    telnet.negotiate(&Action::Dont, option)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn subnegotiate(&mut self, telnet: &mut Telnet, option: TelnetOption, data: &[u8]) -> Result<(), TelnetError> {
+        if option == TelnetOption::TTYPE && data.first() == Some(&TTYPE_SEND) {
+            let mut reply = vec![TTYPE_IS];
+            reply.extend_from_slice(self.terminal_type.to_uppercase().as_bytes());
+            telnet.subnegotiate(TelnetOption::TTYPE, &reply)?;
+        }
+        Ok(())
+    }
+
+    fn resize(&mut self, telnet: &mut Telnet, cols: u16, rows: u16) -> Result<(), TelnetError> {
+        self.cols = cols;
+        self.rows = rows;
+        if self.local.contains(&TelnetOption::NAWS) {
+            self.send_window_size(telnet)?;
+        }
+        Ok(())
+    }
+
+    /// 发送 NAWS 子协商（宽、高各两字节，数据中的 IAC 需要转义）
+    fn send_window_size(&self, telnet: &mut Telnet) -> Result<(), TelnetError> {
+        let mut data = Vec::with_capacity(8);
+        for byte in [self.cols.to_be_bytes(), self.rows.to_be_bytes()].concat() {
+            data.push(byte);
+            if byte == 0xFF {
+                data.push(0xFF);
+            }
+        }
+        telnet.subnegotiate(TelnetOption::NAWS, &data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn split_iac_is_not_eof() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            // IAC WILL ECHO 拆分到两个 TCP 段中
+            socket.write_all(&[255]).unwrap();
+            socket.flush().unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            socket.write_all(&[251, 1]).unwrap();
+            socket.write_all(b"hello").unwrap();
+        });
+
+        let stream = EofStream::new(TcpStream::connect(addr).unwrap());
+        let eof = stream.eof.clone();
+        let mut telnet = Telnet::from_stream(Box::new(stream), 64);
+
+        let mut split = false;
+        let mut data = Vec::new();
+        loop {
+            match telnet.read_timeout(Duration::from_secs(5)).unwrap() {
+                Event::Error(TelnetError::InternalQueueErr) if eof.get() => break,
+                Event::Error(TelnetError::InternalQueueErr) => split = true,
+                Event::Data(bytes) => data.extend_from_slice(&bytes),
+                Event::TimedOut => panic!("server did not close the connection"),
+                _ => {}
+            }
+        }
+        server.join().unwrap();
+
+        // 只有单独的 IAC 时没有事件，连接保持；后续数据照常读到
+        assert!(split);
+        assert!(data.ends_with(b"hello"));
+    }
+
+    #[test]
+    fn encode_input_pads_bare_cr() {
+        assert_eq!(encode_input(b"ls\r"), b"ls\r\0");
+        assert_eq!(encode_input(b"a\r\nb"), b"a\r\nb");
+    }
+}
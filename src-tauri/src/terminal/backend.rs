@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::any::Any;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// 终端会话状态（terminal_status 命令返回）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TerminalStatus {
+    Connected,
+    /// 连接断开，正在按重连策略重连（仅 SSH）
+    Reconnecting,
+    /// 连接已结束或会话已关闭
+    Closed,
+}
+
+/// 在会话对象与其读写线程之间共享的状态
+#[derive(Debug, Clone, Default)]
+pub struct StatusCell(Arc<AtomicU8>);
+
+impl StatusCell {
+    pub fn get(&self) -> TerminalStatus {
+        match self.0.load(Ordering::Relaxed) {
+            0 => TerminalStatus::Connected,
+            1 => TerminalStatus::Reconnecting,
+            _ => TerminalStatus::Closed,
+        }
+    }
+
+    pub fn set(&self, status: TerminalStatus) {
+        let value = match status {
+            TerminalStatus::Connected => 0,
+            TerminalStatus::Reconnecting => 1,
+            TerminalStatus::Closed => 2,
+        };
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+/// 终端后端，本地 PTY、SSH、Telnet、串口会话均实现此 trait
+///
+/// 输出与退出通过 terminal-output-{id} / terminal-exit-{id} 事件发送到前端，
+/// 新的传输协议只需实现此 trait 并注册到 TerminalRegistry。
+#[async_trait]
+pub trait TerminalBackend: Send + Sync + 'static {
+    /// 写入用户输入
+    async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 调整终端大小，不支持窗口大小的传输（如串口）忽略即可
+    async fn resize(&self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 主动关闭会话，关闭后不再发送 terminal-exit 事件
+    async fn close(&self);
+
+    fn status(&self) -> TerminalStatus;
+
+    /// 用于取回具体的会话类型（如 SSH 端口转发、串口 BREAK）
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
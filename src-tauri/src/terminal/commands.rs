@@ -1,17 +1,45 @@
 use tauri::{AppHandle, State};
-use super::pty_manager::{PtyConfig, PtySession, SessionMap};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use super::backend::{TerminalBackend, TerminalStatus};
+use super::pty_manager::{PtyConfig, PtySession};
+
+/// 所有终端会话（本地、SSH、Telnet、串口）共用的注册表，按终端 ID 索引
+pub type TerminalRegistry = Arc<Mutex<HashMap<String, Arc<dyn TerminalBackend>>>>;
+
+/// 创建终端会话注册表
+pub fn create_terminal_registry() -> TerminalRegistry {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// 取出终端会话，随即释放注册表锁
+pub async fn lookup(registry: &TerminalRegistry, id: &str) -> Result<Arc<dyn TerminalBackend>, String> {
+    registry.lock().await
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("Session {} not found", id))
+}
+
+/// 取出指定类型的终端会话，类型不符时返回错误
+pub async fn lookup_as<T: TerminalBackend>(registry: &TerminalRegistry, id: &str) -> Result<Arc<T>, String> {
+    lookup(registry, id).await?
+        .into_any()
+        .downcast::<T>()
+        .map_err(|_| format!("Session {} does not support this operation", id))
+}
 
 #[tauri::command]
 pub async fn create_terminal(
     config: PtyConfig,
-    sessions: State<'_, SessionMap>,
+    sessions: State<'_, TerminalRegistry>,
     app_handle: AppHandle,
 ) -> Result<(), String> {
     debug_log!("[CMD] create_terminal called with config: {:?}", config);
     
     // 防止重复创建：如果该 ID 已存在，直接返回成功
     {
-        let sessions_guard = sessions.lock().unwrap();
+        let sessions_guard = sessions.lock().await;
         if sessions_guard.contains_key(&config.id) {
             debug_log!("[CMD] Terminal {} already exists, skipping creation", config.id);
             return Ok(());
@@ -25,45 +53,57 @@ pub async fn create_terminal(
             err_msg
         })?;
     
-    sessions.lock().unwrap().insert(config.id.clone(), session);
+    sessions.lock().await.insert(config.id.clone(), Arc::new(session));
     debug_log!("[CMD] create_terminal completed successfully for id: {}", config.id);
     Ok(())
 }
 
+/// 写入用户输入（所有终端类型通用）
 #[tauri::command]
 pub async fn write_to_terminal(
     id: String,
     data: String,
-    sessions: State<'_, SessionMap>,
+    sessions: State<'_, TerminalRegistry>,
 ) -> Result<(), String> {
-    let sessions = sessions.lock().unwrap();
-    if let Some(session) = sessions.get(&id) {
-        session.write(data.as_bytes())
-            .map_err(|e| format!("Write failed: {}", e))?;
-    }
-    Ok(())
+    let session = lookup(&sessions, &id).await?;
+    session.write(data.as_bytes()).await
+        .map_err(|e| format!("Write failed: {}", e))
 }
 
+/// 调整终端大小（所有终端类型通用）
 #[tauri::command]
 pub async fn resize_terminal(
     id: String,
     rows: u16,
     cols: u16,
-    sessions: State<'_, SessionMap>,
+    sessions: State<'_, TerminalRegistry>,
 ) -> Result<(), String> {
-    let mut sessions = sessions.lock().unwrap();
-    if let Some(session) = sessions.get_mut(&id) {
-        session.resize(rows, cols)
-            .map_err(|e| format!("Resize failed: {}", e))?;
-    }
-    Ok(())
+    let session = lookup(&sessions, &id).await?;
+    session.resize(rows, cols).await
+        .map_err(|e| format!("Resize failed: {}", e))
 }
 
+/// 关闭终端会话（所有终端类型通用）
 #[tauri::command]
 pub async fn close_terminal(
     id: String,
-    sessions: State<'_, SessionMap>,
+    sessions: State<'_, TerminalRegistry>,
 ) -> Result<(), String> {
-    sessions.lock().unwrap().remove(&id);
+    let session = sessions.lock().await.remove(&id);
+    if let Some(session) = session {
+        session.close().await;
+    }
     Ok(())
 }
+
+/// 查询终端会话状态，会话不存在时视为已关闭
+#[tauri::command]
+pub async fn terminal_status(
+    id: String,
+    sessions: State<'_, TerminalRegistry>,
+) -> Result<TerminalStatus, String> {
+    Ok(sessions.lock().await
+        .get(&id)
+        .map(|session| session.status())
+        .unwrap_or(TerminalStatus::Closed))
+}
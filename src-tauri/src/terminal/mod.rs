@@ -1,5 +1,7 @@
 pub mod pty_manager;
+pub mod backend;
 pub mod commands;
 
 // 只导出需要在 setup 中使用的函数和类型
-pub use pty_manager::create_session_map;
+pub use backend::{StatusCell, TerminalBackend, TerminalStatus};
+pub use commands::{create_terminal_registry, TerminalRegistry};
@@ -1,7 +1,9 @@
+use crate::terminal::{StatusCell, TerminalBackend, TerminalStatus};
+use async_trait::async_trait;
 use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
+use std::any::Any;
 use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
 use tauri::{AppHandle, Emitter};
 use serde::{Deserialize, Serialize};
 
@@ -17,11 +19,12 @@ pub struct PtySession {
     id: String,
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
     pair: Arc<Mutex<PtyPair>>,  // 保留完整的 pair，用于 resize
-    _child: Box<dyn portable_pty::Child + Send>,
+    child: Mutex<Box<dyn portable_pty::Child + Send + Sync>>,
+    status: StatusCell,
 }
 
 impl PtySession {
-    pub fn new(config: PtyConfig, app_handle: AppHandle) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(config: PtyConfig, app_handle: AppHandle) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         debug_log!("[PTY] Creating new session: id={}, shell={}, cols={}, rows={}", 
             config.id, config.shell, config.cols, config.rows);
         
@@ -68,6 +71,8 @@ impl PtySession {
 
         let terminal_id = config.id.clone();
         let app = app_handle.clone();
+        let status = StatusCell::default();
+        let status_clone = status.clone();
 
         debug_log!("[PTY] Starting reader thread for terminal: {}", terminal_id);
         
@@ -83,7 +88,6 @@ impl PtySession {
                 match reader.read(&mut buf) {
                     Ok(0) => {
                         debug_log!("[PTY-READ] terminal={} - Read 0 bytes, PTY closed", terminal_id);
-                        let _ = app.emit(&format!("terminal-exit-{}", terminal_id), ());
                         break;
                     }
                     Ok(n) => {
@@ -96,11 +100,15 @@ impl PtySession {
                     }
                     Err(e) => {
                         debug_log!("[PTY-READ] terminal={} - Read error: {}", terminal_id, e);
-                        let _ = app.emit(&format!("terminal-exit-{}", terminal_id), ());
                         break;
                     }
                 }
             }
+            // 会话已被关闭时不再通知前端
+            if status_clone.get() != TerminalStatus::Closed {
+                status_clone.set(TerminalStatus::Closed);
+                let _ = app.emit(&format!("terminal-exit-{}", terminal_id), ());
+            }
             debug_log!("[PTY-READ] terminal={} - Reader thread exiting", terminal_id);
         });
 
@@ -110,11 +118,15 @@ impl PtySession {
             id: config.id,
             writer,
             pair,
-            _child: child,
+            child: Mutex::new(child),
+            status,
         })
     }
+}
 
-    pub fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+#[async_trait]
+impl TerminalBackend for PtySession {
+    async fn write(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         debug_log!("[PTY-WRITE] id={}, bytes={}, data: {:?}", 
             self.id, 
             data.len(), 
@@ -127,7 +139,7 @@ impl PtySession {
         Ok(())
     }
 
-    pub fn resize(&mut self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error>> {
+    async fn resize(&self, rows: u16, cols: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let pair = self.pair.lock().unwrap();
         pair.master.resize(PtySize {
             rows,
@@ -137,10 +149,20 @@ impl PtySession {
         })?;
         Ok(())
     }
-}
 
-pub type SessionMap = Arc<Mutex<HashMap<String, PtySession>>>;
+    /// 结束 shell 进程
+    async fn close(&self) {
+        self.status.set(TerminalStatus::Closed);
+        if let Err(e) = self.child.lock().unwrap().kill() {
+            debug_log!("[PTY] Failed to kill shell for {}: {}", self.id, e);
+        }
+    }
+
+    fn status(&self) -> TerminalStatus {
+        self.status.get()
+    }
 
-pub fn create_session_map() -> SessionMap {
-    Arc::new(Mutex::new(HashMap::new()))
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
 }
@@ -0,0 +1,62 @@
+use crate::config::{ConnectionStorage, ConnectionStorageState};
+use crate::vault::{Vault, VaultStatus};
+use tauri::State;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub type VaultState = Arc<Mutex<Vault>>;
+
+/// 查询保险库是否已创建、是否已解锁
+#[tauri::command]
+pub async fn vault_status(vault: State<'_, VaultState>) -> Result<VaultStatus, String> {
+    vault.lock().await
+        .status()
+        .map_err(|e| format!("Failed to read vault: {}", e))
+}
+
+/// 用主密码解锁保险库（首次调用时以该密码创建），每次启动应用后调用一次
+/// 解锁后把 connections.toml 中遗留的明文凭据迁移进保险库
+#[tauri::command]
+pub async fn unlock_vault(
+    master_password: String,
+    vault: State<'_, VaultState>,
+    storage: State<'_, ConnectionStorageState>,
+) -> Result<VaultStatus, String> {
+    debug_log!("[VAULT] unlock_vault called");
+
+    let mut vault = vault.lock().await;
+    vault.unlock(&master_password).await
+        .map_err(|e| format!("Failed to unlock vault: {}", e))?;
+
+    let storage = storage.lock().await;
+    let migrated = migrate_plaintext(&vault, &storage)
+        .map_err(|e| format!("Failed to migrate credentials: {}", e))?;
+    if migrated > 0 {
+        debug_log!("[VAULT] Migrated plaintext credentials of {} connections", migrated);
+    }
+
+    vault.status().map_err(|e| format!("Failed to read vault: {}", e))
+}
+
+/// 锁定保险库，清除内存中的密钥
+#[tauri::command]
+pub async fn lock_vault(vault: State<'_, VaultState>) -> Result<(), String> {
+    debug_log!("[VAULT] lock_vault called");
+    vault.lock().await.lock();
+    Ok(())
+}
+
+/// 把所有连接中的明文凭据移入保险库并改写配置文件，返回被修改的连接数
+fn migrate_plaintext(vault: &Vault, storage: &ConnectionStorage) -> Result<usize, String> {
+    let mut connections = storage.load_connections().map_err(|e| e.to_string())?;
+    let mut migrated = 0;
+    for connection in connections.iter_mut() {
+        if vault.seal_connection(connection).map_err(|e| e.to_string())? {
+            migrated += 1;
+        }
+    }
+    if migrated > 0 {
+        storage.save_connections(&connections).map_err(|e| e.to_string())?;
+    }
+    Ok(migrated)
+}
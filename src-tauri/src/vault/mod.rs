@@ -0,0 +1,5 @@
+mod store;
+pub mod commands;
+
+pub use store::{escape_secrets, strip_secret_refs, strip_secrets, Vault, VaultStatus, SECRET_REF_PREFIX};
+pub use commands::*;
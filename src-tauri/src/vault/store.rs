@@ -0,0 +1,500 @@
+use crate::models::{Connection, SshAuth, SshConfig};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+type VaultResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 连接配置中引用保险库条目的前缀，如 `vault:ssh-1700000000000/ssh`
+pub const SECRET_REF_PREFIX: &str = "vault:";
+/// 以 `vault:` 或 `plain:` 开头的明文加上该前缀保存，避免被误认为引用
+pub const LITERAL_PREFIX: &str = "plain:";
+
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+/// 用于校验主密码的固定明文，解锁时能解密即说明密码正确
+const CHECK_PLAINTEXT: &[u8] = b"konnect-vault";
+const CHECK_AAD: &[u8] = b"konnect-vault-check";
+
+/// Argon2id 参数，随文件保存以便日后调整
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+/// 一条 AES-256-GCM 密文（base64）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// vault.json 的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    check: Sealed,
+    /// 条目 ID → 密文；条目 ID 同时作为 AEAD 附加数据，密文不能被挪到其他条目下
+    #[serde(default)]
+    secrets: HashMap<String, Sealed>,
+}
+
+/// 保险库状态，返回给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    /// 是否已设置主密码
+    pub initialized: bool,
+    pub unlocked: bool,
+    pub secrets: usize,
+}
+
+/// 凭据保险库：密码与私钥口令加密保存在 vault.json，connections.toml 中只保留 `vault:<id>` 引用
+/// 密钥由主密码经 Argon2id 派生，仅在解锁期间保存在内存中
+pub struct Vault {
+    path: PathBuf,
+    cipher: Option<Aes256Gcm>,
+}
+
+impl Vault {
+    pub fn new() -> VaultResult<Self> {
+        let config_dir = dirs::config_dir()
+            .ok_or("无法获取配置目录")?
+            .join("konnect");
+        fs::create_dir_all(&config_dir)?;
+
+        Ok(Self {
+            path: config_dir.join("vault.json"),
+            cipher: None,
+        })
+    }
+
+    pub fn status(&self) -> VaultResult<VaultStatus> {
+        let file = self.read_file()?;
+        Ok(VaultStatus {
+            initialized: file.is_some(),
+            unlocked: self.cipher.is_some(),
+            secrets: file.map(|f| f.secrets.len()).unwrap_or(0),
+        })
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// 用主密码解锁；保险库不存在时以该密码新建
+    pub async fn unlock(&mut self, master_password: &str) -> VaultResult<()> {
+        if master_password.is_empty() {
+            return Err("Master password must not be empty".into());
+        }
+
+        let Some(file) = self.read_file()? else {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let kdf = KdfParams {
+                salt: BASE64.encode(salt),
+                m_cost: Params::DEFAULT_M_COST,
+                t_cost: Params::DEFAULT_T_COST,
+                p_cost: Params::DEFAULT_P_COST,
+            };
+            let cipher = derive_cipher(master_password, &kdf).await?;
+            let check = seal(&cipher, CHECK_AAD, CHECK_PLAINTEXT)?;
+            self.write_file(&VaultFile {
+                version: VAULT_VERSION,
+                kdf,
+                check,
+                secrets: HashMap::new(),
+            })?;
+            debug_log!("[VAULT] Created new vault at {}", self.path.display());
+            self.cipher = Some(cipher);
+            return Ok(());
+        };
+
+        let cipher = derive_cipher(master_password, &file.kdf).await?;
+        open(&cipher, CHECK_AAD, &file.check).map_err(|_| "Incorrect master password")?;
+        debug_log!("[VAULT] Unlocked ({} secrets)", file.secrets.len());
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    pub fn lock(&mut self) {
+        self.cipher = None;
+    }
+
+    /// 把连接中的明文凭据移入保险库，字段改写为引用
+    /// 同时清理该连接下已不再被引用的条目；返回连接是否被修改
+    /// 保险库未创建或未解锁时明文保持不变，下次解锁时再迁移
+    pub fn seal_connection(&self, connection: &mut Connection) -> VaultResult<bool> {
+        let mut file = self.read_file()?;
+        let prefix = format!("{}/", connection.id);
+        let mut referenced = HashSet::new();
+        let mut changed = false;
+
+        for (slot, value) in connection_slots(connection) {
+            if let Some(id) = value.strip_prefix(SECRET_REF_PREFIX) {
+                referenced.insert(id.to_string());
+                continue;
+            }
+            let (Some(cipher), Some(file)) = (self.cipher.as_ref(), file.as_mut()) else {
+                debug_log!("[VAULT] Vault unavailable, keeping {}{} in plaintext", prefix, slot);
+                continue;
+            };
+            let id = format!("{}{}", prefix, slot);
+            let plaintext = unescape_literal(value);
+            file.secrets.insert(id.clone(), seal(cipher, id.as_bytes(), plaintext.as_bytes())?);
+            *value = format!("{}{}", SECRET_REF_PREFIX, id);
+            referenced.insert(id);
+            changed = true;
+        }
+
+        if let Some(mut file) = file {
+            let before = file.secrets.len();
+            file.secrets.retain(|id, _| !id.starts_with(&prefix) || referenced.contains(id));
+            if changed || file.secrets.len() != before {
+                self.write_file(&file)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// 删除连接对应的所有条目（无需解锁）
+    pub fn remove_connection(&self, connection_id: &str) -> VaultResult<()> {
+        let Some(mut file) = self.read_file()? else {
+            return Ok(());
+        };
+        let prefix = format!("{}/", connection_id);
+        let before = file.secrets.len();
+        file.secrets.retain(|id, _| !id.starts_with(&prefix));
+        if file.secrets.len() != before {
+            self.write_file(&file)?;
+        }
+        Ok(())
+    }
+
+    /// 把连接中的引用替换为明文，连接前调用；未迁移的明文字段去掉转义前缀
+    pub fn resolve_connection(&self, connection: &mut Connection) -> VaultResult<()> {
+        self.resolve_slots(connection_slots(connection))
+    }
+
+    pub fn resolve_ssh_config(&self, config: &mut SshConfig) -> VaultResult<()> {
+        self.resolve_slots(ssh_slots(config))
+    }
+
+    fn resolve_slots(&self, slots: Vec<(String, &mut String)>) -> VaultResult<()> {
+        // 明文字段去掉转义前缀，引用字段从保险库解密
+        let mut refs = Vec::new();
+        for (_, value) in slots {
+            if value.starts_with(SECRET_REF_PREFIX) {
+                refs.push(value);
+            } else {
+                *value = unescape_literal(value).to_string();
+            }
+        }
+        if refs.is_empty() {
+            return Ok(());
+        }
+
+        let cipher = self.cipher.as_ref().ok_or("Vault is locked")?;
+        let file = self.read_file()?.ok_or("Vault is not initialized")?;
+        for value in refs {
+            let id = &value[SECRET_REF_PREFIX.len()..];
+            let sealed = file.secrets.get(id)
+                .ok_or_else(|| format!("Secret {} not found in vault", id))?;
+            let plaintext = open(cipher, id.as_bytes(), sealed)?;
+            *value = String::from_utf8(plaintext)?;
+        }
+        Ok(())
+    }
+
+    fn read_file(&self) -> VaultResult<Option<VaultFile>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&self.path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// 先写入同目录下的临时文件（创建时即为 0600）并落盘，再原子替换 vault.json，
+    /// 写入中途崩溃或磁盘已满时原文件保持完整
+    fn write_file(&self, file: &VaultFile) -> VaultResult<()> {
+        let content = serde_json::to_string_pretty(file)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        // 上次写入中断留下的临时文件可能带有其他权限，删除后重新创建
+        let _ = fs::remove_file(&tmp_path);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let result = (|| -> VaultResult<()> {
+            let mut tmp = options.open(&tmp_path)?;
+            tmp.write_all(content.as_bytes())?;
+            tmp.sync_all()?;
+            fs::rename(&tmp_path, &self.path)?;
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result?;
+
+        // 同步目录，确保重命名本身也已落盘
+        #[cfg(unix)]
+        if let Some(dir) = self.path.parent() {
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+/// 连接中所有凭据字段，槽位名用于生成条目 ID
+fn connection_slots(connection: &mut Connection) -> Vec<(String, &mut String)> {
+    let mut slots = connection.ssh_config.as_mut().map(ssh_slots).unwrap_or_default();
+    if let Some(ftp) = connection.ftp_config.as_mut() {
+        if !ftp.password.is_empty() {
+            slots.push(("ftp".to_string(), &mut ftp.password));
+        }
+    }
+    slots
+}
+
+fn ssh_slots(config: &mut SshConfig) -> Vec<(String, &mut String)> {
+    let mut slots = Vec::new();
+    if let Some(secret) = auth_secret(&mut config.auth) {
+        slots.push(("ssh".to_string(), secret));
+    }
+    for (index, hop) in config.jump_hosts.iter_mut().enumerate() {
+        if let Some(secret) = auth_secret(&mut hop.auth) {
+            slots.push((format!("jump-{}", index), secret));
+        }
+    }
+    slots
+}
+
+fn auth_secret(auth: &mut SshAuth) -> Option<&mut String> {
+    match auth {
+        SshAuth::Password(password) => Some(password),
+        SshAuth::PublicKey { passphrase: Some(passphrase), .. } => Some(passphrase),
+        _ => None,
+    }
+    .filter(|secret| !secret.is_empty())
+}
+
+/// Argon2id 派生耗时较长，放到阻塞线程池中执行；主密码副本与派生出的密钥用后清零
+async fn derive_cipher(master_password: &str, kdf: &KdfParams) -> VaultResult<Aes256Gcm> {
+    let master_password = Zeroizing::new(master_password.to_string());
+    let salt = BASE64.decode(&kdf.salt)?;
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+        .map_err(|e| format!("Invalid KDF parameters: {}", e))?;
+    tokio::task::spawn_blocking(move || -> VaultResult<Aes256Gcm> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(master_password.as_bytes(), &salt, key.as_mut_slice())
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice())))
+    })
+    .await?
+}
+
+fn seal(cipher: &Aes256Gcm, aad: &[u8], plaintext: &[u8]) -> VaultResult<Sealed> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, Payload { msg: plaintext, aad })
+        .map_err(|_| "Encryption failed")?;
+    Ok(Sealed {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn open(cipher: &Aes256Gcm, aad: &[u8], sealed: &Sealed) -> VaultResult<Vec<u8>> {
+    let nonce = BASE64.decode(&sealed.nonce)?;
+    if nonce.len() != 12 {
+        return Err("Invalid nonce".into());
+    }
+    let ciphertext = BASE64.decode(&sealed.ciphertext)?;
+    Ok(cipher.decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad })
+        .map_err(|_| "Decryption failed")?)
+}
+
+/// 明文写入配置文件前转义：以引用前缀或转义前缀开头的值加上 `plain:`
+fn escape_literal(value: &str) -> String {
+    if value.starts_with(SECRET_REF_PREFIX) || value.starts_with(LITERAL_PREFIX) {
+        format!("{}{}", LITERAL_PREFIX, value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn unescape_literal(value: &str) -> &str {
+    value.strip_prefix(LITERAL_PREFIX).unwrap_or(value)
+}
+
+/// 把已解析为明文的凭据转义回配置文件格式，用于导出
+pub fn escape_secrets(connection: &mut Connection) {
+    for (_, value) in connection_slots(connection) {
+        *value = escape_literal(value);
+    }
+}
+
+/// 清除连接中的所有凭据，用于导出分享
+pub fn strip_secrets(connection: &mut Connection) {
+    strip(connection, |_| true);
+}
+
+/// 清除指向保险库的引用、保留明文；其他设备导出的引用在本机无法解析
+pub fn strip_secret_refs(connection: &mut Connection) {
+    strip(connection, |value| value.starts_with(SECRET_REF_PREFIX));
+}
+
+fn strip(connection: &mut Connection, matches: impl Fn(&str) -> bool) {
+    for (_, value) in connection_slots(connection) {
+        if matches(value) {
+            value.clear();
+        }
+    }
+
+    // 清空后的私钥口令视为未设置
+    if let Some(ssh) = connection.ssh_config.as_mut() {
+        let auths = std::iter::once(&mut ssh.auth).chain(ssh.jump_hosts.iter_mut().map(|j| &mut j.auth));
+        for auth in auths {
+            if let SshAuth::PublicKey { passphrase, .. } = auth {
+                if passphrase.as_deref() == Some("") {
+                    *passphrase = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FtpConfig;
+
+    fn vault_in(dir: &tempfile::TempDir) -> Vault {
+        Vault {
+            path: dir.path().join("vault.json"),
+            cipher: None,
+        }
+    }
+
+    fn ftp_connection(password: &str) -> Connection {
+        let mut ftp = FtpConfig::new("example.com".to_string(), 21, "user".to_string());
+        ftp.password = password.to_string();
+        let mut connection = Connection::new_local("ftp".to_string());
+        connection.ftp_config = Some(ftp);
+        connection
+    }
+
+    fn ftp_password(connection: &Connection) -> &str {
+        &connection.ftp_config.as_ref().unwrap().password
+    }
+
+    #[test]
+    fn literals_with_reserved_prefixes_are_escaped() {
+        assert_eq!(escape_literal("secret"), "secret");
+        assert_eq!(escape_literal("vault:secret"), "plain:vault:secret");
+        assert_eq!(escape_literal("plain:secret"), "plain:plain:secret");
+        for value in ["secret", "vault:secret", "plain:secret", ""] {
+            assert_eq!(unescape_literal(&escape_literal(value)), value);
+        }
+    }
+
+    #[test]
+    fn seal_without_vault_keeps_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault_in(&dir);
+        let mut connection = ftp_connection(&escape_literal("vault:not-a-ref"));
+
+        assert!(!vault.seal_connection(&mut connection).unwrap());
+        assert_eq!(ftp_password(&connection), "plain:vault:not-a-ref");
+
+        vault.resolve_connection(&mut connection).unwrap();
+        assert_eq!(ftp_password(&connection), "vault:not-a-ref");
+    }
+
+    #[tokio::test]
+    async fn escaped_literal_is_sealed_and_resolved_verbatim() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = vault_in(&dir);
+        vault.unlock("master").await.unwrap();
+
+        let mut connection = ftp_connection(&escape_literal("vault:not-a-ref"));
+        assert!(vault.seal_connection(&mut connection).unwrap());
+        let reference = ftp_password(&connection).to_string();
+        assert_eq!(reference, format!("{}{}/ftp", SECRET_REF_PREFIX, connection.id));
+
+        // 已是引用的字段再次保存时保持不变
+        assert!(!vault.seal_connection(&mut connection).unwrap());
+        assert_eq!(ftp_password(&connection), reference);
+
+        vault.resolve_connection(&mut connection).unwrap();
+        assert_eq!(ftp_password(&connection), "vault:not-a-ref");
+    }
+
+    #[tokio::test]
+    async fn locked_vault_keeps_plaintext_until_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = vault_in(&dir);
+        vault.unlock("master").await.unwrap();
+        vault.lock();
+
+        let mut connection = ftp_connection("secret");
+        assert!(!vault.seal_connection(&mut connection).unwrap());
+        assert_eq!(ftp_password(&connection), "secret");
+        assert!(vault.resolve_connection(&mut ftp_connection("vault:missing")).is_err());
+
+        vault.unlock("master").await.unwrap();
+        assert!(vault.seal_connection(&mut connection).unwrap());
+        assert!(ftp_password(&connection).starts_with(SECRET_REF_PREFIX));
+        assert_eq!(vault.status().unwrap().secrets, 1);
+    }
+
+    #[tokio::test]
+    async fn vault_file_is_replaced_atomically_with_owner_only_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = vault_in(&dir);
+        // 模拟上次写入中断留下的临时文件
+        fs::write(dir.path().join("vault.json.tmp"), "partial").unwrap();
+
+        vault.unlock("master").await.unwrap();
+        vault.seal_connection(&mut ftp_connection("secret")).unwrap();
+
+        assert!(!dir.path().join("vault.json.tmp").exists());
+        assert_eq!(vault.status().unwrap().secrets, 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join("vault.json")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_master_password_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = vault_in(&dir);
+        vault.unlock("master").await.unwrap();
+        vault.lock();
+
+        assert!(vault.unlock("wrong").await.is_err());
+        assert!(!vault.is_unlocked());
+        vault.unlock("master").await.unwrap();
+        assert!(vault.is_unlocked());
+    }
+}
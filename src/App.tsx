@@ -3,7 +3,12 @@ import { Terminal } from "./components/Terminal";
 import { ConnectionDialog } from "./components/ConnectionDialog";
 import { useTerminalStore } from "./store/terminalStore";
 import { TerminalSession } from "./types/terminal";
-import { Connection, ConnectionType, SshConfig } from "./types/connection";
+import { Connection, ConnectionMatch, ConnectionType, SshConfig, ReconnectPolicy, TelnetConfig, SerialConfig, FtpConfig } from "./types/connection";
+import { SftpExplorer } from "./components/SftpExplorer";
+import { VaultDialog } from "./components/VaultDialog";
+import { ImportDialog } from "./components/ImportDialog";
+import { BundleDialog } from "./components/BundleDialog";
+import { VaultStatus } from "./types/vault";
 import { invoke } from "@tauri-apps/api/core";
 
 function App() {
@@ -12,7 +17,13 @@ function App() {
   const [editingConnection, setEditingConnection] = useState<Connection | null>(null);
   const [showSavedMenu, setShowSavedMenu] = useState(false);
   const [savedConnections, setSavedConnections] = useState<Connection[]>([]);
+  const [searchQuery, setSearchQuery] = useState("");
+  const [searchResults, setSearchResults] = useState<ConnectionMatch[]>([]);
   const initializedRef = useRef(false);
+  const [vaultStatus, setVaultStatus] = useState<VaultStatus | null>(null);
+  const [showVaultDialog, setShowVaultDialog] = useState(false);
+  const [showImportDialog, setShowImportDialog] = useState(false);
+  const [bundleMode, setBundleMode] = useState<'export' | 'import' | null>(null);
 
   // 标签页重命名状态
   const [editingTabId, setEditingTabId] = useState<string | null>(null);
@@ -24,13 +35,49 @@ function App() {
     loadSavedConnections();
   }, []);
 
+  // 搜索已保存的连接，空查询按收藏与最近使用排序
+  useEffect(() => {
+    if (!showSavedMenu) return;
+    invoke<ConnectionMatch[]>('search_connections', { query: searchQuery })
+      .then(setSearchResults)
+      .catch((error) => console.error('[App] Failed to search connections:', error));
+  }, [showSavedMenu, searchQuery, savedConnections]);
+
+  // 启动时检查凭据保险库，未解锁则提示输入主密码
+  useEffect(() => {
+    invoke<VaultStatus>('vault_status')
+      .then((status) => {
+        setVaultStatus(status);
+        setShowVaultDialog(!status.unlocked);
+      })
+      .catch((error) => console.error('[App] Failed to query vault:', error));
+  }, []);
+
+  const handleVaultUnlocked = (status: VaultStatus) => {
+    setVaultStatus(status);
+    setShowVaultDialog(false);
+    // 解锁时会迁移明文凭据，重新加载连接以获取引用
+    loadSavedConnections();
+  };
+
+  const handleLockVault = async () => {
+    try {
+      await invoke('lock_vault');
+      setVaultStatus(await invoke<VaultStatus>('vault_status'));
+    } catch (error) {
+      console.error('[App] Failed to lock vault:', error);
+    }
+  };
+
   const loadSavedConnections = async () => {
     try {
       const connections = await invoke<Connection[]>('load_connections');
       console.log('[App] Loaded connections:', connections);
       setSavedConnections(connections);
+      return connections;
     } catch (error) {
       console.error('[App] Failed to load connections:', error);
+      return [];
     }
   };
 
@@ -67,18 +114,26 @@ function App() {
     return () => clearTimeout(timer);
   }, [addSession]);
 
-  const handleNewConnection = useCallback(async (name: string, connectionType: ConnectionType, sshConfig?: SshConfig) => {
+  const handleNewConnection = useCallback(async (name: string, connectionType: ConnectionType, sshConfig?: SshConfig, reconnect?: ReconnectPolicy | null, telnetConfig?: TelnetConfig, serialConfig?: SerialConfig, ftpConfig?: FtpConfig) => {
     const connection: Connection = {
       id: `${connectionType.toLowerCase()}-${Date.now()}`,
       name,
       connection_type: connectionType,
       ssh_config: sshConfig,
+      reconnect,
+      telnet_config: telnetConfig,
+      serial_config: serialConfig,
+      ftp_config: ftpConfig,
     };
 
-    // 保存连接配置
+    // 保存连接配置；保存后凭据已移入保险库，会话使用带引用的配置，避免明文写入本地存储
     try {
       await invoke('save_connection', { connection });
-      await loadSavedConnections();
+      const saved = (await loadSavedConnections()).find((c) => c.id === connection.id);
+      if (saved) {
+        sshConfig = saved.ssh_config;
+        ftpConfig = saved.ftp_config;
+      }
       console.log('[App] Connection saved successfully');
     } catch (error) {
       console.error('[App] Failed to save connection:', error);
@@ -89,8 +144,13 @@ function App() {
       id: connection.id,
       name,
       connectionType,
+      connectionId: connection.id,
       shell: connectionType === ConnectionType.Local ? '/bin/zsh' : undefined,
       sshConfig,
+      reconnect,
+      telnetConfig,
+      serialConfig,
+      ftpConfig,
       createdAt: Date.now(),
       isActive: true,
     };
@@ -103,14 +163,31 @@ function App() {
       id: `${connection.connection_type.toLowerCase()}-${Date.now()}`,
       name: connection.name,
       connectionType: connection.connection_type,
+      connectionId: connection.id,
       shell: connection.connection_type === ConnectionType.Local ? '/bin/zsh' : undefined,
       sshConfig: connection.ssh_config,
+      portForwards: connection.port_forwards,
+      reconnect: connection.reconnect,
+      telnetConfig: connection.telnet_config,
+      serialConfig: connection.serial_config,
+      ftpConfig: connection.ftp_config,
       createdAt: Date.now(),
       isActive: true,
     };
     addSession(newSession);
+    invoke('mark_connection_used', { id: connection.id })
+      .catch((error) => console.error('[App] Failed to mark connection used:', error));
   }, [addSession]);
 
+  const handleToggleFavorite = async (connection: Connection) => {
+    try {
+      await invoke('set_connection_favorite', { id: connection.id, favorite: !connection.favorited_at });
+      await loadSavedConnections();
+    } catch (error) {
+      console.error('[App] Failed to update favorite:', error);
+    }
+  };
+
   const handleDeleteConnection = async (id: string) => {
     try {
       await invoke('delete_connection', { id });
@@ -126,7 +203,7 @@ function App() {
   }, [removeSession]);
 
   // 更新连接
-  const handleUpdateConnection = useCallback(async (name: string, connectionType: ConnectionType, sshConfig?: SshConfig) => {
+  const handleUpdateConnection = useCallback(async (name: string, connectionType: ConnectionType, sshConfig?: SshConfig, reconnect?: ReconnectPolicy | null, telnetConfig?: TelnetConfig, serialConfig?: SerialConfig, ftpConfig?: FtpConfig) => {
     if (!editingConnection) return;
 
     const updatedConnection: Connection = {
@@ -134,6 +211,10 @@ function App() {
       name,
       connection_type: connectionType,
       ssh_config: sshConfig,
+      reconnect,
+      telnet_config: telnetConfig,
+      serial_config: serialConfig,
+      ftp_config: ftpConfig,
     };
 
     try {
@@ -217,20 +298,66 @@ function App() {
                   <div className="absolute left-0 mt-2 w-64 bg-[#1A1A1F] border border-gray-800 rounded-xl shadow-2xl z-20 py-2 animate-in fade-in zoom-in duration-200">
                     <div className="px-4 py-2 border-b border-gray-800 flex justify-between items-center">
                       <span className="text-xs font-semibold text-gray-500 uppercase tracking-wider">Saved Connections</span>
-                      {savedConnections.length > 0 && (
-                        <span className="text-[10px] bg-gray-800 text-gray-400 px-1.5 py-0.5 rounded-full">
-                          {savedConnections.length}
-                        </span>
-                      )}
+                      <div className="flex items-center gap-2">
+                        {savedConnections.length > 0 && (
+                          <span className="text-[10px] bg-gray-800 text-gray-400 px-1.5 py-0.5 rounded-full">
+                            {savedConnections.length}
+                          </span>
+                        )}
+                        <button
+                          onClick={() => {
+                            setShowImportDialog(true);
+                            setShowSavedMenu(false);
+                          }}
+                          className="text-[10px] text-cyan-400 hover:text-cyan-300"
+                        >
+                          Import…
+                        </button>
+                        <button
+                          onClick={() => {
+                            setBundleMode('import');
+                            setShowSavedMenu(false);
+                          }}
+                          className="text-[10px] text-cyan-400 hover:text-cyan-300"
+                          title="Import a Konnect connection bundle"
+                        >
+                          Bundle…
+                        </button>
+                        <button
+                          onClick={() => {
+                            setBundleMode('export');
+                            setShowSavedMenu(false);
+                          }}
+                          className="text-[10px] text-cyan-400 hover:text-cyan-300"
+                        >
+                          Export…
+                        </button>
+                      </div>
                     </div>
+                    {savedConnections.length > 0 && (
+                      <div className="px-3 pt-2">
+                        <input
+                          type="text"
+                          value={searchQuery}
+                          onChange={(e) => setSearchQuery(e.target.value)}
+                          placeholder="Search name, host, user, tag…"
+                          autoFocus
+                          className="w-full px-2 py-1 bg-[#0D0D11] border border-gray-700 rounded-md text-xs text-white placeholder-gray-600 focus:outline-none focus:border-cyan-600"
+                        />
+                      </div>
+                    )}
                     <div className="max-h-[60vh] overflow-y-auto">
                       {savedConnections.length === 0 ? (
                         <div className="px-4 py-8 text-center">
                           <p className="text-sm text-gray-500">No saved connections</p>
                         </div>
+                      ) : searchResults.length === 0 ? (
+                        <div className="px-4 py-6 text-center">
+                          <p className="text-sm text-gray-500">No matching connections</p>
+                        </div>
                       ) : (
                         <div className="p-1">
-                          {savedConnections.map((conn) => (
+                          {searchResults.map(({ connection: conn, group_path }) => (
                             <div key={conn.id} className="group relative">
                               <button
                                 onClick={() => {
@@ -242,17 +369,42 @@ function App() {
                                 <span className="text-lg mt-0.5 flex-shrink-0">
                                   {conn.connection_type === ConnectionType.SSH ? '🔐' :
                                     conn.connection_type === ConnectionType.Local ? '💻' :
-                                      conn.connection_type === ConnectionType.Telnet ? '📡' : '🔌'}
+                                      conn.connection_type === ConnectionType.Telnet ? '📡' :
+                                        conn.connection_type === ConnectionType.Ftp ? '📂' : '🔌'}
                                 </span>
                                 <div className="flex-1 min-w-0">
-                                  <div className="text-sm font-medium text-gray-200 truncate">
-                                    {conn.name}
+                                  <div className="text-sm font-medium text-gray-200 truncate flex items-center gap-1.5">
+                                    {conn.color && (
+                                      <span className="w-2 h-2 rounded-full flex-shrink-0" style={{ backgroundColor: conn.color }} />
+                                    )}
+                                    <span className="truncate">{conn.name}</span>
                                   </div>
                                   <div className="text-[10px] text-gray-500 truncate">
-                                    {conn.ssh_config ? `${conn.ssh_config.username}@${conn.ssh_config.host}` : conn.connection_type}
+                                    {group_path.length > 0 && `${group_path.join(' / ')} · `}
+                                    {conn.ssh_config ? `${conn.ssh_config.username}@${conn.ssh_config.host}` :
+                                      conn.ftp_config ? `${conn.ftp_config.username || 'anonymous'}@${conn.ftp_config.host}` : conn.connection_type}
                                   </div>
+                                  {conn.tags && conn.tags.length > 0 && (
+                                    <div className="flex flex-wrap gap-1 mt-0.5">
+                                      {conn.tags.map((tag) => (
+                                        <span key={tag} className="text-[9px] bg-gray-800 text-gray-400 px-1 rounded">
+                                          {tag}
+                                        </span>
+                                      ))}
+                                    </div>
+                                  )}
                                 </div>
                               </button>
+                              <button
+                                onClick={(e) => {
+                                  e.stopPropagation();
+                                  handleToggleFavorite(conn);
+                                }}
+                                className={`absolute right-14 top-1/2 -translate-y-1/2 transition-opacity p-1.5 rounded-md hover:bg-yellow-500/20 text-yellow-400 text-xs leading-none ${conn.favorited_at ? '' : 'opacity-0 group-hover:opacity-100'}`}
+                                title={conn.favorited_at ? 'Remove from favorites' : 'Add to favorites'}
+                              >
+                                {conn.favorited_at ? '★' : '☆'}
+                              </button>
                               <button
                                 onClick={(e) => {
                                   e.stopPropagation();
@@ -292,6 +444,15 @@ function App() {
             </div>
           </div>
           <div className="flex items-center space-x-2">
+            {vaultStatus && (
+              <button
+                onClick={() => vaultStatus.unlocked ? handleLockVault() : setShowVaultDialog(true)}
+                className="px-2 py-1 rounded-lg text-xs text-gray-400 hover:text-white hover:bg-gray-700 transition-colors"
+                title={vaultStatus.unlocked ? 'Lock credential vault' : 'Unlock credential vault'}
+              >
+                {vaultStatus.unlocked ? '🔓 Vault' : '🔒 Vault'}
+              </button>
+            )}
             <div className="text-xs text-gray-500 font-mono">
               {sessions.length} {sessions.length === 1 ? 'session' : 'sessions'}
             </div>
@@ -344,13 +505,32 @@ function App() {
               key={session.id}
               className={`h-full ${session.id === activeSessionId ? 'block' : 'hidden'}`}
             >
+              {session.connectionType === ConnectionType.Ftp ? (
+                <SftpExplorer
+                  protocol="ftp"
+                  sessionId={session.id}
+                  connection={{
+                    id: session.id,
+                    name: session.name,
+                    connection_type: ConnectionType.Ftp,
+                    ftp_config: session.ftpConfig,
+                  }}
+                  onClose={() => handleCloseTerminal(session.id)}
+                />
+              ) : (
               <Terminal
                 terminalId={session.id}
+                connectionId={session.connectionId}
                 connectionType={session.connectionType}
                 shell={session.shell}
                 sshConfig={session.sshConfig}
+                portForwards={session.portForwards}
+                reconnect={session.reconnect}
+                telnetConfig={session.telnetConfig}
+                serialConfig={session.serialConfig}
                 onClose={() => handleCloseTerminal(session.id)}
               />
+              )}
             </div>
           ))}
         </div>
@@ -365,6 +545,25 @@ function App() {
         onSave={editingConnection ? handleUpdateConnection : handleNewConnection}
         editConnection={editingConnection}
       />
+
+      <ImportDialog
+        isOpen={showImportDialog}
+        onClose={() => setShowImportDialog(false)}
+        onImported={() => loadSavedConnections()}
+      />
+
+      <BundleDialog
+        mode={bundleMode}
+        onClose={() => setBundleMode(null)}
+        onImported={() => loadSavedConnections()}
+      />
+
+      <VaultDialog
+        isOpen={showVaultDialog}
+        status={vaultStatus}
+        onUnlocked={handleVaultUnlocked}
+        onSkip={() => setShowVaultDialog(false)}
+      />
     </div>
   );
 }
@@ -0,0 +1,208 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import { BundleFormat, BundleImportOptions, BundleImportReport } from '../types/import';
+
+interface BundleDialogProps {
+    /** 打开的模式，为 null 时不显示 */
+    mode: 'export' | 'import' | null;
+    onClose: () => void;
+    /** 导入完成回调 */
+    onImported: () => void;
+}
+
+/**
+ * 连接导出包对话框
+ * 导出：选择格式与是否包含凭据；导入：先 dry-run 显示冲突，再确认导入
+ */
+export const BundleDialog: React.FC<BundleDialogProps> = ({ mode, onClose, onImported }) => {
+    const [format, setFormat] = useState<BundleFormat>('Json');
+    const [includeSecrets, setIncludeSecrets] = useState(false);
+    const [path, setPath] = useState('');
+    const [options, setOptions] = useState<BundleImportOptions>({ mode: 'Merge', rekey: false, overwrite: false });
+    const [report, setReport] = useState<BundleImportReport | null>(null);
+    const [message, setMessage] = useState<string | null>(null);
+    const [error, setError] = useState<string | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    useEffect(() => {
+        setPath('');
+        setReport(null);
+        setMessage(null);
+        setError(null);
+    }, [mode]);
+
+    // 导入选项变化后重新生成预览
+    useEffect(() => {
+        if (mode === 'import' && path) {
+            runImport(true);
+        }
+    }, [path, options]);
+
+    if (!mode) {
+        return null;
+    }
+
+    const handleExport = async () => {
+        const extension = format === 'Json' ? 'json' : 'toml';
+        const target = await save({
+            title: 'Export connections',
+            defaultPath: `konnect-connections.${extension}`,
+            filters: [{ name: format, extensions: [extension] }],
+        });
+        if (!target) return;
+
+        setBusy(true);
+        setError(null);
+        try {
+            const count = await invoke<number>('export_connections', {
+                path: target,
+                options: { format, strip_secrets: !includeSecrets },
+            });
+            setMessage(`Exported ${count} connection${count === 1 ? '' : 's'}`);
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleBrowse = async () => {
+        const picked = await open({
+            title: 'Import connections',
+            filters: [{ name: 'Connection bundle', extensions: ['json', 'toml'] }],
+        });
+        if (typeof picked === 'string') {
+            setPath(picked);
+        }
+    };
+
+    const runImport = async (dryRun: boolean) => {
+        setBusy(true);
+        setError(null);
+        try {
+            const result = await invoke<BundleImportReport>('import_connections', {
+                path,
+                options: { ...options, dry_run: dryRun },
+            });
+            setReport(result);
+            if (!dryRun) {
+                setMessage(`Imported ${result.imported.length} connection${result.imported.length === 1 ? '' : 's'}`);
+                onImported();
+            }
+        } catch (err) {
+            setReport(null);
+            setError(String(err));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/60 backdrop-blur-sm">
+            <div className="bg-[#1a1a24] rounded-xl shadow-2xl border border-gray-700 w-full max-w-lg mx-4 overflow-hidden">
+                <div className="px-6 py-4 border-b border-gray-700">
+                    <h2 className="text-lg font-semibold text-white">
+                        {mode === 'export' ? 'Export Connections' : 'Import Connection Bundle'}
+                    </h2>
+                </div>
+
+                <div className="px-6 py-4 space-y-3 text-sm text-gray-300">
+                    {mode === 'export' ? (
+                        <>
+                            <div className="flex items-center gap-3">
+                                <span>Format</span>
+                                {(['Json', 'Toml'] as BundleFormat[]).map((f) => (
+                                    <label key={f} className="flex items-center gap-1 cursor-pointer">
+                                        <input type="radio" checked={format === f} onChange={() => setFormat(f)} />
+                                        {f.toUpperCase()}
+                                    </label>
+                                ))}
+                            </div>
+                            <label className="flex items-center gap-2 cursor-pointer">
+                                <input type="checkbox" checked={includeSecrets} onChange={(e) => setIncludeSecrets(e.target.checked)} />
+                                Include passwords and passphrases (plaintext, requires unlocked vault)
+                            </label>
+                        </>
+                    ) : (
+                        <>
+                            <div className="flex gap-2">
+                                <input
+                                    type="text"
+                                    value={path}
+                                    readOnly
+                                    placeholder="Select a .json or .toml bundle"
+                                    className="flex-1 px-3 py-1.5 bg-[#0D0D11] border border-gray-700 rounded-lg text-white text-sm"
+                                />
+                                <button
+                                    onClick={handleBrowse}
+                                    className="px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-lg transition-colors"
+                                >
+                                    Browse…
+                                </button>
+                            </div>
+                            <div className="flex items-center gap-3">
+                                {(['Merge', 'Replace'] as const).map((m) => (
+                                    <label key={m} className="flex items-center gap-1 cursor-pointer">
+                                        <input type="radio" checked={options.mode === m} onChange={() => setOptions({ ...options, mode: m })} />
+                                        {m === 'Merge' ? 'Merge with existing' : 'Replace all'}
+                                    </label>
+                                ))}
+                            </div>
+                            <label className="flex items-center gap-2 cursor-pointer">
+                                <input type="checkbox" checked={!!options.rekey} onChange={(e) => setOptions({ ...options, rekey: e.target.checked })} />
+                                Assign new IDs
+                            </label>
+                            {options.mode === 'Merge' && !options.rekey && (
+                                <label className="flex items-center gap-2 cursor-pointer">
+                                    <input type="checkbox" checked={!!options.overwrite} onChange={(e) => setOptions({ ...options, overwrite: e.target.checked })} />
+                                    Overwrite connections with the same ID
+                                </label>
+                            )}
+
+                            {report && (
+                                <div className="border border-gray-700 rounded-lg p-3 space-y-1 max-h-48 overflow-y-auto">
+                                    <div>
+                                        {report.dry_run ? 'Will import' : 'Imported'} {report.imported.length}
+                                        {report.removed > 0 && `, remove ${report.removed} existing`}
+                                    </div>
+                                    {report.conflicts.map((c) => (
+                                        <div key={`${c.id}-${c.name}`} className="text-xs text-yellow-400">
+                                            {c.action === 'Skipped' ? 'Skip' : 'Overwrite'} {c.name} (conflicts with {c.existing_name}, id {c.id})
+                                        </div>
+                                    ))}
+                                </div>
+                            )}
+                        </>
+                    )}
+
+                    {message && <p className="text-green-400">{message}</p>}
+                    {error && <p className="text-red-400">{error}</p>}
+                </div>
+
+                <div className="px-6 py-3 border-t border-gray-700 flex justify-end space-x-2">
+                    <button onClick={onClose} className="px-4 py-2 text-sm text-gray-400 hover:text-white transition-colors">
+                        Close
+                    </button>
+                    {mode === 'export' ? (
+                        <button
+                            onClick={handleExport}
+                            disabled={busy}
+                            className="px-4 py-2 text-sm bg-cyan-600 hover:bg-cyan-500 disabled:opacity-50 text-white rounded-lg transition-colors"
+                        >
+                            Export…
+                        </button>
+                    ) : (
+                        <button
+                            onClick={() => runImport(false)}
+                            disabled={busy || !report || !report.dry_run}
+                            className="px-4 py-2 text-sm bg-cyan-600 hover:bg-cyan-500 disabled:opacity-50 text-white rounded-lg transition-colors"
+                        >
+                            Import
+                        </button>
+                    )}
+                </div>
+            </div>
+        </div>
+    );
+};
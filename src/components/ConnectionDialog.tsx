@@ -1,16 +1,31 @@
 import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import {
     Connection,
     ConnectionType,
     SshConfig,
+    ReconnectPolicy,
+    TelnetConfig,
+    SerialConfig,
+    SerialParity,
+    SerialFlowControl,
+    SerialPortEntry,
+    FtpConfig,
+    FtpSecurity,
+    DEFAULT_RECONNECT_POLICY,
+    AlgorithmPreset,
+    DEFAULT_SSH_ADVANCED,
     createPasswordAuth,
     createPublicKeyAuth,
+    createAgentAuth,
 } from '../types/connection';
+import { DiagnosticReport } from '../types/diagnostics';
+import { editSecret, isSecretRef, secretInputValue } from '../types/vault';
 
 interface ConnectionDialogProps {
     isOpen: boolean;
     onClose: () => void;
-    onSave: (name: string, connectionType: ConnectionType, sshConfig?: SshConfig) => void;
+    onSave: (name: string, connectionType: ConnectionType, sshConfig?: SshConfig, reconnect?: ReconnectPolicy | null, telnetConfig?: TelnetConfig, serialConfig?: SerialConfig, ftpConfig?: FtpConfig) => void;
     editConnection?: Connection | null;
 }
 
@@ -29,22 +44,82 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
     const [username, setUsername] = useState('');
 
     // Authentication method
-    const [authType, setAuthType] = useState<'password' | 'publickey'>('password');
+    const [authType, setAuthType] = useState<'password' | 'publickey' | 'agent'>('password');
     const [password, setPassword] = useState('');
     const [privateKeyPath, setPrivateKeyPath] = useState('');
     const [passphrase, setPassphrase] = useState('');
+    const [agentSocketPath, setAgentSocketPath] = useState('');
+
+    // 串口配置
+    const [serialDevice, setSerialDevice] = useState('');
+    const [baudRate, setBaudRate] = useState(115200);
+    const [dataBits, setDataBits] = useState(8);
+    const [parity, setParity] = useState<SerialParity>('None');
+    const [stopBits, setStopBits] = useState(1);
+    const [flowControl, setFlowControl] = useState<SerialFlowControl>('None');
+    const [serialPorts, setSerialPorts] = useState<SerialPortEntry[]>([]);
+
+    // FTP 配置（主机、用户名与密码复用 SSH 字段）
+    const [ftpSecurity, setFtpSecurity] = useState<FtpSecurity>('None');
+    const [passive, setPassive] = useState(true);
+
+    // 断线自动重连
+    const [autoReconnect, setAutoReconnect] = useState(false);
+
+    // 高级设置
+    const [showAdvanced, setShowAdvanced] = useState(false);
+    const [preset, setPreset] = useState<AlgorithmPreset>(DEFAULT_SSH_ADVANCED.preset);
+    const [compression, setCompression] = useState(DEFAULT_SSH_ADVANCED.compression);
+    const [keepaliveInput, setKeepaliveInput] = useState(String(DEFAULT_SSH_ADVANCED.keepalive_interval_secs));
+    const [timeoutInput, setTimeoutInput] = useState(String(DEFAULT_SSH_ADVANCED.connect_timeout_secs));
+
+    // 连接测试
+    const [testing, setTesting] = useState(false);
+    const [testReport, setTestReport] = useState<DiagnosticReport | null>(null);
 
     // 编辑模式：预填充表单
     useEffect(() => {
         if (editConnection) {
             setName(editConnection.name);
             setConnectionType(editConnection.connection_type);
+            setAutoReconnect(!!editConnection.reconnect);
+
+            if (editConnection.telnet_config) {
+                setHost(editConnection.telnet_config.host);
+                setPortInput(editConnection.telnet_config.port.toString());
+            }
+
+            if (editConnection.ftp_config) {
+                const ftp = editConnection.ftp_config;
+                setHost(ftp.host);
+                setPortInput(ftp.port.toString());
+                setUsername(ftp.username);
+                setPassword(ftp.password);
+                setFtpSecurity(ftp.security);
+                setPassive(ftp.passive);
+            }
+
+            if (editConnection.serial_config) {
+                const serial = editConnection.serial_config;
+                setSerialDevice(serial.device);
+                setBaudRate(serial.baud_rate);
+                setDataBits(serial.data_bits);
+                setParity(serial.parity);
+                setStopBits(serial.stop_bits);
+                setFlowControl(serial.flow_control);
+            }
 
             if (editConnection.ssh_config) {
                 setHost(editConnection.ssh_config.host);
                 setPortInput(editConnection.ssh_config.port.toString());
                 setUsername(editConnection.ssh_config.username);
 
+                const advanced = { ...DEFAULT_SSH_ADVANCED, ...editConnection.ssh_config.advanced };
+                setPreset(advanced.preset);
+                setCompression(advanced.compression);
+                setKeepaliveInput(String(advanced.keepalive_interval_secs));
+                setTimeoutInput(String(advanced.connect_timeout_secs));
+
                 // 判断认证类型
                 const auth = editConnection.ssh_config.auth;
                 if ('Password' in auth) {
@@ -54,6 +129,9 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                     setAuthType('publickey');
                     setPrivateKeyPath(auth.PublicKey.private_key_path);
                     setPassphrase(auth.PublicKey.passphrase || '');
+                } else if ('Agent' in auth) {
+                    setAuthType('agent');
+                    setAgentSocketPath(auth.Agent.socket_path || '');
                 }
             }
         } else {
@@ -67,15 +145,46 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
             setPassword('');
             setPrivateKeyPath('');
             setPassphrase('');
+            setAgentSocketPath('');
+            setAutoReconnect(false);
+            setSerialDevice('');
+            setBaudRate(115200);
+            setDataBits(8);
+            setParity('None');
+            setStopBits(1);
+            setFlowControl('None');
+            setFtpSecurity('None');
+            setPassive(true);
+            setPreset(DEFAULT_SSH_ADVANCED.preset);
+            setCompression(DEFAULT_SSH_ADVANCED.compression);
+            setKeepaliveInput(String(DEFAULT_SSH_ADVANCED.keepalive_interval_secs));
+            setTimeoutInput(String(DEFAULT_SSH_ADVANCED.connect_timeout_secs));
         }
+        setShowAdvanced(false);
+        setTestReport(null);
     }, [editConnection, isOpen]);
 
+    // 选择串口类型时加载可用串口
+    useEffect(() => {
+        if (!isOpen || connectionType !== ConnectionType.Serial) return;
+        invoke<SerialPortEntry[]>('list_serial_ports')
+            .then(setSerialPorts)
+            .catch((error) => console.error('[ConnectionDialog] Failed to list serial ports:', error));
+    }, [isOpen, connectionType]);
+
+    // 各协议的默认端口
+    const defaultPort = (type: ConnectionType, security: FtpSecurity): string => {
+        if (type === ConnectionType.Telnet) return '23';
+        if (type === ConnectionType.Ftp) return security === 'Implicit' ? '990' : '21';
+        return '22';
+    };
+
     // Validate port on blur
     const handlePortBlur = () => {
         const portNum = parseInt(portInput, 10);
 
         if (portInput === '' || isNaN(portNum)) {
-            setPortInput('22');
+            setPortInput(defaultPort(connectionType, ftpSecurity));
         } else if (portNum < 1) {
             setPortInput('1');
         } else if (portNum > 65535) {
@@ -83,14 +192,82 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
         }
     };
 
+    // 切换连接类型时，端口仍为另一协议的默认值则同步切换
+    const handleTypeChange = (type: ConnectionType) => {
+        if (['22', '23', '21', '990'].includes(portInput)) {
+            setPortInput(defaultPort(type, ftpSecurity));
+        }
+        setConnectionType(type);
+    };
+
+    // 切换 FTP 加密方式时同步默认端口（隐式 FTPS 使用 990）
+    const handleSecurityChange = (security: FtpSecurity) => {
+        if (portInput === '21' || portInput === '990') {
+            setPortInput(defaultPort(ConnectionType.Ftp, security));
+        }
+        setFtpSecurity(security);
+    };
+
     // Get valid port number for saving
     const getPortNumber = (): number => {
         const portNum = parseInt(portInput, 10);
-        if (isNaN(portNum) || portNum < 1) return 22;
+        if (isNaN(portNum) || portNum < 1) return parseInt(defaultPort(connectionType, ftpSecurity), 10);
         if (portNum > 65535) return 65535;
         return portNum;
     };
 
+    // 解析非负整数秒数，无效时使用默认值
+    const parseSeconds = (input: string, fallback: number): number => {
+        const value = parseInt(input, 10);
+        return isNaN(value) || value < 0 ? fallback : value;
+    };
+
+    const buildSshConfig = (): SshConfig => {
+        const auth = authType === 'password'
+            ? createPasswordAuth(password)
+            : authType === 'publickey'
+                ? createPublicKeyAuth(privateKeyPath.trim(), passphrase.trim() || undefined)
+                : createAgentAuth(agentSocketPath.trim() || undefined);
+
+        return {
+            host: host.trim(),
+            port: getPortNumber(),
+            username: username.trim(),
+            auth,
+            // 跳板机暂无编辑界面，编辑时保留已有配置
+            jump_hosts: editConnection?.ssh_config?.jump_hosts,
+            // 自定义算法列表暂无编辑界面，编辑时保留已有配置
+            advanced: {
+                ...DEFAULT_SSH_ADVANCED,
+                ...editConnection?.ssh_config?.advanced,
+                preset,
+                compression,
+                keepalive_interval_secs: parseSeconds(keepaliveInput, DEFAULT_SSH_ADVANCED.keepalive_interval_secs),
+                connect_timeout_secs: parseSeconds(timeoutInput, DEFAULT_SSH_ADVANCED.connect_timeout_secs),
+            },
+        };
+    };
+
+    // 测试连接：不打开 shell，只返回诊断报告
+    const handleTest = async () => {
+        if (!host.trim() || !username.trim()) {
+            alert('Please enter host address and username');
+            return;
+        }
+
+        setTesting(true);
+        setTestReport(null);
+        try {
+            const report = await invoke<DiagnosticReport>('test_ssh_connection', { config: buildSshConfig() });
+            setTestReport(report);
+        } catch (error) {
+            console.error('[ConnectionDialog] Connection test failed:', error);
+            alert(`Connection test failed: ${error}`);
+        } finally {
+            setTesting(false);
+        }
+    };
+
     const handleSave = () => {
         if (!name.trim()) {
             alert('Please enter a connection name');
@@ -120,18 +297,54 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                 return;
             }
 
-            const auth = authType === 'password'
-                ? createPasswordAuth(password)
-                : createPublicKeyAuth(privateKeyPath.trim(), passphrase.trim() || undefined);
+            // 编辑时保留已有的重连参数
+            const reconnect = autoReconnect
+                ? (editConnection?.reconnect ?? DEFAULT_RECONNECT_POLICY)
+                : null;
+            onSave(name.trim(), connectionType, buildSshConfig(), reconnect);
+        } else if (connectionType === ConnectionType.Telnet) {
+            if (!host.trim()) {
+                alert('Please enter host address');
+                return;
+            }
 
-            const sshConfig: SshConfig = {
+            // 编辑时保留终端类型等其他设置
+            const telnetConfig: TelnetConfig = {
+                ...editConnection?.telnet_config,
                 host: host.trim(),
-                port,
-                username: username.trim(),
-                auth,
+                port: getPortNumber(),
             };
+            onSave(name.trim(), connectionType, undefined, null, telnetConfig);
+        } else if (connectionType === ConnectionType.Serial) {
+            if (!serialDevice.trim()) {
+                alert('Please select a serial device');
+                return;
+            }
 
-            onSave(name.trim(), connectionType, sshConfig);
+            const serialConfig: SerialConfig = {
+                device: serialDevice.trim(),
+                baud_rate: baudRate,
+                data_bits: dataBits,
+                parity,
+                stop_bits: stopBits,
+                flow_control: flowControl,
+            };
+            onSave(name.trim(), connectionType, undefined, null, undefined, serialConfig);
+        } else if (connectionType === ConnectionType.Ftp) {
+            if (!host.trim()) {
+                alert('Please enter host address');
+                return;
+            }
+
+            const ftpConfig: FtpConfig = {
+                host: host.trim(),
+                port: getPortNumber(),
+                username: username.trim(),
+                password,
+                security: ftpSecurity,
+                passive,
+            };
+            onSave(name.trim(), connectionType, undefined, null, undefined, undefined, ftpConfig);
         } else {
             onSave(name.trim(), connectionType);
         }
@@ -145,6 +358,7 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
         setPassword('');
         setPrivateKeyPath('');
         setPassphrase('');
+        setAgentSocketPath('');
         onClose();
     };
 
@@ -180,7 +394,7 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                     </label>
                     <div className="flex gap-2">
                         <button
-                            onClick={() => setConnectionType(ConnectionType.Local)}
+                            onClick={() => handleTypeChange(ConnectionType.Local)}
                             className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${connectionType === ConnectionType.Local
                                 ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
                                 : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
@@ -189,7 +403,7 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                             Local Terminal
                         </button>
                         <button
-                            onClick={() => setConnectionType(ConnectionType.SSH)}
+                            onClick={() => handleTypeChange(ConnectionType.SSH)}
                             className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${connectionType === ConnectionType.SSH
                                 ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
                                 : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
@@ -197,9 +411,247 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                         >
                             SSH
                         </button>
+                        <button
+                            onClick={() => handleTypeChange(ConnectionType.Telnet)}
+                            className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${connectionType === ConnectionType.Telnet
+                                ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
+                                : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
+                                }`}
+                        >
+                            Telnet
+                        </button>
+                        <button
+                            onClick={() => handleTypeChange(ConnectionType.Serial)}
+                            className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${connectionType === ConnectionType.Serial
+                                ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
+                                : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
+                                }`}
+                        >
+                            Serial
+                        </button>
+                        <button
+                            onClick={() => handleTypeChange(ConnectionType.Ftp)}
+                            className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${connectionType === ConnectionType.Ftp
+                                ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
+                                : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
+                                }`}
+                        >
+                            FTP
+                        </button>
                     </div>
                 </div>
 
+                {/* Telnet Config Form */}
+                {connectionType === ConnectionType.Telnet && (
+                    <div className="grid grid-cols-3 gap-3 mb-4">
+                        <div className="col-span-2">
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Host
+                            </label>
+                            <input
+                                type="text"
+                                value={host}
+                                onChange={(e) => setHost(e.target.value)}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                placeholder="e.g., 192.168.1.1"
+                            />
+                        </div>
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Port
+                            </label>
+                            <input
+                                type="text"
+                                value={portInput}
+                                onChange={(e) => setPortInput(e.target.value)}
+                                onBlur={handlePortBlur}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                            />
+                        </div>
+                    </div>
+                )}
+
+                {/* Serial Config Form */}
+                {connectionType === ConnectionType.Serial && (
+                    <div className="space-y-4 mb-4">
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Device
+                            </label>
+                            <input
+                                type="text"
+                                list="serial-ports"
+                                value={serialDevice}
+                                onChange={(e) => setSerialDevice(e.target.value)}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                placeholder="e.g., /dev/ttyUSB0 or COM3"
+                            />
+                            <datalist id="serial-ports">
+                                {serialPorts.map((port) => (
+                                    <option key={port.name} value={port.name}>
+                                        {port.description ? `${port.port_type} - ${port.description}` : port.port_type}
+                                    </option>
+                                ))}
+                            </datalist>
+                        </div>
+                        <div className="grid grid-cols-2 gap-3">
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Baud Rate
+                                </label>
+                                <select
+                                    value={baudRate}
+                                    onChange={(e) => setBaudRate(parseInt(e.target.value, 10))}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                >
+                                    {[1200, 2400, 4800, 9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600].map((rate) => (
+                                        <option key={rate} value={rate}>{rate}</option>
+                                    ))}
+                                </select>
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Data Bits
+                                </label>
+                                <select
+                                    value={dataBits}
+                                    onChange={(e) => setDataBits(parseInt(e.target.value, 10))}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                >
+                                    {[5, 6, 7, 8].map((bits) => (
+                                        <option key={bits} value={bits}>{bits}</option>
+                                    ))}
+                                </select>
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Parity
+                                </label>
+                                <select
+                                    value={parity}
+                                    onChange={(e) => setParity(e.target.value as SerialParity)}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                >
+                                    <option value="None">None</option>
+                                    <option value="Odd">Odd</option>
+                                    <option value="Even">Even</option>
+                                </select>
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Stop Bits
+                                </label>
+                                <select
+                                    value={stopBits}
+                                    onChange={(e) => setStopBits(parseInt(e.target.value, 10))}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                >
+                                    <option value={1}>1</option>
+                                    <option value={2}>2</option>
+                                </select>
+                            </div>
+                        </div>
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Flow Control
+                            </label>
+                            <select
+                                value={flowControl}
+                                onChange={(e) => setFlowControl(e.target.value as SerialFlowControl)}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                            >
+                                <option value="None">None</option>
+                                <option value="Software">Software (XON/XOFF)</option>
+                                <option value="Hardware">Hardware (RTS/CTS)</option>
+                            </select>
+                        </div>
+                    </div>
+                )}
+
+                {/* FTP Config Form */}
+                {connectionType === ConnectionType.Ftp && (
+                    <div className="space-y-4 mb-4">
+                        <div className="grid grid-cols-3 gap-3">
+                            <div className="col-span-2">
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Host
+                                </label>
+                                <input
+                                    type="text"
+                                    value={host}
+                                    onChange={(e) => setHost(e.target.value)}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                    placeholder="e.g., ftp.example.com"
+                                />
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Port
+                                </label>
+                                <input
+                                    type="text"
+                                    value={portInput}
+                                    onChange={(e) => setPortInput(e.target.value)}
+                                    onBlur={handlePortBlur}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                />
+                            </div>
+                        </div>
+
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Encryption
+                            </label>
+                            <select
+                                value={ftpSecurity}
+                                onChange={(e) => handleSecurityChange(e.target.value as FtpSecurity)}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                            >
+                                <option value="None">None (plain FTP)</option>
+                                <option value="Explicit">Explicit FTPS (AUTH TLS)</option>
+                                <option value="Implicit">Implicit FTPS</option>
+                            </select>
+                        </div>
+
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Username
+                            </label>
+                            <input
+                                type="text"
+                                value={username}
+                                onChange={(e) => setUsername(e.target.value)}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                placeholder="Leave empty for anonymous"
+                            />
+                        </div>
+
+                        <div>
+                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                Password
+                            </label>
+                            <input
+                                type="password"
+                                value={secretInputValue(password)}
+                                onChange={(e) => setPassword(editSecret(password, e.target.value))}
+                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                placeholder="Enter password"
+                                title={isSecretRef(password) ? 'Stored in credential vault, type to replace' : undefined}
+                            />
+                        </div>
+
+                        <label className="flex items-center gap-2 text-sm text-gray-300 cursor-pointer">
+                            <input
+                                type="checkbox"
+                                checked={passive}
+                                onChange={(e) => setPassive(e.target.checked)}
+                                className="accent-cyan-500"
+                            />
+                            Passive mode
+                        </label>
+                    </div>
+                )}
+
                 {/* SSH Config Form */}
                 {connectionType === ConnectionType.SSH && (
                     <div className="space-y-4 mb-4">
@@ -268,6 +720,15 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                                 >
                                     Public Key
                                 </button>
+                                <button
+                                    onClick={() => setAuthType('agent')}
+                                    className={`flex-1 px-3 py-2 rounded-lg text-sm font-medium transition-colors ${authType === 'agent'
+                                        ? 'bg-cyan-500/20 text-cyan-400 border border-cyan-500'
+                                        : 'bg-[#0D0D11] text-gray-400 border border-gray-700 hover:border-gray-600'
+                                        }`}
+                                >
+                                    SSH Agent
+                                </button>
                             </div>
                         </div>
 
@@ -279,10 +740,11 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                                 </label>
                                 <input
                                     type="password"
-                                    value={password}
-                                    onChange={(e) => setPassword(e.target.value)}
+                                    value={secretInputValue(password)}
+                                    onChange={(e) => setPassword(editSecret(password, e.target.value))}
                                     className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
                                     placeholder="Enter password"
+                                    title={isSecretRef(password) ? 'Stored in credential vault, type to replace' : undefined}
                                 />
                             </div>
                         )}
@@ -312,14 +774,149 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                                     </label>
                                     <input
                                         type="password"
-                                        value={passphrase}
-                                        onChange={(e) => setPassphrase(e.target.value)}
+                                        value={secretInputValue(passphrase)}
+                                        onChange={(e) => setPassphrase(editSecret(passphrase, e.target.value))}
+                                        title={isSecretRef(passphrase) ? 'Stored in credential vault, type to replace' : undefined}
                                         className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
                                         placeholder="Enter if key is password-protected"
                                     />
                                 </div>
                             </>
                         )}
+
+                        {/* SSH Agent Auth */}
+                        {authType === 'agent' && (
+                            <div>
+                                <label className="block text-sm font-medium text-gray-300 mb-2">
+                                    Agent Socket (Optional)
+                                </label>
+                                <input
+                                    type="text"
+                                    value={agentSocketPath}
+                                    onChange={(e) => setAgentSocketPath(e.target.value)}
+                                    className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                    placeholder="Defaults to $SSH_AUTH_SOCK"
+                                />
+                                <p className="text-xs text-gray-500 mt-1">
+                                    Uses identities from ssh-agent or a password manager's agent
+                                </p>
+                            </div>
+                        )}
+
+                        {/* Auto Reconnect */}
+                        <label className="flex items-center gap-2 text-sm text-gray-300 cursor-pointer">
+                            <input
+                                type="checkbox"
+                                checked={autoReconnect}
+                                onChange={(e) => setAutoReconnect(e.target.checked)}
+                                className="accent-cyan-500"
+                            />
+                            Reconnect automatically when the connection drops
+                        </label>
+
+                        {/* Advanced Settings */}
+                        <div>
+                            <button
+                                type="button"
+                                onClick={() => setShowAdvanced(!showAdvanced)}
+                                className="text-sm text-gray-400 hover:text-cyan-400 transition-colors"
+                            >
+                                {showAdvanced ? '▾' : '▸'} Advanced
+                            </button>
+                            {showAdvanced && (
+                                <div className="mt-3 space-y-3">
+                                    <div>
+                                        <label className="block text-sm font-medium text-gray-300 mb-2">
+                                            Algorithms
+                                        </label>
+                                        <select
+                                            value={preset}
+                                            onChange={(e) => setPreset(e.target.value as AlgorithmPreset)}
+                                            className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                        >
+                                            <option value="Compatible">Compatible (includes legacy SHA1 key exchange)</option>
+                                            <option value="Modern">Modern only (no SHA1)</option>
+                                        </select>
+                                    </div>
+                                    <div className="grid grid-cols-2 gap-3">
+                                        <div>
+                                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                                Keepalive (s)
+                                            </label>
+                                            <input
+                                                type="number"
+                                                min={0}
+                                                value={keepaliveInput}
+                                                onChange={(e) => setKeepaliveInput(e.target.value)}
+                                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                            />
+                                        </div>
+                                        <div>
+                                            <label className="block text-sm font-medium text-gray-300 mb-2">
+                                                Connect Timeout (s)
+                                            </label>
+                                            <input
+                                                type="number"
+                                                min={0}
+                                                value={timeoutInput}
+                                                onChange={(e) => setTimeoutInput(e.target.value)}
+                                                className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white focus:outline-none focus:border-cyan-500"
+                                            />
+                                        </div>
+                                    </div>
+                                    <p className="text-xs text-gray-500">0 disables keepalive / the timeout</p>
+                                    <label className="flex items-center gap-2 text-sm text-gray-300 cursor-pointer">
+                                        <input
+                                            type="checkbox"
+                                            checked={compression}
+                                            onChange={(e) => setCompression(e.target.checked)}
+                                            className="accent-cyan-500"
+                                        />
+                                        Enable compression
+                                    </label>
+                                </div>
+                            )}
+                        </div>
+
+                        {/* Connection Test Report */}
+                        {testReport && (
+                            <div className={`p-3 rounded-lg border text-xs font-mono space-y-1 ${testReport.error
+                                ? 'border-red-500/50 bg-red-500/10'
+                                : 'border-green-500/50 bg-green-500/10'
+                                }`}>
+                                <div className={testReport.error ? 'text-red-400' : 'text-green-400'}>
+                                    {testReport.error ?? 'Connection and authentication succeeded'}
+                                </div>
+                                {testReport.jump_hosts.length > 0 && (
+                                    <div className="text-gray-400">Via: {testReport.jump_hosts.join(' → ')}</div>
+                                )}
+                                {testReport.dns_ms !== null && (
+                                    <div className="text-gray-400">
+                                        DNS: {testReport.resolved_addresses.join(', ')} ({testReport.dns_ms} ms)
+                                    </div>
+                                )}
+                                {testReport.tcp_connect_ms !== null && (
+                                    <div className="text-gray-400">TCP connect: {testReport.tcp_connect_ms} ms</div>
+                                )}
+                                {testReport.server_banner && (
+                                    <div className="text-gray-400">Server: {testReport.server_banner}</div>
+                                )}
+                                {testReport.algorithms && (
+                                    <div className="text-gray-400">
+                                        Algorithms: {testReport.algorithms.kex}, {testReport.algorithms.host_key}, {testReport.algorithms.cipher}, {testReport.algorithms.client_mac}
+                                    </div>
+                                )}
+                                {testReport.host_key_fingerprint && (
+                                    <div className="text-gray-400 break-all">
+                                        Host key: {testReport.host_key_type} {testReport.host_key_fingerprint}
+                                        {testReport.host_key_status && ` (${testReport.host_key_status.status})`}
+                                    </div>
+                                )}
+                                {testReport.auth_methods.length > 0 && (
+                                    <div className="text-gray-400">Auth methods: {testReport.auth_methods.join(', ')}</div>
+                                )}
+                            </div>
+                        )}
                     </div>
                 )}
 
@@ -331,6 +928,15 @@ export const ConnectionDialog: React.FC<ConnectionDialogProps> = ({
                     >
                         Cancel
                     </button>
+                    {connectionType === ConnectionType.SSH && (
+                        <button
+                            onClick={handleTest}
+                            disabled={testing}
+                            className="flex-1 px-4 py-2 bg-[#0D0D11] border border-gray-700 hover:border-cyan-500 text-gray-300 rounded-lg transition-colors disabled:opacity-50"
+                        >
+                            {testing ? 'Testing...' : 'Test'}
+                        </button>
+                    )}
                     <button
                         onClick={handleSave}
                         className="flex-1 px-4 py-2 bg-gradient-to-r from-cyan-500 to-blue-600 hover:from-cyan-600 hover:to-blue-700 text-white rounded-lg transition-all"
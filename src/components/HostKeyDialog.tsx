@@ -0,0 +1,106 @@
+import { HostKeyDecision, HostKeyPromptPayload } from '../types/hostkey';
+
+interface HostKeyDialogProps {
+    /** 主机密钥提示数据，为 null 时不显示 */
+    promptData: HostKeyPromptPayload | null;
+    /** 用户做出选择的回调 */
+    onDecision: (decision: HostKeyDecision) => void;
+}
+
+/**
+ * 主机密钥确认对话框组件
+ * 首次连接未知主机时显示服务器指纹，由用户决定是否信任
+ */
+export const HostKeyDialog: React.FC<HostKeyDialogProps> = ({
+    promptData,
+    onDecision,
+}) => {
+    if (!promptData) {
+        return null;
+    }
+
+    const hostLabel = promptData.port === 22
+        ? promptData.host
+        : `[${promptData.host}]:${promptData.port}`;
+
+    const handleKeyDown = (e: React.KeyboardEvent) => {
+        if (e.key === 'Escape') {
+            onDecision('Reject');
+        }
+    };
+
+    return (
+        <div
+            className="fixed inset-0 z-50 flex items-center justify-center bg-black/60 backdrop-blur-sm"
+            onKeyDown={handleKeyDown}
+        >
+            <div className="bg-[#1a1a24] rounded-xl shadow-2xl border border-gray-700 w-full max-w-md mx-4 overflow-hidden">
+                {/* Header */}
+                <div className="px-6 py-4 border-b border-gray-700 bg-gradient-to-r from-amber-600/20 to-orange-600/20">
+                    <div className="flex items-center space-x-3">
+                        <div className="w-10 h-10 rounded-full bg-amber-500/20 flex items-center justify-center">
+                            <span className="text-xl">🔑</span>
+                        </div>
+                        <div>
+                            <h2 className="text-lg font-semibold text-white">Unknown Host</h2>
+                            <p className="text-sm text-gray-400 mt-0.5">
+                                The authenticity of {hostLabel} can't be established.
+                            </p>
+                        </div>
+                    </div>
+                </div>
+
+                {/* Fingerprint */}
+                <div className="p-6 space-y-2">
+                    <label className="block text-sm font-medium text-gray-300">
+                        {promptData.key_type} key fingerprint
+                    </label>
+                    <div className="px-4 py-3 bg-[#0d0d11] border border-gray-600 rounded-lg text-cyan-300 font-mono text-xs break-all select-all">
+                        {promptData.fingerprint}
+                    </div>
+
+                    {/* Buttons */}
+                    <div className="flex space-x-3 pt-4">
+                        <button
+                            type="button"
+                            onClick={() => onDecision('Reject')}
+                            className="flex-1 px-4 py-3 bg-gray-700 hover:bg-gray-600 
+                                text-gray-300 rounded-lg font-medium
+                                transition-colors duration-200"
+                        >
+                            Reject
+                        </button>
+                        <button
+                            type="button"
+                            onClick={() => onDecision('AcceptOnce')}
+                            className="flex-1 px-4 py-3 bg-gray-700 hover:bg-gray-600 
+                                text-white rounded-lg font-medium
+                                transition-colors duration-200"
+                        >
+                            Once
+                        </button>
+                        <button
+                            type="button"
+                            autoFocus
+                            onClick={() => onDecision('AcceptAndSave')}
+                            className="flex-1 px-4 py-3 bg-gradient-to-r from-cyan-500 to-blue-500 
+                                hover:from-cyan-400 hover:to-blue-400
+                                text-white rounded-lg font-medium
+                                transition-all duration-200 shadow-lg shadow-cyan-500/25"
+                        >
+                            Trust
+                        </button>
+                    </div>
+                </div>
+
+                {/* Footer hint */}
+                <div className="px-6 py-3 bg-gray-800/50 border-t border-gray-700">
+                    <p className="text-xs text-gray-500 text-center">
+                        Trusted keys are saved to Konnect's known_hosts. Press{' '}
+                        <kbd className="px-1.5 py-0.5 bg-gray-700 rounded text-gray-400">Esc</kbd> to reject
+                    </p>
+                </div>
+            </div>
+        </div>
+    );
+};
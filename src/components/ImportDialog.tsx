@@ -0,0 +1,234 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Connection } from '../types/connection';
+import { ImportPreview, ImportSource } from '../types/import';
+
+const SOURCES: { value: ImportSource; label: string; placeholder: string; extensions?: string[]; directory?: boolean }[] = [
+    { value: 'SshConfig', label: 'OpenSSH config', placeholder: '~/.ssh/config' },
+    { value: 'Putty', label: 'PuTTY (.reg)', placeholder: 'Registry export', extensions: ['reg'] },
+    { value: 'MobaXterm', label: 'MobaXterm (.mxtsessions)', placeholder: 'Sessions export', extensions: ['mxtsessions'] },
+    { value: 'Xshell', label: 'Xshell (.xsh / folder)', placeholder: 'Session file or Sessions folder', extensions: ['xsh'], directory: true },
+    { value: 'Termius', label: 'Termius (JSON)', placeholder: 'Exported JSON', extensions: ['json'] },
+];
+
+interface ImportDialogProps {
+    /** 是否显示对话框 */
+    isOpen: boolean;
+    onClose: () => void;
+    /** 导入完成回调 */
+    onImported: (connections: Connection[]) => void;
+}
+
+/**
+ * 从 OpenSSH config 或其他终端工具的导出文件导入连接
+ * 先预览每个条目的映射结果与不支持的选项，再导入勾选的条目
+ */
+export const ImportDialog: React.FC<ImportDialogProps> = ({
+    isOpen,
+    onClose,
+    onImported,
+}) => {
+    const [source, setSource] = useState<ImportSource>('SshConfig');
+    const [path, setPath] = useState('');
+    const [preview, setPreview] = useState<ImportPreview | null>(null);
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+    const [error, setError] = useState<string | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    const sourceInfo = SOURCES.find((s) => s.value === source)!;
+
+    const loadPreview = async () => {
+        // 除 OpenSSH config 外需要先选择文件
+        if (source !== 'SshConfig' && !path.trim()) {
+            setPreview(null);
+            return;
+        }
+        setBusy(true);
+        setError(null);
+        try {
+            const result = await invoke<ImportPreview>('import_preview', { source, path: path.trim() || null });
+            setPreview(result);
+            setSelected(new Set(result.entries.filter((e) => !e.duplicate).map((e) => e.source_name)));
+        } catch (err) {
+            setPreview(null);
+            setError(String(err));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    useEffect(() => {
+        if (isOpen) {
+            loadPreview();
+        }
+    }, [isOpen, source]);
+
+    if (!isOpen) {
+        return null;
+    }
+
+    const toggle = (name: string) => {
+        const next = new Set(selected);
+        if (next.has(name)) {
+            next.delete(name);
+        } else {
+            next.add(name);
+        }
+        setSelected(next);
+    };
+
+    const handleSourceChange = (value: ImportSource) => {
+        setSource(value);
+        setPath('');
+        setPreview(null);
+        setSelected(new Set());
+    };
+
+    const handleBrowse = async (directory: boolean) => {
+        const picked = await open({
+            directory,
+            title: `Select ${sourceInfo.label}`,
+            filters: directory || !sourceInfo.extensions ? undefined : [{ name: sourceInfo.label, extensions: sourceInfo.extensions }],
+        });
+        if (typeof picked === 'string') {
+            setPath(picked);
+        }
+    };
+
+    const handleImport = async () => {
+        setBusy(true);
+        setError(null);
+        try {
+            const imported = await invoke<Connection[]>('import_from_source', {
+                source,
+                path: path.trim() || null,
+                names: Array.from(selected),
+            });
+            onImported(imported);
+            onClose();
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/60 backdrop-blur-sm">
+            <div className="bg-[#1a1a24] rounded-xl shadow-2xl border border-gray-700 w-full max-w-2xl mx-4 overflow-hidden flex flex-col max-h-[80vh]">
+                <div className="px-6 py-4 border-b border-gray-700">
+                    <h2 className="text-lg font-semibold text-white">Import Connections</h2>
+                    <div className="flex gap-2 mt-3">
+                        <select
+                            value={source}
+                            onChange={(e) => handleSourceChange(e.target.value as ImportSource)}
+                            className="px-2 py-1.5 bg-[#0D0D11] border border-gray-700 rounded-lg text-white text-sm focus:outline-none focus:border-cyan-500"
+                        >
+                            {SOURCES.map((s) => (
+                                <option key={s.value} value={s.value}>{s.label}</option>
+                            ))}
+                        </select>
+                        <input
+                            type="text"
+                            value={path}
+                            onChange={(e) => setPath(e.target.value)}
+                            placeholder={sourceInfo.placeholder}
+                            className="flex-1 px-3 py-1.5 bg-[#0D0D11] border border-gray-700 rounded-lg text-white text-sm focus:outline-none focus:border-cyan-500"
+                        />
+                        <button
+                            onClick={() => handleBrowse(false)}
+                            className="px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-lg transition-colors"
+                        >
+                            Browse…
+                        </button>
+                        {sourceInfo.directory && (
+                            <button
+                                onClick={() => handleBrowse(true)}
+                                className="px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-lg transition-colors"
+                                title="Select folder"
+                            >
+                                📁
+                            </button>
+                        )}
+                        <button
+                            onClick={loadPreview}
+                            disabled={busy}
+                            className="px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 disabled:opacity-50 text-white rounded-lg transition-colors"
+                        >
+                            Preview
+                        </button>
+                    </div>
+                </div>
+
+                <div className="flex-1 overflow-y-auto px-6 py-3 space-y-2">
+                    {error && <p className="text-sm text-red-400">{error}</p>}
+                    {preview?.warnings.map((warning, i) => (
+                        <p key={i} className="text-xs text-yellow-400">⚠ {warning}</p>
+                    ))}
+                    {preview && preview.entries.length === 0 && (
+                        <p className="text-sm text-gray-500 text-center py-6">No sessions found</p>
+                    )}
+                    {preview?.entries.map((entry) => {
+                        const ssh = entry.connection.ssh_config;
+                        const target = ssh
+                            ? `${ssh.username}@${ssh.host}:${ssh.port}`
+                            : entry.connection.telnet_config
+                                ? `telnet ${entry.connection.telnet_config.host}:${entry.connection.telnet_config.port}`
+                                : entry.connection.ftp_config
+                                    ? `ftp ${entry.connection.ftp_config.host}:${entry.connection.ftp_config.port}`
+                                    : entry.connection.serial_config
+                                        ? `serial ${entry.connection.serial_config.device} @ ${entry.connection.serial_config.baud_rate}`
+                                        : '';
+                        const clean = entry.unsupported.length === 0 && entry.warnings.length === 0;
+                        return (
+                            <label key={entry.source_name} className="flex items-start gap-3 p-2 rounded-lg hover:bg-white/5 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={selected.has(entry.source_name)}
+                                    onChange={() => toggle(entry.source_name)}
+                                    className="mt-1"
+                                />
+                                <div className="flex-1 min-w-0">
+                                    <div className="text-sm text-gray-200 flex items-center gap-2">
+                                        <span className="font-medium">{entry.source_name}</span>
+                                        {target && (
+                                            <span className="text-xs text-gray-500 truncate">
+                                                {target}
+                                                {ssh?.jump_hosts && ssh.jump_hosts.length > 0 && ` via ${ssh.jump_hosts.map((j) => j.host).join(' → ')}`}
+                                            </span>
+                                        )}
+                                        {entry.duplicate && <span className="text-[10px] px-1.5 rounded bg-gray-700 text-gray-300">exists</span>}
+                                        {clean && <span className="text-[10px] text-green-400">✓</span>}
+                                    </div>
+                                    {entry.unsupported.length > 0 && (
+                                        <div className="text-xs text-yellow-500">Unsupported: {entry.unsupported.join(', ')}</div>
+                                    )}
+                                    {entry.warnings.map((warning, i) => (
+                                        <div key={i} className="text-xs text-yellow-400">{warning}</div>
+                                    ))}
+                                </div>
+                            </label>
+                        );
+                    })}
+                </div>
+
+                <div className="px-6 py-3 border-t border-gray-700 flex justify-end space-x-2">
+                    <button
+                        onClick={onClose}
+                        className="px-4 py-2 text-sm text-gray-400 hover:text-white transition-colors"
+                    >
+                        Cancel
+                    </button>
+                    <button
+                        onClick={handleImport}
+                        disabled={busy || selected.size === 0}
+                        className="px-4 py-2 text-sm bg-cyan-600 hover:bg-cyan-500 disabled:opacity-50 text-white rounded-lg transition-colors"
+                    >
+                        Import {selected.size > 0 ? selected.size : ''}
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+};
@@ -78,6 +78,9 @@ export const MfaDialog: React.FC<MfaDialogProps> = ({
                             <h2 className="text-lg font-semibold text-white">
                                 {promptData.name || 'Multi-Factor Authentication'}
                             </h2>
+                            {promptData.host && (
+                                <p className="text-xs text-cyan-400 mt-0.5">{promptData.host}</p>
+                            )}
                             {promptData.instructions && (
                                 <p className="text-sm text-gray-400 mt-0.5">
                                     {promptData.instructions}
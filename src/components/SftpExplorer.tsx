@@ -1,18 +1,25 @@
-import { useState, useEffect, useCallback } from 'react';
+import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { save, open } from '@tauri-apps/plugin-dialog';
-import { FileEntry } from '../types/sftp';
+import { FileDetails, FileEntry, InterruptedTransfer, QueuedTransfer, TransferProgress, TreeOptions, TreeSummary } from '../types/sftp';
 import { Connection } from '../types/connection';
+import { TransferQueuePanel } from './TransferQueuePanel';
+
+/** 文件浏览协议，决定建立会话的命令（sftp_connect / ftp_connect），其余操作统一使用 fs_* 命令 */
+export type FileProtocol = 'sftp' | 'ftp';
 
 interface SftpExplorerProps {
     sessionId: string;
     connection: Connection;
+    protocol?: FileProtocol;
     onClose: () => void;
 }
 
 export const SftpExplorer: React.FC<SftpExplorerProps> = ({
     sessionId,
     connection,
+    protocol = 'sftp',
     onClose
 }) => {
     const [currentPath, setCurrentPath] = useState('/');
@@ -20,30 +27,39 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState<string | null>(null);
     const [isConnected, setIsConnected] = useState(false);
-    const [transferring, setTransferring] = useState<string | null>(null);
+    const [interrupted, setInterrupted] = useState<InterruptedTransfer[]>([]);
+    const [details, setDetails] = useState<FileDetails | null>(null);
+    const [folderTransfer, setFolderTransfer] = useState<{ id: string; label: string; progress?: TransferProgress } | null>(null);
+
+    // 父组件每次渲染都会传入新的 connection 对象，放入 ref 避免重复连接
+    const connectionRef = useRef(connection);
+    connectionRef.current = connection;
 
-    // Connect to SFTP
+    // Connect to SFTP / FTP
     const connect = useCallback(async () => {
         setLoading(true);
         setError(null);
         try {
-            await invoke('sftp_connect', { config: connection });
+            await invoke(`${protocol}_connect`, { config: connectionRef.current, sessionId });
             setIsConnected(true);
-            // Load root directory after connection
-            await loadDirectory('/');
+            // Start in the user's home directory (falls back to root)
+            const home = await invoke<string>('fs_canonicalize', { id: sessionId, path: '.' })
+                .catch(() => '/');
+            await loadDirectory(home);
+            loadInterrupted();
         } catch (err) {
             setError(`Connection failed: ${err}`);
         } finally {
             setLoading(false);
         }
-    }, [connection]);
+    }, [protocol, sessionId]);
 
     // Load directory
     const loadDirectory = useCallback(async (path: string) => {
         setLoading(true);
         setError(null);
         try {
-            const entries = await invoke<FileEntry[]>('sftp_list_dir', {
+            const entries = await invoke<FileEntry[]>('fs_list_dir', {
                 id: sessionId,
                 path
             });
@@ -79,85 +95,251 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
         loadDirectory(newPath);
     }, [currentPath, loadDirectory]);
 
-    // Download file
-    const handleDownload = useCallback(async (entry: FileEntry) => {
-        if (entry.is_dir) return;
+    // Interrupted transfers of this connection (survive app restarts)
+    const loadInterrupted = useCallback(async () => {
+        try {
+            const records = await invoke<InterruptedTransfer[]>('fs_list_interrupted_transfers');
+            setInterrupted(records.filter(r => r.connection_id === connectionRef.current.id));
+        } catch (err) {
+            console.error('Failed to load interrupted transfers:', err);
+        }
+    }, []);
 
+    // Queue a download or upload; transfers run in the background
+    const enqueue = useCallback(async (
+        direction: QueuedTransfer['direction'],
+        remotePath: string,
+        localPath: string,
+        transferId?: string
+    ) => {
         try {
-            // Select save location
-            const localPath = await save({
-                defaultPath: entry.name,
-                title: 'Save file to...',
+            await invoke<string>('transfer_enqueue', {
+                sessionId,
+                direction,
+                remotePath,
+                localPath,
+                transferId,
+                connectionId: connectionRef.current.id
             });
+            setError(null);
+        } catch (err) {
+            setError(`Failed to queue transfer: ${err}`);
+        }
+    }, [sessionId]);
 
-            if (!localPath) return; // User cancelled
+    // Resume an interrupted transfer from where it stopped
+    const handleResume = useCallback(async (record: InterruptedTransfer) => {
+        setInterrupted(prev => prev.filter(r => r.transfer_id !== record.transfer_id));
+        await enqueue(record.direction, record.remote_path, record.local_path, record.transfer_id);
+    }, [enqueue]);
 
-            setTransferring(`Downloading: ${entry.name}`);
-            const remotePath = currentPath === '/'
-                ? `/${entry.name}`
-                : `${currentPath}/${entry.name}`;
+    // Forget an interrupted transfer
+    const handleDiscard = useCallback(async (record: InterruptedTransfer) => {
+        try {
+            await invoke('fs_discard_interrupted_transfer', { transferId: record.transfer_id });
+        } catch (err) {
+            console.error('Failed to discard transfer:', err);
+        }
+        loadInterrupted();
+    }, [loadInterrupted]);
 
-            await invoke('sftp_download_file', {
+    // Copy a whole directory tree (runs outside the queue, one folder at a time)
+    const transferFolder = useCallback(async (
+        command: 'fs_download_dir' | 'fs_upload_dir',
+        label: string,
+        remotePath: string,
+        localPath: string
+    ) => {
+        const transferId = crypto.randomUUID();
+        const options: TreeOptions = { preserve_times: true, preserve_permissions: true, symlinks: 'Follow' };
+        setFolderTransfer({ id: transferId, label });
+        try {
+            const summary = await invoke<TreeSummary>(command, {
                 id: sessionId,
                 remotePath,
-                localPath
+                localPath,
+                transferId,
+                options
             });
-
-            setTransferring(null);
-            setError(null);
+            setError(summary.attr_failures > 0
+                ? `Transferred ${summary.files} files; could not preserve attributes of ${summary.attr_failures} entries`
+                : null);
+            if (command === 'fs_upload_dir') await loadDirectory(currentPath);
         } catch (err) {
-            setTransferring(null);
-            setError(`Download failed: ${err}`);
+            setError(`${label} failed: ${err}`);
+        } finally {
+            setFolderTransfer(null);
         }
-    }, [sessionId, currentPath]);
+    }, [sessionId, currentPath, loadDirectory]);
+
+    // Folder transfer progress
+    useEffect(() => {
+        if (!folderTransfer) return;
+        const unlisten = listen<TransferProgress>('sftp-transfer-progress', (event) => {
+            if (event.payload.transfer_id !== folderTransfer.id || event.payload.done) return;
+            setFolderTransfer(prev => prev && { ...prev, progress: event.payload });
+        });
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, [folderTransfer?.id]);
+
+    // Download file or folder
+    const handleDownload = useCallback(async (entry: FileEntry) => {
+        const remotePath = currentPath === '/'
+            ? `/${entry.name}`
+            : `${currentPath}/${entry.name}`;
+
+        if (entry.is_dir) {
+            const parent = await open({ directory: true, title: 'Download folder into...' });
+            if (!parent) return; // User cancelled
+            await transferFolder('fs_download_dir', `Downloading folder: ${entry.name}`, remotePath, `${parent}/${entry.name}`);
+            return;
+        }
+
+        // Select save location
+        const localPath = await save({
+            defaultPath: entry.name,
+            title: 'Save file to...',
+        });
+
+        if (!localPath) return; // User cancelled
+
+        await enqueue('Download', remotePath, localPath);
+    }, [currentPath, enqueue, transferFolder]);
 
     // Upload file
     const handleUpload = useCallback(async () => {
-        try {
-            // Select file to upload
-            const selected = await open({
-                multiple: false,
-                title: 'Select file to upload',
-            });
+        // Select file to upload
+        const selected = await open({
+            multiple: false,
+            title: 'Select file to upload',
+        });
 
-            if (!selected) return; // User cancelled
+        if (!selected) return; // User cancelled
 
-            const localPath = selected as string;
-            const fileName = localPath.split('/').pop() || 'uploaded_file';
-            const remotePath = currentPath === '/'
-                ? `/${fileName}`
-                : `${currentPath}/${fileName}`;
+        const localPath = selected as string;
+        const fileName = localPath.split(/[\\/]/).pop() || 'uploaded_file';
+        const remotePath = currentPath === '/'
+            ? `/${fileName}`
+            : `${currentPath}/${fileName}`;
+        await enqueue('Upload', remotePath, localPath);
+    }, [currentPath, enqueue]);
 
-            setTransferring(`Uploading: ${fileName}`);
+    // Upload folder
+    const handleUploadFolder = useCallback(async () => {
+        const selected = await open({ directory: true, title: 'Select folder to upload' });
+        if (!selected) return; // User cancelled
+
+        const localPath = selected as string;
+        const folderName = localPath.replace(/[\\/]+$/, '').split(/[\\/]/).pop() || 'uploaded_folder';
+        const remotePath = currentPath === '/'
+            ? `/${folderName}`
+            : `${currentPath}/${folderName}`;
+        await transferFolder('fs_upload_dir', `Uploading folder: ${folderName}`, remotePath, localPath);
+    }, [currentPath, transferFolder]);
+
+    // Refresh the listing when an upload into the current directory finishes
+    const currentPathRef = useRef(currentPath);
+    currentPathRef.current = currentPath;
+    const handleJobCompleted = useCallback((job: QueuedTransfer) => {
+        const dir = job.remote_path.substring(0, job.remote_path.lastIndexOf('/')) || '/';
+        if (job.direction === 'Upload' && dir === currentPathRef.current) {
+            loadDirectory(currentPathRef.current);
+        }
+    }, [loadDirectory]);
 
-            await invoke('sftp_upload_file', {
+    const entryPath = useCallback((entry: FileEntry) => (
+        currentPath === '/' ? `/${entry.name}` : `${currentPath}/${entry.name}`
+    ), [currentPath]);
+
+    // Rename / move (a path containing '/' moves the entry)
+    const handleRename = useCallback(async (entry: FileEntry) => {
+        const target = prompt(`Rename "${entry.name}" to:`, entry.name);
+        if (!target || target === entry.name) return;
+        const to = target.startsWith('/')
+            ? target
+            : currentPath === '/' ? `/${target}` : `${currentPath}/${target}`;
+        try {
+            await invoke('fs_rename', { id: sessionId, from: entryPath(entry), to });
+            await loadDirectory(currentPath);
+        } catch (err) {
+            setError(`Rename failed: ${err}`);
+        }
+    }, [sessionId, currentPath, entryPath, loadDirectory]);
+
+    // Change permissions (octal such as 755 or symbolic such as u+x,go-w)
+    const handleChmod = useCallback(async (entry: FileEntry) => {
+        const mode = prompt(`Permissions for "${entry.name}" (e.g. 755 or u+x,go-w):`, entry.permissions ?? '');
+        if (!mode) return;
+        try {
+            await invoke<number>('fs_chmod', { id: sessionId, path: entryPath(entry), mode });
+            await loadDirectory(currentPath);
+        } catch (err) {
+            setError(`Chmod failed: ${err}`);
+        }
+    }, [sessionId, currentPath, entryPath, loadDirectory]);
+
+    // Show owner, group, times and link target
+    const handleDetails = useCallback(async (entry: FileEntry) => {
+        try {
+            setDetails(await invoke<FileDetails>('fs_stat_details', { id: sessionId, path: entryPath(entry) }));
+        } catch (err) {
+            setError(`Failed to get details: ${err}`);
+        }
+    }, [sessionId, entryPath]);
+
+    // Change owner / group of the entry shown in the details panel
+    const handleChown = useCallback(async () => {
+        if (!details) return;
+        const value = prompt('New owner[:group] (name or numeric id):',
+            `${details.owner ?? details.uid ?? ''}:${details.group ?? details.gid ?? ''}`);
+        if (!value) return;
+        const [owner, group] = value.split(':');
+        try {
+            await invoke('fs_chown', {
                 id: sessionId,
-                localPath,
-                remotePath
+                path: details.path,
+                owner: owner || undefined,
+                group: group || undefined
             });
+            setDetails(await invoke<FileDetails>('fs_stat_details', { id: sessionId, path: details.path }));
+        } catch (err) {
+            setError(`Chown failed: ${err}`);
+        }
+    }, [sessionId, details]);
 
-            setTransferring(null);
-            setError(null);
-            // Refresh directory
+    // Create a symbolic link in the current directory
+    const handleSymlink = useCallback(async () => {
+        const target = prompt('Link target path:');
+        if (!target) return;
+        const name = prompt('Link name:', target.split('/').pop() || '');
+        if (!name) return;
+        const link = currentPath === '/' ? `/${name}` : `${currentPath}/${name}`;
+        try {
+            await invoke('fs_symlink', { id: sessionId, target, link });
             await loadDirectory(currentPath);
         } catch (err) {
-            setTransferring(null);
-            setError(`Upload failed: ${err}`);
+            setError(`Failed to create symlink: ${err}`);
         }
     }, [sessionId, currentPath, loadDirectory]);
 
     // Delete file/directory
     const handleDelete = useCallback(async (entry: FileEntry) => {
-        if (!confirm(`Are you sure you want to delete "${entry.name}"?`)) return;
+        const message = entry.is_dir
+            ? `Are you sure you want to delete "${entry.name}" and everything in it?`
+            : `Are you sure you want to delete "${entry.name}"?`;
+        if (!confirm(message)) return;
 
         try {
             const fullPath = currentPath === '/'
                 ? `/${entry.name}`
                 : `${currentPath}/${entry.name}`;
-            await invoke('sftp_remove', {
+            await invoke('fs_remove', {
                 id: sessionId,
                 path: fullPath,
-                isDir: entry.is_dir
+                isDir: entry.is_dir,
+                recursive: entry.is_dir
             });
             await loadDirectory(currentPath);
         } catch (err) {
@@ -178,7 +360,7 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
         connect();
         return () => {
             // Disconnect
-            invoke('sftp_disconnect', { id: sessionId }).catch(console.error);
+            invoke('fs_disconnect', { id: sessionId }).catch(console.error);
         };
     }, [connect, sessionId]);
 
@@ -188,7 +370,7 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
             <div className="flex items-center justify-between px-4 py-3 border-b border-gray-800">
                 <div className="flex items-center space-x-2">
                     <span className="text-lg">📁</span>
-                    <span className="text-sm font-medium text-white">SFTP File Manager</span>
+                    <span className="text-sm font-medium text-white">{protocol.toUpperCase()} File Manager</span>
                 </div>
                 <button
                     onClick={onClose}
@@ -213,13 +395,28 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
                 </div>
                 <button
                     onClick={handleUpload}
-                    disabled={!!transferring}
                     className="px-3 py-1 text-sm bg-cyan-600 hover:bg-cyan-500 text-white rounded disabled:opacity-50 flex items-center gap-1"
                     title="Upload file"
                 >
                     <span>📤</span>
                     <span>Upload</span>
                 </button>
+                <button
+                    onClick={handleUploadFolder}
+                    disabled={!!folderTransfer}
+                    className="px-3 py-1 text-sm bg-cyan-700 hover:bg-cyan-600 text-white rounded disabled:opacity-50 flex items-center gap-1"
+                    title="Upload folder"
+                >
+                    <span>📁</span>
+                    <span>Folder</span>
+                </button>
+                <button
+                    onClick={handleSymlink}
+                    className="px-2 py-1 text-sm bg-gray-800 hover:bg-gray-700 text-gray-300 rounded"
+                    title="New symbolic link"
+                >
+                    🔗
+                </button>
                 <button
                     onClick={() => loadDirectory(currentPath)}
                     className="px-2 py-1 text-sm bg-gray-800 hover:bg-gray-700 text-gray-300 rounded"
@@ -229,14 +426,73 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
                 </button>
             </div>
 
-            {/* Transfer status */}
-            {transferring && (
+            {/* Details */}
+            {details && (
+                <div className="px-4 py-2 bg-[#16161B] border-b border-gray-800 text-xs text-gray-300 space-y-0.5">
+                    <div className="flex items-center">
+                        <span className="flex-1 font-mono truncate text-white">{details.path}</span>
+                        <button onClick={handleChown} className="ml-2 text-cyan-400 hover:text-cyan-300">Change owner</button>
+                        <button onClick={() => setDetails(null)} className="ml-3 text-gray-400 hover:text-white">✕</button>
+                    </div>
+                    <div>
+                        {details.is_symlink ? 'Symbolic link' : details.is_dir ? 'Directory' : 'File'}
+                        {details.link_target && ` → ${details.link_target}`}
+                        {!details.is_dir && ` · ${formatSize(details.size)}`}
+                        {details.permissions && ` · ${details.permissions}`}
+                    </div>
+                    <div>
+                        Owner: {details.owner ?? '?'}{details.uid !== undefined && details.uid !== null && ` (${details.uid})`}
+                        {' · '}Group: {details.group ?? '?'}{details.gid !== undefined && details.gid !== null && ` (${details.gid})`}
+                    </div>
+                    <div>
+                        Modified: {details.modified ? new Date(details.modified * 1000).toLocaleString() : '?'}
+                        {' · '}Accessed: {details.accessed ? new Date(details.accessed * 1000).toLocaleString() : '?'}
+                    </div>
+                </div>
+            )}
+
+            {/* Folder transfer */}
+            {folderTransfer && (
                 <div className="px-4 py-2 bg-blue-500/20 text-blue-400 text-sm flex items-center">
                     <span className="animate-spin mr-2">⏳</span>
-                    {transferring}
+                    <span className="flex-1 truncate">{folderTransfer.label}</span>
+                    {folderTransfer.progress && (
+                        <span className="ml-2 text-xs text-blue-300 whitespace-nowrap">
+                            {folderTransfer.progress.files_done ?? 0}/{folderTransfer.progress.files_total ?? 0} files
+                            {' · '}{formatSize(folderTransfer.progress.bytes)}
+                            {folderTransfer.progress.total ? ` of ${formatSize(folderTransfer.progress.total)}` : ''}
+                        </span>
+                    )}
+                    <button
+                        onClick={() => invoke('sftp_cancel_transfer', { transferId: folderTransfer.id }).catch(console.error)}
+                        className="ml-3 px-2 py-0.5 text-xs rounded bg-gray-700 hover:bg-gray-600 text-gray-200"
+                    >
+                        Cancel
+                    </button>
                 </div>
             )}
 
+            {/* Interrupted transfers */}
+            {interrupted.map(record => (
+                <div key={record.transfer_id} className="px-4 py-2 bg-yellow-500/10 text-yellow-400 text-sm flex items-center">
+                    <span className="flex-1 truncate">
+                        Interrupted {record.direction === 'Download' ? 'download' : 'upload'}: {record.remote_path}
+                    </span>
+                    <button
+                        onClick={() => handleResume(record)}
+                        className="ml-3 px-2 py-0.5 text-xs rounded bg-blue-600 hover:bg-blue-500 text-white"
+                    >
+                        Resume
+                    </button>
+                    <button
+                        onClick={() => handleDiscard(record)}
+                        className="ml-2 px-2 py-0.5 text-xs rounded bg-gray-700 hover:bg-gray-600 text-gray-200"
+                    >
+                        Discard
+                    </button>
+                </div>
+            ))}
+
             {/* Error message */}
             {error && (
                 <div className="px-4 py-2 bg-red-500/20 text-red-400 text-sm">
@@ -268,7 +524,7 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
                                 }}
                             >
                                 <span className="text-lg mr-3">
-                                    {entry.is_dir ? '📂' : '📄'}
+                                    {entry.is_symlink ? '🔗' : entry.is_dir ? '📂' : '📄'}
                                 </span>
                                 <div className="flex-1 min-w-0">
                                     <div className="text-sm text-gray-200 truncate">
@@ -281,18 +537,47 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
                                 </div>
                                 {/* Action buttons */}
                                 <div className="flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
-                                    {!entry.is_dir && (
-                                        <button
-                                            onClick={(e) => {
-                                                e.stopPropagation();
-                                                handleDownload(entry);
-                                            }}
-                                            className="px-2 py-1 text-xs text-cyan-400 hover:bg-cyan-500/20 rounded"
-                                            title="Download"
-                                        >
-                                            📥
-                                        </button>
-                                    )}
+                                    <button
+                                        onClick={(e) => {
+                                            e.stopPropagation();
+                                            handleDownload(entry);
+                                        }}
+                                        disabled={entry.is_dir && !!folderTransfer}
+                                        className="px-2 py-1 text-xs text-cyan-400 hover:bg-cyan-500/20 rounded disabled:opacity-50"
+                                        title={entry.is_dir ? 'Download folder' : 'Download'}
+                                    >
+                                        📥
+                                    </button>
+                                    <button
+                                        onClick={(e) => {
+                                            e.stopPropagation();
+                                            handleRename(entry);
+                                        }}
+                                        className="px-2 py-1 text-xs text-gray-300 hover:bg-white/10 rounded"
+                                        title="Rename / move"
+                                    >
+                                        ✏️
+                                    </button>
+                                    <button
+                                        onClick={(e) => {
+                                            e.stopPropagation();
+                                            handleChmod(entry);
+                                        }}
+                                        className="px-2 py-1 text-xs text-gray-300 hover:bg-white/10 rounded"
+                                        title="Permissions"
+                                    >
+                                        🔒
+                                    </button>
+                                    <button
+                                        onClick={(e) => {
+                                            e.stopPropagation();
+                                            handleDetails(entry);
+                                        }}
+                                        className="px-2 py-1 text-xs text-gray-300 hover:bg-white/10 rounded"
+                                        title="Details"
+                                    >
+                                        ℹ️
+                                    </button>
                                     <button
                                         onClick={(e) => {
                                             e.stopPropagation();
@@ -310,6 +595,9 @@ export const SftpExplorer: React.FC<SftpExplorerProps> = ({
                 )}
             </div>
 
+            {/* Transfer queue */}
+            <TransferQueuePanel sessionId={sessionId} onJobCompleted={handleJobCompleted} />
+
             {/* Status bar */}
             <div className="px-4 py-2 border-t border-gray-800 text-xs text-gray-500">
                 {isConnected ? (
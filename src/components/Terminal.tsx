@@ -5,10 +5,12 @@ import { WebLinksAddon } from '@xterm/addon-web-links';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import '@xterm/xterm/css/xterm.css';
-import { ConnectionType, SshConfig, Connection } from '../types/connection';
+import { ConnectionType, SshConfig, Connection, PortForwardConfig, ReconnectPolicy, ReconnectingPayload, TelnetConfig, SerialConfig } from '../types/connection';
 import { MfaPromptPayload } from '../types/mfa';
+import { HostKeyDecision, HostKeyPromptPayload } from '../types/hostkey';
 import { SftpExplorer } from './SftpExplorer';
 import { MfaDialog } from './MfaDialog';
+import { HostKeyDialog } from './HostKeyDialog';
 
 // 模块级的 Set，用于追踪已创建后端 session 的终端 ID
 // 这可以防止 React StrictMode 双重渲染导致的重复创建
@@ -17,20 +19,29 @@ const createdSessionIds = new Set<string>();
 // 追踪正在进行的 cleanup 操作，用于处理 StrictMode 快速卸载/重新挂载
 const pendingCleanups = new Map<string, ReturnType<typeof setTimeout>>();
 
-
 interface TerminalProps {
     terminalId: string;
+    connectionId?: string;
     connectionType: ConnectionType;
     shell?: string;
     sshConfig?: SshConfig;
+    portForwards?: PortForwardConfig[];
+    reconnect?: ReconnectPolicy | null;
+    telnetConfig?: TelnetConfig;
+    serialConfig?: SerialConfig;
     onClose?: () => void;
 }
 
 export const Terminal: React.FC<TerminalProps> = ({
     terminalId,
+    connectionId,
     connectionType,
     shell = '/bin/zsh',
     sshConfig,
+    portForwards,
+    reconnect,
+    telnetConfig,
+    serialConfig,
     onClose
 }) => {
     const terminalRef = useRef<HTMLDivElement>(null);
@@ -42,6 +53,8 @@ export const Terminal: React.FC<TerminalProps> = ({
     const [showSftp, setShowSftp] = useState(false);
     // MFA 状态
     const [mfaPrompt, setMfaPrompt] = useState<MfaPromptPayload | null>(null);
+    // 主机密钥确认状态
+    const [hostKeyPrompt, setHostKeyPrompt] = useState<HostKeyPromptPayload | null>(null);
 
 
     // 同步最新的 onClose 回调
@@ -126,17 +139,52 @@ export const Terminal: React.FC<TerminalProps> = ({
                         return;
                     }
 
-                    // 创建 SSH 连接
+                    // 创建 SSH 连接（同一连接 ID 的终端与 SFTP 共享底层 SSH 连接）
                     const connection: Connection = {
-                        id: terminalId,
+                        id: connectionId ?? terminalId,
                         name: 'SSH Session',
                         connection_type: connectionType,
                         ssh_config: sshConfig,
+                        port_forwards: portForwards,
+                        reconnect,
                     };
 
                     console.log('[Frontend] Creating SSH terminal with config:', JSON.stringify(connection, null, 2));
-                    await invoke('create_ssh_terminal', { config: connection });
+                    await invoke('create_ssh_terminal', { config: connection, terminalId });
                     console.log('[Frontend] SSH terminal created successfully');
+                } else if (connectionType === ConnectionType.Telnet) {
+                    if (!telnetConfig) {
+                        console.error('Telnet config is required for Telnet connection');
+                        term.write('\r\n\x1b[1;31mError: Telnet config is missing\x1b[0m\r\n');
+                        return;
+                    }
+
+                    const connection: Connection = {
+                        id: connectionId ?? terminalId,
+                        name: 'Telnet Session',
+                        connection_type: connectionType,
+                        telnet_config: telnetConfig,
+                    };
+                    await invoke('create_telnet_terminal', {
+                        config: connection,
+                        terminalId,
+                        cols: term.cols,
+                        rows: term.rows,
+                    });
+                } else if (connectionType === ConnectionType.Serial) {
+                    if (!serialConfig) {
+                        console.error('Serial config is required for Serial connection');
+                        term.write('\r\n\x1b[1;31mError: Serial config is missing\x1b[0m\r\n');
+                        return;
+                    }
+
+                    const connection: Connection = {
+                        id: connectionId ?? terminalId,
+                        name: 'Serial Session',
+                        connection_type: connectionType,
+                        serial_config: serialConfig,
+                    };
+                    await invoke('create_serial_terminal', { config: connection, terminalId });
                 } else {
                     // 创建本地终端
                     console.log(`[Terminal] Creating backend session for ${terminalId}`);
@@ -178,13 +226,34 @@ export const Terminal: React.FC<TerminalProps> = ({
             unlistenExit = unlisten;
         });
 
+        // 监听 SSH 断线重连事件
+        let unlistenReconnecting: UnlistenFn;
+        let unlistenReconnected: UnlistenFn;
+        if (connectionType === ConnectionType.SSH) {
+            listen<ReconnectingPayload>(`terminal-reconnecting-${terminalId}`, (event) => {
+                const { attempt, max_attempts, delay_ms, last_error } = event.payload;
+                if (last_error) {
+                    term.write(`\r\n\x1b[1;31m${last_error}\x1b[0m`);
+                }
+                term.write(`\r\n\x1b[1;33mConnection lost, reconnecting in ${(delay_ms / 1000).toFixed(1)}s (attempt ${attempt}/${max_attempts})...\x1b[0m\r\n`);
+            }).then((unlisten) => {
+                unlistenReconnecting = unlisten;
+            });
+            listen(`terminal-reconnected-${terminalId}`, () => {
+                term.write('\r\n\x1b[1;32mReconnected\x1b[0m\r\n');
+            }).then((unlisten) => {
+                unlistenReconnected = unlisten;
+            });
+        }
+
         // 监听 SSH MFA 提示事件
         let unlistenMfa: UnlistenFn;
         if (connectionType === ConnectionType.SSH) {
             listen<MfaPromptPayload>('ssh-mfa-prompt', (event) => {
                 console.log('[Terminal] Received MFA prompt:', event.payload);
-                // 只处理当前终端的 MFA 请求
-                if (event.payload.terminal_id === terminalId) {
+                // 只处理当前终端（及其 SFTP 面板）的 MFA 请求
+                const id = event.payload.terminal_id;
+                if (id === terminalId || id === `sftp-${terminalId}`) {
                     setMfaPrompt(event.payload);
                 }
             }).then((unlisten) => {
@@ -192,6 +261,20 @@ export const Terminal: React.FC<TerminalProps> = ({
             });
         }
 
+        // 监听 SSH 主机密钥确认事件（终端与其 SFTP 面板共用）
+        let unlistenHostKey: UnlistenFn;
+        if (connectionType === ConnectionType.SSH) {
+            listen<HostKeyPromptPayload>('ssh-hostkey-prompt', (event) => {
+                console.log('[Terminal] Received host key prompt:', event.payload);
+                const id = event.payload.terminal_id;
+                if (id === terminalId || id === `sftp-${terminalId}`) {
+                    setHostKeyPrompt(event.payload);
+                }
+            }).then((unlisten) => {
+                unlistenHostKey = unlisten;
+            });
+        }
+
         // 监听用户输入
         const disposable = term.onData((data) => {
             if (!isReadyRef.current) {
@@ -199,13 +282,9 @@ export const Terminal: React.FC<TerminalProps> = ({
                 return;
             }
 
-            const writeCommand = connectionType === ConnectionType.SSH
-                ? 'write_to_ssh_terminal'
-                : 'write_to_terminal';
-
             console.log(`[Terminal] Sending input to ${connectionType} (${terminalId}): len=${data.length}`);
 
-            invoke(writeCommand, {
+            invoke('write_to_terminal', {
                 id: terminalId,
                 data
             }).catch((error) => {
@@ -218,11 +297,7 @@ export const Terminal: React.FC<TerminalProps> = ({
             if (fitAddonRef.current && xtermRef.current) {
                 fitAddon.fit();
 
-                const resizeCommand = connectionType === ConnectionType.SSH
-                    ? 'resize_ssh_terminal'
-                    : 'resize_terminal';
-
-                invoke(resizeCommand, {
+                invoke('resize_terminal', {
                     id: terminalId,
                     rows: xtermRef.current.rows,
                     cols: xtermRef.current.cols
@@ -237,7 +312,10 @@ export const Terminal: React.FC<TerminalProps> = ({
         return () => {
             if (unlistenOutput) unlistenOutput();
             if (unlistenExit) unlistenExit();
+            if (unlistenReconnecting) unlistenReconnecting();
+            if (unlistenReconnected) unlistenReconnected();
             if (unlistenMfa) unlistenMfa();
+            if (unlistenHostKey) unlistenHostKey();
             disposable.dispose();
             window.removeEventListener('resize', handleResize);
             term.dispose();
@@ -245,21 +323,17 @@ export const Terminal: React.FC<TerminalProps> = ({
 
             // 延迟关闭后端 session，以处理 React StrictMode 的快速卸载/重新挂载
             // 如果组件在延迟期间重新挂载，createSession 会取消这个 cleanup
-            const closeCommand = connectionType === ConnectionType.SSH
-                ? 'close_ssh_terminal'
-                : 'close_terminal';
-
             const cleanupTimeout = setTimeout(() => {
                 pendingCleanups.delete(terminalId);
                 createdSessionIds.delete(terminalId);
                 console.log(`[Terminal] Closing backend session for ${terminalId}`);
-                invoke(closeCommand, { id: terminalId }).catch(console.error);
+                invoke('close_terminal', { id: terminalId }).catch(console.error);
             }, 100); // 100ms 延迟，足够 StrictMode 重新挂载
 
             pendingCleanups.set(terminalId, cleanupTimeout);
         };
 
-    }, [terminalId, shell, connectionType, sshConfig]);
+    }, [terminalId, shell, connectionType, sshConfig, telnetConfig, serialConfig]);
 
     // 当 SFTP 面板切换时重新计算终端尺寸
     useEffect(() => {
@@ -277,7 +351,7 @@ export const Terminal: React.FC<TerminalProps> = ({
         console.log('[Terminal] Submitting MFA response:', responses.length, 'items');
         try {
             await invoke('submit_ssh_mfa_response', {
-                terminalId,
+                terminalId: mfaPrompt?.terminal_id ?? terminalId,
                 responses,
             });
             setMfaPrompt(null);
@@ -291,13 +365,28 @@ export const Terminal: React.FC<TerminalProps> = ({
     const handleMfaCancel = async () => {
         console.log('[Terminal] Cancelling MFA');
         try {
-            await invoke('cancel_ssh_mfa', { terminalId });
+            await invoke('cancel_ssh_mfa', { terminalId: mfaPrompt?.terminal_id ?? terminalId });
         } catch (error) {
             console.error('[Terminal] Failed to cancel MFA:', error);
         }
         setMfaPrompt(null);
     };
 
+    // 主机密钥确认处理
+    const handleHostKeyDecision = async (decision: HostKeyDecision) => {
+        if (!hostKeyPrompt) return;
+        console.log('[Terminal] Submitting host key decision:', decision);
+        try {
+            await invoke('submit_ssh_hostkey_response', {
+                terminalId: hostKeyPrompt.terminal_id,
+                decision,
+            });
+        } catch (error) {
+            console.error('[Terminal] Failed to submit host key decision:', error);
+        }
+        setHostKeyPrompt(null);
+    };
+
     return (
         <div className="relative w-full h-full flex bg-[#0D0D11]">
             {/* 终端容器 */}
@@ -317,6 +406,20 @@ export const Terminal: React.FC<TerminalProps> = ({
                         </button>
                     </div>
                 )}
+                {/* 串口连接时显示 BREAK 按钮 */}
+                {connectionType === ConnectionType.Serial && (
+                    <div className="flex items-center px-3 py-1.5 bg-[#16161B] border-b border-gray-800">
+                        <button
+                            onClick={() => invoke('send_serial_break', { id: terminalId }).catch((error) => {
+                                console.error('[Terminal] Failed to send BREAK:', error);
+                            })}
+                            className="px-3 py-1 text-xs rounded-md transition-colors bg-gray-800 hover:bg-gray-700 text-gray-400 hover:text-white"
+                            title="Send a serial BREAK signal"
+                        >
+                            Send BREAK
+                        </button>
+                    </div>
+                )}
                 <div
                     ref={terminalRef}
                     className="flex-1 p-2"
@@ -330,7 +433,7 @@ export const Terminal: React.FC<TerminalProps> = ({
                     <SftpExplorer
                         sessionId={`sftp-${terminalId}`}
                         connection={{
-                            id: `sftp-${terminalId}`,
+                            id: connectionId ?? terminalId,
                             name: 'SFTP Session',
                             connection_type: connectionType,
                             ssh_config: sshConfig,
@@ -347,6 +450,12 @@ export const Terminal: React.FC<TerminalProps> = ({
                 onSubmit={handleMfaSubmit}
                 onCancel={handleMfaCancel}
             />
+
+            {/* 主机密钥确认对话框 */}
+            <HostKeyDialog
+                promptData={hostKeyPrompt}
+                onDecision={handleHostKeyDecision}
+            />
         </div>
     );
 };
@@ -0,0 +1,133 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { QueuedTransfer, TransferProgress } from '../types/sftp';
+
+interface TransferQueuePanelProps {
+    sessionId: string;
+    /** 任务完成时回调（如上传完成后刷新目录） */
+    onJobCompleted?: (job: QueuedTransfer) => void;
+}
+
+const formatSize = (size: number): string => {
+    if (size < 1024) return `${size} B`;
+    if (size < 1024 * 1024) return `${(size / 1024).toFixed(1)} KB`;
+    if (size < 1024 * 1024 * 1024) return `${(size / 1024 / 1024).toFixed(1)} MB`;
+    return `${(size / 1024 / 1024 / 1024).toFixed(1)} GB`;
+};
+
+const formatEta = (secs: number): string => {
+    if (secs < 60) return `${secs}s`;
+    if (secs < 3600) return `${Math.floor(secs / 60)}m ${secs % 60}s`;
+    return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+};
+
+const statusColor: Record<QueuedTransfer['status'], string> = {
+    Queued: 'text-gray-400',
+    Running: 'text-blue-400',
+    Paused: 'text-yellow-400',
+    Completed: 'text-green-400',
+    Failed: 'text-red-400',
+    Cancelled: 'text-gray-500',
+};
+
+/** 当前文件会话的传输队列 */
+export const TransferQueuePanel: React.FC<TransferQueuePanelProps> = ({ sessionId, onJobCompleted }) => {
+    const [jobs, setJobs] = useState<QueuedTransfer[]>([]);
+    const [progress, setProgress] = useState<Record<string, TransferProgress>>({});
+
+    useEffect(() => {
+        invoke<QueuedTransfer[]>('transfer_list')
+            .then(all => setJobs(all.filter(job => job.session_id === sessionId)))
+            .catch(console.error);
+
+        const unlistenJob = listen<QueuedTransfer>('transfer-job-updated', (event) => {
+            const job = event.payload;
+            if (job.session_id !== sessionId) return;
+            setJobs(prev => {
+                const index = prev.findIndex(j => j.id === job.id);
+                if (index === -1) return [...prev, job];
+                const next = [...prev];
+                next[index] = job;
+                return next;
+            });
+            if (job.status === 'Completed') onJobCompleted?.(job);
+        });
+        const unlistenProgress = listen<TransferProgress>('sftp-transfer-progress', (event) => {
+            setProgress(prev => ({ ...prev, [event.payload.transfer_id]: event.payload }));
+        });
+        return () => {
+            unlistenJob.then(fn => fn());
+            unlistenProgress.then(fn => fn());
+        };
+    }, [sessionId, onJobCompleted]);
+
+    const control = useCallback(async (command: string, id: string) => {
+        try {
+            await invoke(command, { id });
+        } catch (err) {
+            console.error(`${command} failed:`, err);
+        }
+    }, []);
+
+    const clearFinished = useCallback(async () => {
+        try {
+            await invoke('transfer_clear_finished');
+            setJobs(prev => prev.filter(job => ['Queued', 'Running', 'Paused'].includes(job.status)));
+        } catch (err) {
+            console.error('Failed to clear transfers:', err);
+        }
+    }, []);
+
+    if (jobs.length === 0) return null;
+
+    return (
+        <div className="border-t border-gray-800 bg-[#16161B] max-h-48 overflow-y-auto">
+            <div className="flex items-center justify-between px-4 py-1 text-xs text-gray-400">
+                <span>Transfers</span>
+                <button onClick={clearFinished} className="hover:text-white">Clear finished</button>
+            </div>
+            {jobs.map(job => {
+                const name = (job.direction === 'Download' ? job.remote_path : job.local_path).split(/[\\/]/).pop();
+                const live = progress[job.id];
+                const bytes = job.status === 'Running' && live ? live.bytes : job.bytes;
+                const total = job.total ?? live?.total;
+                return (
+                    <div key={job.id} className="px-4 py-1 text-xs">
+                        <div className="flex items-center gap-2">
+                            <span>{job.direction === 'Download' ? '📥' : '📤'}</span>
+                            <span className="flex-1 truncate text-gray-300" title={job.error ?? undefined}>{name}</span>
+                            <span className={statusColor[job.status]}>{job.status}</span>
+                            {job.status === 'Running' && live && (
+                                <span className="text-gray-400 whitespace-nowrap">
+                                    {formatSize(live.rate)}/s
+                                    {live.eta_secs !== undefined && live.eta_secs !== null && ` · ${formatEta(live.eta_secs)}`}
+                                </span>
+                            )}
+                            {(job.status === 'Queued' || job.status === 'Running') && (
+                                <button onClick={() => control('transfer_pause', job.id)} className="text-gray-400 hover:text-white" title="Pause">⏸</button>
+                            )}
+                            {job.status === 'Paused' && (
+                                <button onClick={() => control('transfer_resume', job.id)} className="text-gray-400 hover:text-white" title="Resume">▶</button>
+                            )}
+                            {(job.status === 'Failed' || job.status === 'Cancelled') && (
+                                <button onClick={() => control('transfer_retry', job.id)} className="text-gray-400 hover:text-white" title="Retry">↻</button>
+                            )}
+                            {!['Completed', 'Failed', 'Cancelled'].includes(job.status) && (
+                                <button onClick={() => control('transfer_cancel', job.id)} className="text-gray-400 hover:text-white" title="Cancel">✕</button>
+                            )}
+                        </div>
+                        {total ? (
+                            <div className="mt-1 h-1 bg-gray-700 rounded">
+                                <div
+                                    className="h-1 bg-blue-500 rounded"
+                                    style={{ width: `${Math.min(100, (bytes / total) * 100)}%` }}
+                                />
+                            </div>
+                        ) : null}
+                    </div>
+                );
+            })}
+        </div>
+    );
+};
@@ -0,0 +1,129 @@
+import { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { VaultStatus } from '../types/vault';
+
+interface VaultDialogProps {
+    /** 是否显示对话框 */
+    isOpen: boolean;
+    /** 当前保险库状态，未初始化时以输入的密码创建保险库 */
+    status: VaultStatus | null;
+    /** 解锁成功回调 */
+    onUnlocked: (status: VaultStatus) => void;
+    /** 跳过解锁（保存了凭据的连接将无法使用） */
+    onSkip: () => void;
+}
+
+/**
+ * 凭据保险库解锁对话框
+ * 每次启动应用后输入一次主密码；首次使用时设置主密码并迁移已有的明文凭据
+ */
+export const VaultDialog: React.FC<VaultDialogProps> = ({
+    isOpen,
+    status,
+    onUnlocked,
+    onSkip,
+}) => {
+    const [password, setPassword] = useState('');
+    const [confirmPassword, setConfirmPassword] = useState('');
+    const [error, setError] = useState<string | null>(null);
+    const [busy, setBusy] = useState(false);
+    const inputRef = useRef<HTMLInputElement>(null);
+
+    const creating = !!status && !status.initialized;
+
+    useEffect(() => {
+        if (isOpen) {
+            setPassword('');
+            setConfirmPassword('');
+            setError(null);
+            setTimeout(() => inputRef.current?.focus(), 100);
+        }
+    }, [isOpen]);
+
+    if (!isOpen || !status) {
+        return null;
+    }
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (creating && password !== confirmPassword) {
+            setError('Passwords do not match');
+            return;
+        }
+        setBusy(true);
+        setError(null);
+        try {
+            const next = await invoke<VaultStatus>('unlock_vault', { masterPassword: password });
+            onUnlocked(next);
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <div
+            className="fixed inset-0 z-50 flex items-center justify-center bg-black/60 backdrop-blur-sm"
+            onKeyDown={(e) => e.key === 'Escape' && onSkip()}
+        >
+            <div className="bg-[#1a1a24] rounded-xl shadow-2xl border border-gray-700 w-full max-w-md mx-4 overflow-hidden">
+                <div className="px-6 py-4 border-b border-gray-700 bg-gradient-to-r from-cyan-600/20 to-blue-600/20">
+                    <div className="flex items-center space-x-3">
+                        <div className="w-10 h-10 rounded-full bg-cyan-500/20 flex items-center justify-center">
+                            <span className="text-xl">🗝️</span>
+                        </div>
+                        <div>
+                            <h2 className="text-lg font-semibold text-white">
+                                {creating ? 'Create Credential Vault' : 'Unlock Credential Vault'}
+                            </h2>
+                            <p className="text-xs text-gray-400">
+                                {creating
+                                    ? 'Choose a master password. Saved passwords will be encrypted with it.'
+                                    : `${status.secrets} saved credential${status.secrets === 1 ? '' : 's'}`}
+                            </p>
+                        </div>
+                    </div>
+                </div>
+
+                <form onSubmit={handleSubmit} className="px-6 py-4 space-y-3">
+                    <input
+                        ref={inputRef}
+                        type="password"
+                        value={password}
+                        onChange={(e) => setPassword(e.target.value)}
+                        placeholder="Master password"
+                        className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white text-sm focus:outline-none focus:border-cyan-500"
+                    />
+                    {creating && (
+                        <input
+                            type="password"
+                            value={confirmPassword}
+                            onChange={(e) => setConfirmPassword(e.target.value)}
+                            placeholder="Confirm master password"
+                            className="w-full px-3 py-2 bg-[#0D0D11] border border-gray-700 rounded-lg text-white text-sm focus:outline-none focus:border-cyan-500"
+                        />
+                    )}
+                    {error && <p className="text-xs text-red-400">{error}</p>}
+
+                    <div className="flex justify-end space-x-2 pt-2">
+                        <button
+                            type="button"
+                            onClick={onSkip}
+                            className="px-4 py-2 text-sm text-gray-400 hover:text-white transition-colors"
+                        >
+                            Later
+                        </button>
+                        <button
+                            type="submit"
+                            disabled={busy || !password}
+                            className="px-4 py-2 text-sm bg-cyan-600 hover:bg-cyan-500 disabled:opacity-50 text-white rounded-lg transition-colors"
+                        >
+                            {busy ? 'Unlocking…' : creating ? 'Create' : 'Unlock'}
+                        </button>
+                    </div>
+                </form>
+            </div>
+        </div>
+    );
+};
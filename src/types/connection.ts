@@ -4,6 +4,7 @@ export enum ConnectionType {
     SSH = 'Ssh',      // 注意：值是 'Ssh'（首字母大写），与 Rust 枚举匹配
     Telnet = 'Telnet',
     Serial = 'Serial',
+    Ftp = 'Ftp',
 }
 
 export interface SshConfig {
@@ -11,17 +12,205 @@ export interface SshConfig {
     port: number;
     username: string;
     auth: SshAuth;
+    /** 跳板机链路（ProxyJump），按连接顺序排列 */
+    jump_hosts?: JumpHost[];
+    /** 算法、心跳与超时等高级设置 */
+    advanced?: SshAdvanced;
+}
+
+/**
+ * 算法预设
+ * - Compatible：兼容旧服务器，包含 SHA1 密钥交换算法
+ * - Modern：只使用现代算法，排除所有基于 SHA1 的算法
+ */
+export type AlgorithmPreset = 'Compatible' | 'Modern';
+
+/** SSH 高级设置，算法列表为空时使用预设 */
+export interface SshAdvanced {
+    preset: AlgorithmPreset;
+    kex: string[];
+    ciphers: string[];
+    macs: string[];
+    host_key_algorithms: string[];
+    compression: boolean;
+    /** 心跳间隔（秒），0 表示不发送 */
+    keepalive_interval_secs: number;
+    /** 连续多少次心跳无响应后断开 */
+    keepalive_max: number;
+    /** 连接超时（秒），0 表示不限制 */
+    connect_timeout_secs: number;
+}
+
+/** 默认高级设置（与后端 SshAdvanced::default 一致） */
+export const DEFAULT_SSH_ADVANCED: SshAdvanced = {
+    preset: 'Compatible',
+    kex: [],
+    ciphers: [],
+    macs: [],
+    host_key_algorithms: [],
+    compression: false,
+    keepalive_interval_secs: 30,
+    keepalive_max: 6,
+    connect_timeout_secs: 30,
+};
+
+/** Telnet 连接配置 */
+export interface TelnetConfig {
+    host: string;
+    port: number;
+    /** TTYPE 协商时上报的终端类型，默认 xterm-256color */
+    terminal_type?: string;
+    /** TCP 连接超时（秒），0 表示不限制，默认 10 */
+    connect_timeout_secs?: number;
+}
+
+export type SerialParity = 'None' | 'Odd' | 'Even';
+
+/** 串口流控：Software 为 XON/XOFF，Hardware 为 RTS/CTS */
+export type SerialFlowControl = 'None' | 'Software' | 'Hardware';
+
+/** 串口连接配置 */
+export interface SerialConfig {
+    /** 设备路径，如 /dev/ttyUSB0、COM3 */
+    device: string;
+    baud_rate: number;
+    /** 数据位（5 - 8） */
+    data_bits: number;
+    parity: SerialParity;
+    /** 停止位（1 或 2） */
+    stop_bits: number;
+    flow_control: SerialFlowControl;
+}
+
+/** list_serial_ports 返回的串口信息 */
+export interface SerialPortEntry {
+    name: string;
+    port_type: string;
+    description: string | null;
+}
+
+/**
+ * FTP 加密方式
+ * - None：明文 FTP
+ * - Explicit：FTPS 显式加密（AUTH TLS），通常使用 21 端口
+ * - Implicit：FTPS 隐式加密，通常使用 990 端口
+ */
+export type FtpSecurity = 'None' | 'Explicit' | 'Implicit';
+
+/** FTP / FTPS 连接配置 */
+export interface FtpConfig {
+    host: string;
+    port: number;
+    /** 为空时使用匿名登录 */
+    username: string;
+    password: string;
+    security: FtpSecurity;
+    /** 被动模式（PASV），关闭时使用主动模式（PORT） */
+    passive: boolean;
+}
+
+/** 跳板机配置，每一跳使用独立的认证方式 */
+export interface JumpHost {
+    host: string;
+    port: number;
+    username: string;
+    auth: SshAuth;
 }
 
 export type SshAuth =
     | { Password: string }
-    | { PublicKey: { private_key_path: string; passphrase?: string } };
+    | { PublicKey: { private_key_path: string; passphrase?: string } }
+    | { Agent: { socket_path?: string } };
+
+/**
+ * 端口转发类型
+ * - Local（-L）：本地 bind_host:bind_port → 经 SSH 主机访问 target_host:target_port
+ * - Remote（-R）：SSH 主机上的 bind_host:bind_port → 本机访问 target_host:target_port
+ * - Dynamic（-D）：本地 bind_host:bind_port 上的 SOCKS5 代理，目标由客户端指定
+ */
+export type ForwardKind = 'Local' | 'Remote' | 'Dynamic';
+
+/** 端口转发配置 */
+export interface PortForwardConfig {
+    id: string;
+    kind?: ForwardKind;
+    bind_host: string;
+    bind_port: number;
+    /** Dynamic 类型忽略目标地址 */
+    target_host?: string;
+    target_port?: number;
+}
+
+/** 运行中的端口转发状态（ssh_forward_list 返回） */
+export interface ForwardStatus extends PortForwardConfig {
+    bytes_sent: number;
+    bytes_received: number;
+    total_connections: number;
+    active_connections: number;
+}
+
+/** 断线自动重连策略：指数退避 + 随机抖动 */
+export interface ReconnectPolicy {
+    max_attempts: number;
+    initial_delay_ms: number;
+    max_delay_ms: number;
+    /** 抖动比例（0.0 - 1.0） */
+    jitter: number;
+}
+
+/** 默认重连策略（与后端 ReconnectPolicy::default 一致） */
+export const DEFAULT_RECONNECT_POLICY: ReconnectPolicy = {
+    max_attempts: 5,
+    initial_delay_ms: 1000,
+    max_delay_ms: 30000,
+    jitter: 0.2,
+};
+
+/** terminal-reconnecting-{id} 事件 payload */
+export interface ReconnectingPayload {
+    attempt: number;
+    max_attempts: number;
+    delay_ms: number;
+    last_error: string | null;
+}
 
 export interface Connection {
     id: string;
     name: string;
     connection_type: ConnectionType;
     ssh_config?: SshConfig;
+    /** 连接建立后自动启动的端口转发 */
+    port_forwards?: PortForwardConfig[];
+    /** 断线自动重连策略，为空时不重连 */
+    reconnect?: ReconnectPolicy | null;
+    telnet_config?: TelnetConfig;
+    serial_config?: SerialConfig;
+    ftp_config?: FtpConfig;
+    /** 所属分组，为空时位于根目录 */
+    group_id?: string | null;
+    tags?: string[];
+    notes?: string;
+    /** 颜色标记（如 #22d3ee） */
+    color?: string | null;
+    /** 收藏时间（Unix 秒），为空表示未收藏 */
+    favorited_at?: number | null;
+    /** 最近一次连接的时间（Unix 秒） */
+    last_used_at?: number | null;
+}
+
+/** 连接分组（文件夹），通过 parent_id 组成层级 */
+export interface ConnectionGroup {
+    id: string;
+    name: string;
+    parent_id?: string | null;
+}
+
+/** search_connections 返回的一条结果 */
+export interface ConnectionMatch {
+    connection: Connection;
+    /** 从根到所属分组的名称 */
+    group_path: string[];
+    score: number;
 }
 
 // 辅助函数：创建密码认证的 SshAuth
@@ -42,6 +231,15 @@ export function createPublicKeyAuth(
     };
 }
 
+// 辅助函数：创建 SSH agent 认证的 SshAuth（socketPath 为空时使用 SSH_AUTH_SOCK）
+export function createAgentAuth(socketPath?: string): SshAuth {
+    return {
+        Agent: {
+            socket_path: socketPath,
+        },
+    };
+}
+
 // 辅助函数：创建本地连接
 export function createLocalConnection(name: string): Connection {
     return {
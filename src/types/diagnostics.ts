@@ -0,0 +1,43 @@
+/**
+ * SSH 连接诊断类型定义
+ * 对应后端 test_ssh_connection 返回的报告（必须与后端结构一致）
+ */
+
+/** 协商得到的算法 */
+export interface NegotiatedAlgorithms {
+    kex: string;
+    host_key: string;
+    cipher: string;
+    client_mac: string;
+    server_mac: string;
+}
+
+/** 主机密钥在 known_hosts 中的状态 */
+export type HostKeyStatus =
+    | { status: 'Known'; path: string }
+    | { status: 'Changed'; path: string; line: number }
+    | { status: 'Revoked'; path: string; line: number }
+    | { status: 'Unknown' };
+
+/** 连接诊断报告，失败时 error 为第一个失败步骤的原因 */
+export interface DiagnosticReport {
+    host: string;
+    port: number;
+    jump_hosts: string[];
+    resolved_addresses: string[];
+    dns_ms: number | null;
+    tcp_connect_ms: number | null;
+    handshake_ms: number | null;
+    /** 服务器标识串，如 "SSH-2.0-OpenSSH_9.6" */
+    server_banner: string | null;
+    algorithms: NegotiatedAlgorithms | null;
+    host_key_type: string | null;
+    /** SHA256 指纹，如 "SHA256:abc..." */
+    host_key_fingerprint: string | null;
+    host_key_status: HostKeyStatus | null;
+    auth_banner: string | null;
+    auth_methods: string[];
+    auth_succeeded: boolean;
+    auth_ms: number | null;
+    error: string | null;
+}
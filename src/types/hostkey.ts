@@ -0,0 +1,19 @@
+/**
+ * SSH 主机密钥确认类型定义
+ * 用于首次连接未知主机时的信任提示
+ */
+
+/** 后端发送的主机密钥确认事件 payload */
+export interface HostKeyPromptPayload {
+    /** 关联的终端（或 SFTP 会话）ID */
+    terminal_id: string;
+    host: string;
+    port: number;
+    /** 密钥算法，如 "ssh-ed25519" */
+    key_type: string;
+    /** SHA256 指纹，如 "SHA256:abc..." */
+    fingerprint: string;
+}
+
+/** 用户对未知主机的决定（必须与后端 Rust 枚举值完全匹配） */
+export type HostKeyDecision = 'Reject' | 'AcceptOnce' | 'AcceptAndSave';
@@ -0,0 +1,62 @@
+// 连接导入相关类型定义
+import { Connection } from './connection';
+
+// 导入来源
+export type ImportSource = 'SshConfig' | 'Putty' | 'MobaXterm' | 'Xshell' | 'Termius';
+
+// 导入预览中的一个条目
+export interface ImportEntry {
+    // 来源中的名称（如 Host 别名），在同一预览中唯一，导入时按此名称选择
+    source_name: string;
+    connection: Connection;
+    // 无法映射的选项，导入后会被丢弃
+    unsupported: string[];
+    warnings: string[];
+    // 已存在同名或同一目标的连接
+    duplicate: boolean;
+}
+
+// 导入预览（dry-run）
+export interface ImportPreview {
+    entries: ImportEntry[];
+    warnings: string[];
+}
+
+// 连接导出包格式
+export type BundleFormat = 'Json' | 'Toml';
+
+// 导出选项（export_connections）
+export interface ExportOptions {
+    format?: BundleFormat;
+    // 去掉密码与私钥口令，默认 true
+    strip_secrets?: boolean;
+    // 只导出指定连接
+    ids?: string[];
+}
+
+// 导入选项（import_connections）
+export interface BundleImportOptions {
+    format?: BundleFormat;
+    mode?: 'Merge' | 'Replace';
+    // 为导入的连接生成新 ID
+    rekey?: boolean;
+    // 合并时覆盖同 ID 的已有连接
+    overwrite?: boolean;
+    dry_run?: boolean;
+}
+
+// 同 ID 冲突
+export interface BundleConflict {
+    id: string;
+    name: string;
+    existing_name: string;
+    action: 'Skipped' | 'Overwritten';
+}
+
+// 导入报告
+export interface BundleImportReport {
+    imported: Connection[];
+    removed: number;
+    conflicts: BundleConflict[];
+    dry_run: boolean;
+}
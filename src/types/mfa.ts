@@ -15,6 +15,8 @@ export interface MfaPrompt {
 export interface MfaPromptPayload {
     /** 关联的终端 ID */
     terminal_id: string;
+    /** 发起认证的主机（经过跳板机时用于区分是哪一跳） */
+    host: string;
     /** 认证名称（通常为空或服务器名） */
     name: string;
     /** 认证说明（通常为空或包含额外说明） */
@@ -3,11 +3,29 @@
 export interface FileEntry {
     name: string;
     is_dir: boolean;
+    is_symlink?: boolean;
     size: number;
     permissions?: string;
     modified?: number;
 }
 
+// 文件详细信息（fs_stat_details）
+export interface FileDetails {
+    path: string;
+    name: string;
+    is_dir: boolean;
+    is_symlink: boolean;
+    size: number;
+    permissions?: string;
+    uid?: number;
+    gid?: number;
+    owner?: string;
+    group?: string;
+    accessed?: number;
+    modified?: number;
+    link_target?: string;
+}
+
 export interface SftpState {
     isConnected: boolean;
     currentPath: string;
@@ -15,3 +33,61 @@ export interface SftpState {
     loading: boolean;
     error?: string;
 }
+
+// 传输进度（sftp-transfer-progress 事件）
+export interface TransferProgress {
+    transfer_id: string;
+    bytes: number;
+    total?: number;
+    rate: number;
+    eta_secs?: number;
+    files_done?: number;
+    files_total?: number;
+    done: boolean;
+}
+
+// 未完成的可续传传输（应用重启后仍保留）
+export interface InterruptedTransfer {
+    transfer_id: string;
+    direction: 'Download' | 'Upload';
+    connection_id?: string;
+    remote_path: string;
+    local_path: string;
+    total?: number;
+    updated_at: number;
+}
+
+// 传输队列任务（transfer-job-updated 事件）
+export type JobStatus = 'Queued' | 'Running' | 'Paused' | 'Completed' | 'Failed' | 'Cancelled';
+
+export interface QueuedTransfer {
+    id: string;
+    session_id: string;
+    direction: 'Download' | 'Upload';
+    remote_path: string;
+    local_path: string;
+    connection_id?: string;
+    status: JobStatus;
+    bytes: number;
+    total?: number;
+    error?: string;
+    attempts: number;
+}
+
+// 目录传输选项
+export type SymlinkPolicy = 'Follow' | 'Skip' | 'Copy';
+
+export interface TreeOptions {
+    preserve_times?: boolean;
+    preserve_permissions?: boolean;
+    symlinks?: SymlinkPolicy;
+}
+
+export interface TreeSummary {
+    files: number;
+    dirs: number;
+    links: number;
+    bytes: number;
+    skipped: number;
+    attr_failures: number;
+}
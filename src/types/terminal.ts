@@ -1,18 +1,27 @@
 // 终端会话类型定义
 
-import { ConnectionType, SshConfig } from './connection';
+import { ConnectionType, SshConfig, PortForwardConfig, ReconnectPolicy, TelnetConfig, SerialConfig, FtpConfig } from './connection';
 
 export interface TerminalSession {
     id: string;
     name: string;
     connectionType: ConnectionType;  // 新增
+    connectionId?: string;  // 已保存连接的 ID，同一连接的终端与 SFTP 共享 SSH 连接
     shell?: string;  // 本地终端使用
     sshConfig?: SshConfig;  // SSH 连接使用
+    portForwards?: PortForwardConfig[];  // SSH 连接建立后自动启动的端口转发
+    reconnect?: ReconnectPolicy | null;  // SSH 断线自动重连策略
+    telnetConfig?: TelnetConfig;  // Telnet 连接使用
+    serialConfig?: SerialConfig;  // 串口连接使用
+    ftpConfig?: FtpConfig;  // FTP 文件浏览使用
     createdAt: number;
     isActive: boolean;
 }
 
 
+/** 终端会话状态（terminal_status 返回） */
+export type TerminalStatus = 'Connected' | 'Reconnecting' | 'Closed';
+
 export interface TerminalConfig {
     defaultShell: string;
     fontSize: number;
@@ -0,0 +1,54 @@
+/**
+ * 凭据保险库类型定义
+ * 连接中的密码 / 私钥口令保存为 `vault:<id>` 引用，明文加密存放在保险库中
+ * 保险库不可用时保存明文，以 `vault:` 或 `plain:` 开头的明文加上 `plain:` 前缀
+ */
+
+/** 保险库引用前缀 */
+export const SECRET_REF_PREFIX = 'vault:';
+
+/** 明文转义前缀 */
+export const LITERAL_PREFIX = 'plain:';
+
+/** 后端返回的保险库状态 */
+export interface VaultStatus {
+    /** 是否已设置主密码 */
+    initialized: boolean;
+    unlocked: boolean;
+    /** 已保存的凭据数量 */
+    secrets: number;
+}
+
+/** 字段是否为保险库引用 */
+export function isSecretRef(value?: string | null): boolean {
+    return !!value && value.startsWith(SECRET_REF_PREFIX);
+}
+
+/** 把用户输入的明文转义为保存格式，避免被误认为引用 */
+export function escapeLiteral(value: string): string {
+    return value.startsWith(SECRET_REF_PREFIX) || value.startsWith(LITERAL_PREFIX)
+        ? LITERAL_PREFIX + value
+        : value;
+}
+
+/** 密码框中显示的内容：引用原样显示，明文去掉转义前缀 */
+export function secretInputValue(value: string): string {
+    if (isSecretRef(value) || !value.startsWith(LITERAL_PREFIX)) {
+        return value;
+    }
+    return value.slice(LITERAL_PREFIX.length);
+}
+
+/**
+ * 把密码框的输入转换为保存格式
+ * 在显示为引用的密码框中输入时，用新输入整体替换引用，而不是拼接到引用后面
+ */
+export function editSecret(previous: string, next: string): string {
+    if (!isSecretRef(previous)) {
+        return escapeLiteral(next);
+    }
+    if (next.startsWith(previous)) {
+        return escapeLiteral(next.slice(previous.length));
+    }
+    return previous.startsWith(next) ? '' : escapeLiteral(next);
+}